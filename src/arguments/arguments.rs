@@ -1,23 +1,64 @@
 use crate::constants::constants::*;
+use crate::utilities::topology::Topology;
 use std::env;
 use std::process;
 
 pub struct Arguments {
+    pub command: String,
     pub strategy: String,
     pub decks: String,
     pub number_of_decks: usize,
     pub number_of_hands: usize,
     pub number_of_threads: usize,
+    pub pin_threads: bool,
+    pub target_stderr: Option<f64>,
+    pub index: bool,
+    pub bet_ramp: bool,
+    pub decisions_csv: Option<String>,
+    pub advantage_play: Option<String>,
+    pub replay: Option<String>,
+    pub replay_loop: bool,
+    pub hand_history: Option<String>,
+    pub hand_history_sample: usize,
+    pub checkpoint: Option<String>,
+    pub checkpoint_interval: usize,
+    pub resume: bool,
+    pub user: String,
+    pub progress: String,
+    pub grid: Option<String>,
+    pub sweep_output: String,
+    pub reports: Vec<String>,
+    pub report_output: Option<String>,
 }
 
 impl Default for Arguments {
     fn default() -> Self {
         Arguments {
+            command: COMMAND_SIMULATE.to_string(),
             strategy: STRATEGY_MIMIC.to_string(),
             decks: DECKS_SINGLE_DECK.to_string(),
             number_of_decks: 1,
             number_of_hands: NUMBER_OF_HANDS_DEFAULT,
-            number_of_threads: NUMBER_OF_CORES_DEFAULT,
+            number_of_threads: Topology::detect().available_cpus,
+            pin_threads: false,
+            target_stderr: None,
+            index: false,
+            bet_ramp: false,
+            decisions_csv: None,
+            advantage_play: None,
+            replay: None,
+            replay_loop: false,
+            hand_history: None,
+            hand_history_sample: HAND_HISTORY_SAMPLE_MINIMUM,
+            checkpoint: None,
+            checkpoint_interval: CHECKPOINT_INTERVAL_DEFAULT,
+            resume: false,
+            user: TRAINER_USER_DEFAULT.to_string(),
+            progress: TRAINER_PROGRESS_FILE.to_string(),
+            grid: None,
+            sweep_output: SWEEP_OUTPUT_DEFAULT.to_string(),
+            reports: Vec::new(),
+            report_output: None,
         }
     }
 }
@@ -40,13 +81,38 @@ impl Arguments {
         let mut args: Arguments = Default::default();
         let mut i = 1;
 
+        if argv
+            .get(1)
+            .is_some_and(|command| [COMMAND_PLAY, COMMAND_TRAIN, COMMAND_SWEEP, COMMAND_REPORT].contains(&command.as_str()))
+        {
+            args.command = argv[1].clone();
+            i = 2;
+        }
+
         while i < argv.len() {
             match argv[i].as_str() {
                 "-h" | "--number-of-hands" => {
                     args.number_of_hands = Arguments::parse_usize_arg(argv, &mut i, NUMBER_OF_HANDS_MINIMUM, NUMBER_OF_HANDS_MAXIMUM, "number of hands");
                 }
                 "-t" | "--number-of-threads" => {
-                    args.number_of_threads = Arguments::parse_usize_arg(argv, &mut i, NUMBER_OF_CORES_MINIMUM, NUMBER_OF_CORES_MAXIMUM, "number of threads");
+                    if argv.get(i + 1).is_some_and(|value| value.eq_ignore_ascii_case(NUMBER_OF_THREADS_AUTO)) {
+                        args.number_of_threads = Topology::detect().available_cpus;
+                        i += 1;
+                    } else {
+                        args.number_of_threads = Arguments::parse_usize_arg(argv, &mut i, NUMBER_OF_THREADS_MINIMUM, NUMBER_OF_THREADS_MAXIMUM, "number of threads");
+                    }
+                }
+                "-C" | "--pin-threads" => {
+                    args.pin_threads = true;
+                }
+                "-E" | "--target-stderr" => {
+                    args.target_stderr = Some(Arguments::parse_f64_arg(
+                        argv,
+                        &mut i,
+                        TARGET_STDERR_MINIMUM,
+                        TARGET_STDERR_MAXIMUM,
+                        "target standard error",
+                    ));
                 }
                 "-M" | "--mimic" => {
                     args.strategy = STRATEGY_MIMIC.to_string();
@@ -81,6 +147,66 @@ impl Arguments {
                     args.decks = DECKS_SIX_SHOE.to_string();
                     args.number_of_decks = 6;
                 }
+                "-S" | "--spanish-21" => {
+                    args.decks = DECKS_SPANISH_21.to_string();
+                    args.number_of_decks = 6;
+                }
+                "-I" | "--index" => {
+                    args.index = true;
+                }
+                "-R" | "--bet-ramp" => {
+                    args.bet_ramp = true;
+                }
+                "-D" | "--decisions-csv" => {
+                    args.decisions_csv = Some(Arguments::parse_string_arg(argv, &mut i, "decisions csv file"));
+                }
+                "-A" | "--advantage-play" => {
+                    let technique = Arguments::parse_string_arg(argv, &mut i, "advantage play").to_lowercase();
+                    if ![ADVANTAGE_PLAY_HOLE_CARD, ADVANTAGE_PLAY_WARP, ADVANTAGE_PLAY_FIRST_BASE, ADVANTAGE_PLAY_ACE_SEQUENCING].contains(&technique.as_str()) {
+                        panic!("Invalid advantage play: {}", technique);
+                    }
+                    args.advantage_play = Some(technique);
+                }
+                "-F" | "--replay" => {
+                    args.replay = Some(Arguments::parse_string_arg(argv, &mut i, "replay file"));
+                }
+                "-O" | "--replay-loop" => {
+                    args.replay_loop = true;
+                }
+                "-Y" | "--hand-history" => {
+                    args.hand_history = Some(Arguments::parse_string_arg(argv, &mut i, "hand history file"));
+                }
+                "-K" | "--hand-history-sample" => {
+                    args.hand_history_sample = Arguments::parse_usize_arg(argv, &mut i, HAND_HISTORY_SAMPLE_MINIMUM, HAND_HISTORY_SAMPLE_MAXIMUM, "hand history sample");
+                }
+                "-V" | "--checkpoint" => {
+                    args.checkpoint = Some(Arguments::parse_string_arg(argv, &mut i, "checkpoint file"));
+                }
+                "-Q" | "--checkpoint-interval" => {
+                    args.checkpoint_interval = Arguments::parse_usize_arg(argv, &mut i, CHECKPOINT_INTERVAL_MINIMUM, CHECKPOINT_INTERVAL_MAXIMUM, "checkpoint interval");
+                }
+                "-Z" | "--resume" => {
+                    args.resume = true;
+                }
+                "-U" | "--user" => {
+                    args.user = Arguments::parse_string_arg(argv, &mut i, "user");
+                }
+                "-G" | "--progress" => {
+                    args.progress = Arguments::parse_string_arg(argv, &mut i, "progress file");
+                }
+                "-J" | "--grid" => {
+                    args.grid = Some(Arguments::parse_string_arg(argv, &mut i, "grid file"));
+                }
+                "-X" | "--sweep-output" => {
+                    args.sweep_output = Arguments::parse_string_arg(argv, &mut i, "sweep output directory");
+                }
+                "-T" | "--report-output" => {
+                    args.report_output = Some(Arguments::parse_string_arg(argv, &mut i, "report output directory"));
+                }
+                // The report command takes the saved report files as they are
+                file if args.command == COMMAND_REPORT && !file.starts_with('-') => {
+                    args.reports.push(file.to_string());
+                }
                 "--help" => {
                     Arguments::print_help_message();
                     process::exit(0);
@@ -97,6 +223,11 @@ impl Arguments {
             i += 1;
         }
 
+        // A recording is played once through, by a single table
+        if args.replay.is_some() {
+            args.number_of_threads = 1;
+        }
+
         args
     }
 
@@ -115,13 +246,43 @@ impl Arguments {
         parsed
     }
 
+    fn parse_f64_arg(argv: &[String], i: &mut usize, min: f64, max: f64, field_name: &str) -> f64 {
+        *i += 1;
+        if *i >= argv.len() {
+            panic!("Missing {}", field_name);
+        }
+        let parsed: f64 = argv[*i].parse().unwrap_or_else(|_| {
+            panic!("Invalid {}", field_name);
+        });
+        if !(min..=max).contains(&parsed) {
+            panic!("{} must be between {} and {}", field_name, min, max);
+        }
+        parsed
+    }
+
+    fn parse_string_arg(argv: &[String], i: &mut usize, field_name: &str) -> String {
+        *i += 1;
+        if *i >= argv.len() {
+            panic!("Missing {}", field_name);
+        }
+        argv[*i].clone()
+    }
+
     fn print_help_message() {
-        println!("Usage: strikerRust++ [options]\n");
+        println!("Usage: strikerRust++ [command] [options]\n");
+        println!("Commands:");
+        println!("  simulate                                     Run the simulation (default)");
+        println!("  play                                         Play hands at the terminal, checked against the strategy charts");
+        println!("  train                                        Running count drills, true count and strategy chart quizzes");
+        println!("  sweep                                        Simulate every configuration in a grid file and compare the results");
+        println!("  report <file>...                             Merge saved reports of the same playbook, compare two playbooks\n");
         println!("Options:");
         println!("  --help                                       Show this help message");
         println!("  --version                                    Display the program version");
         println!("  -h, --number-of-hands <number of hands>      The number of hands to play in this simulation");
-        println!("  -t, --number-of-threads <number of threads>  The number of threads to use in this simulation");
+        println!("  -t, --number-of-threads <number of threads>  The number of threads to use in this simulation, auto (the default) for one per available CPU");
+        println!("  -C, --pin-threads                            Pin each simulation thread to its own CPU");
+        println!("  -E, --target-stderr <percent>                Stop once the standard error of the advantage is under the target, -h caps the hands");
         println!("  -M, --mimic                                  Use the mimic dealer player strategy");
         println!("  -B, --basic                                  Use the basic player strategy");
         println!("  -N, --neural                                 Use the neural player strategy");
@@ -132,6 +293,24 @@ impl Arguments {
         println!("  -1, --single-deck                            Use a single deck of cards and rules");
         println!("  -2, --double-deck                            Use a double deck of cards and rules");
         println!("  -6, --six-shoe                               Use a six deck shoe of cards and rules");
+        println!("  -S, --spanish-21                             Use a six deck Spanish 21 shoe of cards and rules");
+        println!("  -I, --index                                  Generate count based deviation indices for the strategy charts");
+        println!("  -R, --bet-ramp                               Compute a Kelly proportional bet ramp from the simulation results");
+        println!("  -D, --decisions-csv <file>                   Write the results of every chart cell used to a CSV file");
+        println!("  -A, --advantage-play <technique>             See the hole card (hole-card), only tens and aces (warp) or the next card (first-base)");
+        println!("                                               or track aces through the shuffle (ace-sequencing)");
+        println!("  -F, --replay <file>                          Deal the recorded shoes in the file, one per line, instead of shuffling");
+        println!("  -O, --replay-loop                            Start the recording over when it runs out instead of stopping");
+        println!("  -Y, --hand-history <file>                    Write every round as a line of JSON to the file");
+        println!("  -K, --hand-history-sample <rounds>           Only write one round out of every given number of rounds");
+        println!("  -V, --checkpoint <file>                      Save the results so far to the file every so often while simulating");
+        println!("  -Q, --checkpoint-interval <seconds>          The number of seconds between checkpoints, 300 by default");
+        println!("  -Z, --resume                                 Pick the run in the checkpoint file up where it stopped and play the remaining hands");
+        println!("  -U, --user <name>                            The trainee whose progress is kept (train)");
+        println!("  -G, --progress <file>                        The file the trainer keeps progress in (train)");
+        println!("  -J, --grid <file>                            The JSON grid of decks, strategies, penetrations, rules, bet ramps and hands (sweep)");
+        println!("  -X, --sweep-output <directory>               The directory for the report of each cell and the comparison table (sweep)");
+        println!("  -T, --report-output <directory>              The directory for the merged report of each playbook (report)");
     }
 }
 
@@ -177,6 +356,7 @@ mod tests {
     #[case("--single-deck", "-1", DECKS_SINGLE_DECK, 1)]
     #[case("--double-deck", "-2", DECKS_DOUBLE_DECK, 2)]
     #[case("--six-shoe", "-6", DECKS_SIX_SHOE, 6)]
+    #[case("--spanish-21", "-S", DECKS_SPANISH_21, 6)]
     fn test_deck_flags(#[case] flag: &str, #[case] alias: &str, #[case] expected: &str, #[case] count: usize) {
         let arguments = Arguments::make_args(&[flag]);
         assert_eq!(arguments.decks, expected);
@@ -187,6 +367,105 @@ mod tests {
         assert_eq!(arguments.number_of_decks, count);
     }
 
+    #[rstest]
+    #[case("--index")]
+    #[case("-I")]
+    fn test_index_flag(#[case] flag: &str) {
+        let arguments = Arguments::make_args(&[flag]);
+        assert!(arguments.index);
+    }
+
+    #[rstest]
+    #[case("--bet-ramp")]
+    #[case("-R")]
+    fn test_bet_ramp_flag(#[case] flag: &str) {
+        let arguments = Arguments::make_args(&[flag]);
+        assert!(arguments.bet_ramp);
+    }
+
+    #[rstest]
+    #[case("--decisions-csv")]
+    #[case("-D")]
+    fn test_decisions_csv(#[case] flag: &str) {
+        let arguments = Arguments::make_args(&[flag, "decisions.csv"]);
+        assert_eq!(arguments.decisions_csv, Some("decisions.csv".to_string()));
+    }
+
+    #[rstest]
+    #[case("--advantage-play", ADVANTAGE_PLAY_HOLE_CARD)]
+    #[case("-A", ADVANTAGE_PLAY_WARP)]
+    #[case("-A", ADVANTAGE_PLAY_FIRST_BASE)]
+    #[case("-A", ADVANTAGE_PLAY_ACE_SEQUENCING)]
+    fn test_advantage_play(#[case] flag: &str, #[case] technique: &str) {
+        let arguments = Arguments::make_args(&[flag, &technique.to_uppercase()]);
+        assert_eq!(arguments.advantage_play, Some(technique.to_string()));
+    }
+
+    #[test]
+    fn test_commands() {
+        assert_eq!(Arguments::make_args(&[]).command, COMMAND_SIMULATE);
+        let arguments = Arguments::make_args(&[COMMAND_PLAY, "-B", "-6"]);
+        assert_eq!(arguments.command, COMMAND_PLAY);
+        assert_eq!(arguments.strategy, STRATEGY_BASIC);
+        assert_eq!(arguments.number_of_decks, 6);
+
+        let arguments = Arguments::make_args(&[COMMAND_TRAIN, "-U", "alice", "--progress", "team.json"]);
+        assert_eq!(arguments.command, COMMAND_TRAIN);
+        assert_eq!(arguments.user, "alice");
+        assert_eq!(arguments.progress, "team.json");
+        assert_eq!(Arguments::make_args(&[]).user, TRAINER_USER_DEFAULT);
+
+        let arguments = Arguments::make_args(&[COMMAND_SWEEP, "-J", "grid.json", "--sweep-output", "results"]);
+        assert_eq!(arguments.command, COMMAND_SWEEP);
+        assert_eq!(arguments.grid, Some("grid.json".to_string()));
+        assert_eq!(arguments.sweep_output, "results");
+        assert_eq!(Arguments::make_args(&[]).sweep_output, SWEEP_OUTPUT_DEFAULT);
+
+        let arguments = Arguments::make_args(&[COMMAND_REPORT, "a.json", "b.json", "-T", "merged"]);
+        assert_eq!(arguments.command, COMMAND_REPORT);
+        assert_eq!(arguments.reports, vec!["a.json".to_string(), "b.json".to_string()]);
+        assert_eq!(arguments.report_output, Some("merged".to_string()));
+    }
+
+    #[rstest]
+    #[case("--replay", "--replay-loop")]
+    #[case("-F", "-O")]
+    fn test_replay(#[case] flag: &str, #[case] loop_flag: &str) {
+        let arguments = Arguments::make_args(&[flag, "shoes.txt", "-t", "4"]);
+        assert_eq!(arguments.replay, Some("shoes.txt".to_string()));
+        assert_eq!(arguments.number_of_threads, 1);
+        assert!(!arguments.replay_loop);
+
+        let arguments = Arguments::make_args(&[loop_flag]);
+        assert!(arguments.replay_loop);
+    }
+
+    #[rstest]
+    #[case("--hand-history", "--hand-history-sample")]
+    #[case("-Y", "-K")]
+    fn test_hand_history(#[case] flag: &str, #[case] sample_flag: &str) {
+        let arguments = Arguments::make_args(&[flag, "history.jsonl"]);
+        assert_eq!(arguments.hand_history, Some("history.jsonl".to_string()));
+        assert_eq!(arguments.hand_history_sample, 1);
+
+        let arguments = Arguments::make_args(&[sample_flag, "1000"]);
+        assert_eq!(arguments.hand_history_sample, 1000);
+    }
+
+    #[rstest]
+    #[case("--checkpoint", "--checkpoint-interval", "--resume")]
+    #[case("-V", "-Q", "-Z")]
+    fn test_checkpoint(#[case] flag: &str, #[case] interval_flag: &str, #[case] resume_flag: &str) {
+        let arguments = Arguments::make_args(&[flag, "run.checkpoint", interval_flag, "60"]);
+        assert_eq!(arguments.checkpoint, Some("run.checkpoint".to_string()));
+        assert_eq!(arguments.checkpoint_interval, 60);
+        assert!(!arguments.resume);
+
+        let arguments = Arguments::make_args(&[resume_flag]);
+        assert!(arguments.resume);
+        assert_eq!(arguments.checkpoint_interval, CHECKPOINT_INTERVAL_DEFAULT);
+    }
+
     #[rstest]
     #[case("--number-of-hands", MILLION)]
     #[case("-h", MILLION)]
@@ -201,12 +480,49 @@ mod tests {
     fn test_number_of_threads(#[case] flag: &str, #[case] expected: usize) {
         let arguments = Arguments::make_args(&[flag, &expected.to_string()]);
         assert_eq!(arguments.number_of_threads, expected);
+
+        let available_cpus = Topology::detect().available_cpus;
+        assert_eq!(Arguments::make_args(&[flag, "auto"]).number_of_threads, available_cpus);
+        assert_eq!(Arguments::make_args(&[]).number_of_threads, available_cpus);
+        assert_eq!(Arguments::make_args(&[flag, "64"]).number_of_threads, 64);
+    }
+
+    #[rstest]
+    #[case("--target-stderr")]
+    #[case("-E")]
+    fn test_target_stderr(#[case] flag: &str) {
+        assert_eq!(Arguments::make_args(&[flag, "0.05"]).target_stderr, Some(0.05));
+        assert_eq!(Arguments::make_args(&[]).target_stderr, None);
+    }
+
+    #[rstest]
+    #[case("--pin-threads")]
+    #[case("-C")]
+    fn test_pin_threads(#[case] flag: &str) {
+        assert!(Arguments::make_args(&[flag]).pin_threads);
+        assert!(!Arguments::make_args(&[]).pin_threads);
     }
 
     #[test_case("--number-of-threads", ""; "invalid threads missing")]
     #[test_case("--number-of-threads", "empty"; "invalid threads empty")]
-    #[test_case("--number-of-threads", &(NUMBER_OF_CORES_MAXIMUM + 1).to_string(); "invalid threads maximum")]
-    #[test_case("--number-of-threads", &(NUMBER_OF_CORES_MINIMUM - 1).to_string(); "invalid threads minimum")]
+    #[test_case("--number-of-threads", &(NUMBER_OF_THREADS_MAXIMUM + 1).to_string(); "invalid threads maximum")]
+    #[test_case("--number-of-threads", &(NUMBER_OF_THREADS_MINIMUM - 1).to_string(); "invalid threads minimum")]
+    #[test_case("--target-stderr", ""; "invalid target stderr missing")]
+    #[test_case("--target-stderr", "tight"; "invalid target stderr empty")]
+    #[test_case("--target-stderr", "0"; "invalid target stderr minimum")]
+    #[test_case("--target-stderr", "11"; "invalid target stderr maximum")]
+    #[test_case("--decisions-csv", ""; "invalid decisions csv missing")]
+    #[test_case("--advantage-play", ""; "invalid advantage play missing")]
+    #[test_case("--replay", ""; "invalid replay missing")]
+    #[test_case("--hand-history", ""; "invalid hand history missing")]
+    #[test_case("--checkpoint", ""; "invalid checkpoint missing")]
+    #[test_case("--checkpoint-interval", "0"; "invalid checkpoint interval minimum")]
+    #[test_case("--report-output", ""; "invalid report output missing")]
+    #[test_case("results.json", ""; "invalid report file outside report")]
+    #[test_case("--user", ""; "invalid user missing")]
+    #[test_case("--progress", ""; "invalid progress missing")]
+    #[test_case("--hand-history-sample", "0"; "invalid hand history sample minimum")]
+    #[test_case("--advantage-play", "peek"; "invalid advantage play unknown")]
     #[test_case("--number-of-hands", ""; "invalid hands missing")]
     #[test_case("--number-of-hands", "empty"; "invalid hands empty")]
     #[test_case("--number-of-hands", &(NUMBER_OF_HANDS_MAXIMUM + 1).to_string(); "invalid hands maximum")]
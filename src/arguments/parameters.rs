@@ -1,5 +1,6 @@
 use crate::Arguments;
 use crate::constants::constants::*;
+use crate::utilities::topology::Topology;
 use chrono::{Datelike, Local};
 use num_format::{Locale, ToFormattedString};
 use std::fmt;
@@ -14,9 +15,20 @@ pub struct Parameters {
     pub epoch: String,
     pub number_of_decks: usize,
     pub number_of_hands: usize,
-    pub share_of_hands: usize,
+    pub batch_of_hands: usize,
     pub number_of_threads: usize,
+    pub pin_threads: bool,
+    pub topology: Topology,
+    pub target_stderr: Option<f64>,
     pub verbose: bool,
+    pub advantage_play: Option<String>,
+    pub replay: Option<String>,
+    pub replay_loop: bool,
+    pub hand_history: Option<String>,
+    pub hand_history_sample: usize,
+    pub decisions_csv: Option<String>,
+    pub checkpoint: Option<String>,
+    pub checkpoint_interval: usize,
 }
 
 impl Parameters {
@@ -34,24 +46,69 @@ impl Parameters {
             epoch: now.format(TIME_LAYOUT).to_string(),
             number_of_decks: arguments.number_of_decks,
             number_of_hands: arguments.number_of_hands,
-            share_of_hands: (arguments.number_of_hands / threads) + 1,
+            batch_of_hands: Self::batch_of_hands(arguments.number_of_hands, threads),
             number_of_threads: arguments.number_of_threads,
+            pin_threads: arguments.pin_threads,
+            topology: Topology::detect(),
+            target_stderr: arguments.target_stderr,
             verbose: arguments.number_of_threads == 1,
+            advantage_play: arguments.advantage_play.clone(),
+            replay: arguments.replay.clone(),
+            replay_loop: arguments.replay_loop,
+            hand_history: arguments.hand_history.clone(),
+            hand_history_sample: arguments.hand_history_sample,
+            decisions_csv: arguments.decisions_csv.clone(),
+            checkpoint: arguments.checkpoint.clone(),
+            checkpoint_interval: arguments.checkpoint_interval,
         }
     }
+
+    // Several batches per thread keep the load balanced, an even batch keeps a blackjack switch round of two hands whole
+    fn batch_of_hands(number_of_hands: usize, threads: usize) -> usize {
+        let batch = (number_of_hands / (threads * NUMBER_OF_BATCHES_PER_THREAD)).clamp(2, NUMBER_OF_HANDS_BATCH);
+        batch + batch % 2
+    }
 }
 
 impl fmt::Display for Parameters {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         writeln!(f, "{:>4}{:<26}: {}", "", "Processor", self.processor)?;
-        writeln!(f, "{:>4}{:<26}: {}", "", "Threads", self.number_of_threads)?;
+        writeln!(
+            f,
+            "{:>4}{:<26}: {}{}",
+            "",
+            "Threads",
+            self.number_of_threads,
+            if self.pin_threads { " (pinned)" } else { "" }
+        )?;
+        writeln!(
+            f,
+            "{:>4}{:<26}: {} available, {} physical cores",
+            "", "CPUs", self.topology.available_cpus, self.topology.physical_cores
+        )?;
         writeln!(f, "{:>4}{:<26}: {}", "", "Name", self.name)?;
         writeln!(f, "{:>4}{:<26}: {}", "", "Version", STRIKER_VERSION)?;
         writeln!(f, "{:>4}{:<26}: {}", "", "Playbook", self.playbook)?;
         writeln!(f, "{:>4}{:<26}: {}", "", "Decks", self.decks)?;
         writeln!(f, "{:>4}{:<26}: {}", "", "Strategy", self.strategy)?;
+        if let Some(technique) = &self.advantage_play {
+            writeln!(f, "{:>4}{:<26}: {}", "", "Advantage play", technique)?;
+        }
+        if let Some(replay) = &self.replay {
+            let mode = if self.replay_loop { "looped" } else { "once" };
+            writeln!(f, "{:>4}{:<26}: {} ({})", "", "Replay", replay, mode)?;
+        }
+        if let Some(history) = &self.hand_history {
+            writeln!(f, "{:>4}{:<26}: {} (1 in {} rounds)", "", "Hand history", history, self.hand_history_sample)?;
+        }
+        if let Some(checkpoint) = &self.checkpoint {
+            writeln!(f, "{:>4}{:<26}: {} (every {} seconds)", "", "Checkpoint", checkpoint, self.checkpoint_interval)?;
+        }
         writeln!(f, "{:>4}{:<26}: {:>17}", "", "Number of hands", self.number_of_hands.to_formatted_string(&Locale::en))?;
-        writeln!(f, "{:>4}{:<26}: {:>17}", "", "Thread share of hands", self.share_of_hands.to_formatted_string(&Locale::en))?;
+        writeln!(f, "{:>4}{:<26}: {:>17}", "", "Hands per batch", self.batch_of_hands.to_formatted_string(&Locale::en))?;
+        if let Some(target) = self.target_stderr {
+            writeln!(f, "{:>4}{:<26}: {:>17} % (the number of hands is the cap)", "", "Target standard error", target)?;
+        }
         write!(f, "{:>4}{:<26}: {}", "", "Epoch", self.epoch)?;
         Ok(())
     }
@@ -67,4 +124,12 @@ mod tests {
         let parameters = Parameters::new(&args);
         print!("{}", parameters);
     }
+
+    #[test]
+    fn test_batch_of_hands() {
+        assert_eq!(Parameters::batch_of_hands(NUMBER_OF_HANDS_DEFAULT, 24), NUMBER_OF_HANDS_BATCH);
+        assert_eq!(Parameters::batch_of_hands(20_000, 2), 1250);
+        assert_eq!(Parameters::batch_of_hands(1000, 3), 42);
+        assert_eq!(Parameters::batch_of_hands(NUMBER_OF_HANDS_MINIMUM, NUMBER_OF_THREADS_MAXIMUM), 2);
+    }
 }
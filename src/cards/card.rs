@@ -19,6 +19,11 @@ impl Card {
     pub fn is_ace(&self) -> bool {
         self.rank == Rank::Ace
     }
+
+    // Short notation such as "Xs" or "Ah", as read back by a replay file
+    pub fn key(&self) -> String {
+        format!("{}{}", self.rank.key(), self.suit.key())
+    }
 }
 
 impl fmt::Display for Card {
@@ -52,6 +57,13 @@ mod tests {
         assert_eq!(card_str, "Ten of Clubs");
     }
 
+    #[test]
+    fn test_card_key() {
+        assert_eq!(Card::new(Rank::Ace, Suit::Hearts).key(), "Ah");
+        assert_eq!(Card::new(Rank::Queen, Suit::Spades).key(), "Xs");
+        assert_eq!(Card::new(Rank::Seven, Suit::Diamonds).key(), "7d");
+    }
+
     #[test]
     fn test_card_creation() {
         let card1 = Card::new(Rank::Ace, Suit::Spades);
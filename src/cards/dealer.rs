@@ -32,14 +32,14 @@ mod tests {
 
         assert_eq!(dealer.hand.total, 0);
 
-        dealer.hand.draw_card(Some(Card::new(Rank::Ace, Suit::Hearts)));
+        dealer.hand.add_card(Card::new(Rank::Ace, Suit::Hearts));
         assert_eq!(dealer.hand.total, 11);
 
-        dealer.hand.draw_card(Some(Card::new(Rank::Six, Suit::Spades)));
+        dealer.hand.add_card(Card::new(Rank::Six, Suit::Spades));
         assert_eq!(dealer.hand.total, 17); // Soft
         assert!(!dealer.should_stand());
 
-        dealer.hand.draw_card(Some(Card::new(Rank::Ten, Suit::Spades)));
+        dealer.hand.add_card(Card::new(Rank::Ten, Suit::Spades));
         assert_eq!(dealer.hand.total, 17); // Hard
         assert!(dealer.should_stand());
     }
@@ -48,10 +48,10 @@ mod tests {
     fn test_dealer_should_stand_on_soft_17() {
         let mut dealer = Dealer::new(false);
 
-        dealer.hand.draw_card(Some(Card::new(Rank::Ace, Suit::Hearts)));
+        dealer.hand.add_card(Card::new(Rank::Ace, Suit::Hearts));
         assert_eq!(dealer.hand.total, 11);
 
-        dealer.hand.draw_card(Some(Card::new(Rank::Six, Suit::Spades)));
+        dealer.hand.add_card(Card::new(Rank::Six, Suit::Spades));
         assert_eq!(dealer.hand.total, 17); // Soft
         assert!(dealer.should_stand());
     }
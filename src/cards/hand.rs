@@ -1,4 +1,5 @@
 use crate::cards::Card;
+use crate::errors::errors::StrikerError;
 
 #[derive(Debug, Clone, Default)]
 pub struct Hand {
@@ -22,8 +23,13 @@ impl Hand {
         self.soft_ace = 0;
     }
 
-    pub fn draw_card(&mut self, maybe_card: Option<Card>) {
-        let card = maybe_card.expect("Expected a card but got None");
+    // A card from the shoe, which has none left when a recorded shoe is too short for the round
+    pub fn draw_card(&mut self, maybe_card: Option<Card>) -> Result<(), StrikerError> {
+        self.add_card(maybe_card.ok_or(StrikerError::OutOfCards)?);
+        Ok(())
+    }
+
+    pub fn add_card(&mut self, card: Card) {
         self.cards.push(card);
         self.calculate_total();
     }
@@ -56,11 +62,12 @@ impl Hand {
         self.cards.get(0)
     }
 
-    pub fn split_pair(&mut self) -> Result<Card, &'static str> {
+    pub fn split_pair(&mut self) -> Result<Card, StrikerError> {
         if !self.is_pair() {
-            return Err("Error: Trying to split a non-pair");
+            let cards: Vec<String> = self.cards.iter().map(|card| card.key()).collect();
+            return Err(StrikerError::NotAPair(cards.join(" ")));
         }
-        let card = self.cards.pop().expect("Hand was empty when trying to split");
+        let card = self.cards.remove(1);
         self.calculate_total();
         Ok(card)
     }
@@ -96,7 +103,7 @@ mod tests {
     fn make_hand(cards: Vec<(Rank, Suit)>) -> Hand {
         let mut hand = Hand::new();
         for (rank, suit) in cards {
-            hand.draw_card(Some(make_card(rank, suit)));
+            hand.add_card(make_card(rank, suit));
         }
         hand
     }
@@ -121,9 +128,9 @@ mod tests {
         assert!(hand.is_soft());
         assert!(!hand.is_soft_17());
 
-        hand.draw_card(Some(make_card(Rank::Four, Suit::Spades)));
+        hand.add_card(make_card(Rank::Four, Suit::Spades));
         assert_eq!(hand.total, 16);
-        hand.draw_card(Some(make_card(Rank::Ace, Suit::Hearts))); // 16 + 11 → 17 (soft)
+        hand.add_card(make_card(Rank::Ace, Suit::Hearts)); // 16 + 11 → 17 (soft)
         assert_eq!(hand.total, 17);
         assert!(hand.is_soft_17());
     }
@@ -139,16 +146,18 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(expected = "Expected a card but got None")]
-    fn test_draw_card_none_should_panic() {
+    fn test_draw_card_none_is_out_of_cards() {
         let mut hand = Hand::new();
-        hand.draw_card(None);
+        assert!(matches!(hand.draw_card(None), Err(StrikerError::OutOfCards)));
+        assert!(hand.cards.is_empty());
+        assert!(hand.draw_card(Some(make_card(Rank::Two, Suit::Clubs))).is_ok());
+        assert_eq!(hand.total, 2);
     }
 
     #[test]
-    #[should_panic(expected = "Error: Trying to split a non-pair")]
-    fn test_split_non_pair_should_panic() {
+    fn test_split_non_pair_is_an_error() {
         let mut hand = make_hand(vec![(Rank::Ace, Suit::Spades), (Rank::Ten, Suit::Clubs)]);
-        hand.split_pair().unwrap();
+        assert_eq!(hand.split_pair().unwrap_err().to_string(), "Cannot split a non-pair hand (As Xc)");
+        assert_eq!(hand.cards.len(), 2);
     }
 }
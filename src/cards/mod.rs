@@ -3,8 +3,12 @@ pub mod card;
 pub mod dealer;
 pub mod hand;
 pub mod rank;
+pub mod replay;
 pub mod shoe;
+pub mod shuffle;
+pub mod side_bets;
 pub mod suit;
+pub mod tracker;
 pub mod wager;
 
 pub use card::Card;
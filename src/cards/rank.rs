@@ -36,6 +36,26 @@ impl Rank {
         }
     }
 
+    // Accepts the keys plus the usual notation for the ten valued cards, e.g. "T", "10" or "K"
+    pub fn from_key(key: &str) -> Option<Rank> {
+        match key.to_uppercase().as_str() {
+            "2" => Some(Rank::Two),
+            "3" => Some(Rank::Three),
+            "4" => Some(Rank::Four),
+            "5" => Some(Rank::Five),
+            "6" => Some(Rank::Six),
+            "7" => Some(Rank::Seven),
+            "8" => Some(Rank::Eight),
+            "9" => Some(Rank::Nine),
+            "X" | "T" | "10" => Some(Rank::Ten),
+            "J" => Some(Rank::Jack),
+            "Q" => Some(Rank::Queen),
+            "K" => Some(Rank::King),
+            "A" => Some(Rank::Ace),
+            _ => None,
+        }
+    }
+
     pub fn value(&self) -> usize {
         match self {
             Rank::Two => 2,
@@ -132,6 +152,17 @@ mod tests {
             assert_eq!(rank.key(), expected_key, "Rank::{} key failed", expected_str);
             assert_eq!(rank.value(), expected_value, "Rank::{} value failed", expected_str);
             assert_eq!(format!("{}", rank), expected_str);
+            assert_eq!(Rank::from_key(rank.key()).map(|r| r.value()), Some(expected_value));
         }
     }
+
+    #[test]
+    fn test_rank_from_key() {
+        assert_eq!(Rank::from_key("t"), Some(Rank::Ten));
+        assert_eq!(Rank::from_key("10"), Some(Rank::Ten));
+        assert_eq!(Rank::from_key("Q"), Some(Rank::Queen));
+        assert_eq!(Rank::from_key("a"), Some(Rank::Ace));
+        assert_eq!(Rank::from_key("1"), None);
+        assert_eq!(Rank::from_key(""), None);
+    }
 }
@@ -0,0 +1,124 @@
+use crate::cards::{Card, Rank, Suit};
+use crate::errors::errors::StrikerError;
+use std::fs;
+
+// Recorded shoes dealt in order instead of shuffled ones, looping back to the first shoe or stopping once they run out
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct Replay {
+    shoes: Vec<Vec<Card>>,
+    next_shoe: usize,
+    looped: bool,
+    pub exhausted: bool,
+}
+
+impl Replay {
+    pub fn new(shoes: Vec<Vec<Card>>, looped: bool) -> Self {
+        Self {
+            shoes,
+            looped,
+            ..Default::default()
+        }
+    }
+
+    pub fn from_file(path: &str, looped: bool) -> Result<Self, StrikerError> {
+        let text = fs::read_to_string(path).map_err(|e| StrikerError::file("read replay file", path, e))?;
+        let shoes = Self::parse(&text)?;
+        if shoes.is_empty() {
+            return Err(StrikerError::EmptyReplay(path.to_string()));
+        }
+        Ok(Self::new(shoes, looped))
+    }
+
+    // One shoe per line in dealing order, e.g. "A X 5 9h Ks 2", blank lines and lines starting with '#' are skipped.
+    // Cards without a suit letter are given the suits in turn so the side bets still see a mix.
+    pub fn parse(text: &str) -> Result<Vec<Vec<Card>>, StrikerError> {
+        let mut shoes = Vec::new();
+        for (number, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let shoe = line
+                .split(|c: char| c.is_whitespace() || c == ',')
+                .filter(|token| !token.is_empty())
+                .enumerate()
+                .map(|(position, token)| {
+                    Self::parse_card(token, position).ok_or_else(|| StrikerError::InvalidCard {
+                        token: token.to_string(),
+                        line: number + 1,
+                    })
+                })
+                .collect::<Result<Vec<Card>, StrikerError>>()?;
+            shoes.push(shoe);
+        }
+        Ok(shoes)
+    }
+
+    fn parse_card(token: &str, position: usize) -> Option<Card> {
+        if let Some(rank) = Rank::from_key(token) {
+            return Some(Card::new(rank, Suit::iter().nth(position % 4)?));
+        }
+        let (key, suit) = token.split_at(token.len() - token.chars().last()?.len_utf8());
+        Some(Card::new(Rank::from_key(key)?, Suit::from_key(suit.chars().next()?)?))
+    }
+
+    // The next recorded shoe, the first one again when looping, or None when the recording has been played
+    pub fn next_shoe(&mut self) -> Option<Vec<Card>> {
+        if self.next_shoe == self.shoes.len() {
+            self.exhausted = true;
+            if !self.looped {
+                return None;
+            }
+            self.next_shoe = 0;
+        }
+        self.next_shoe += 1;
+        Some(self.shoes[self.next_shoe - 1].clone())
+    }
+
+    pub fn finished(&self) -> bool {
+        self.exhausted && !self.looped
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let shoes = Replay::parse("# recorded at table 4\nA X 5 9h, Ks 2\n\n10 t qd\n").unwrap();
+        assert_eq!(shoes.len(), 2);
+        assert_eq!(shoes[0].len(), 6);
+        assert_eq!(shoes[0][0], Card::new(Rank::Ace, Suit::Hearts));
+        assert_eq!(shoes[0][1], Card::new(Rank::Ten, Suit::Diamonds));
+        assert_eq!(shoes[0][3], Card::new(Rank::Nine, Suit::Hearts));
+        assert_eq!(shoes[0][4], Card::new(Rank::King, Suit::Spades));
+        assert_eq!(shoes[1][2], Card::new(Rank::Queen, Suit::Diamonds));
+        assert!(shoes[1].iter().all(|card| card.rank.value() == 10));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!(Replay::parse("A X 1").unwrap_err().to_string().contains("'1' on line 1"));
+        assert!(Replay::parse("\nA Xz").unwrap_err().to_string().contains("'Xz' on line 2"));
+        assert!(Replay::from_file("/nonexistent/replay.txt", false).is_err());
+    }
+
+    #[test]
+    fn test_next_shoe() {
+        let shoes = Replay::parse("A\n2\n").unwrap();
+        let mut replay = Replay::new(shoes.clone(), false);
+        assert_eq!(replay.next_shoe(), Some(shoes[0].clone()));
+        assert_eq!(replay.next_shoe(), Some(shoes[1].clone()));
+        assert!(!replay.exhausted);
+        assert_eq!(replay.next_shoe(), None);
+        assert!(replay.finished());
+
+        let mut replay = Replay::new(shoes.clone(), true);
+        replay.next_shoe();
+        replay.next_shoe();
+        assert_eq!(replay.next_shoe(), Some(shoes[0].clone()));
+        assert!(replay.exhausted);
+        assert!(!replay.finished());
+    }
+}
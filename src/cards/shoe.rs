@@ -1,5 +1,4 @@
-use crate::cards::{Card, Rank, Suit};
-use rand::seq::SliceRandom;
+use crate::cards::{Card, Rank, Suit, replay::Replay, shuffle::ShuffleModel, tracker::Tracker};
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Shoe {
@@ -12,16 +11,28 @@ pub struct Shoe {
     burn_card: usize,
     next_card: usize,
     last_discard: usize,
+    shuffle_model: ShuffleModel,
+    tracker: Option<Tracker>,
+    replay: Option<Replay>,
 }
 
 impl Shoe {
     pub fn new(number_of_decks: usize, penetration: f64) -> Self {
+        Self::with_ranks(number_of_decks, penetration, &[])
+    }
+
+    // Spanish 21 decks have the tens removed, leaving 48 cards
+    pub fn new_spanish_21(number_of_decks: usize, penetration: f64) -> Self {
+        Self::with_ranks(number_of_decks, penetration, &[Rank::Ten])
+    }
+
+    fn with_ranks(number_of_decks: usize, penetration: f64, excluded: &[Rank]) -> Self {
         let mut cards = Vec::new();
 
         // Populate the shoe with cards based on the number of decks
         for _ in 0..number_of_decks {
             for suit in Suit::iter() {
-                for rank in Rank::iter() {
+                for rank in Rank::iter().filter(|rank| !excluded.contains(rank)) {
                     cards.push(Card::new(rank, suit));
                 }
             }
@@ -40,6 +51,9 @@ impl Shoe {
             burn_card: 1,
             next_card: number_of_cards,
             last_discard: number_of_cards,
+            shuffle_model: ShuffleModel::new(),
+            tracker: None,
+            replay: None,
         };
 
         shoe.shuffle();
@@ -51,24 +65,105 @@ impl Shoe {
         if self.next_card >= self.number_of_cards {
             self.force_shuffle = true;
             self.out_of_cards += 1;
-            self.shuffle_random();
+            self.shuffle_cards();
         }
         let card = self.cards.get(self.next_card).cloned();
+        if let (Some(tracker), Some(card)) = (&mut self.tracker, &card) {
+            tracker.observe(card, self.next_card);
+        }
         self.next_card += 1;
         card
     }
 
+    // Method to draw a card of the given value from the undealt cards
+    pub fn draw_value(&mut self, value: usize) -> Option<Card> {
+        let index = (self.next_card..self.number_of_cards).find(|&i| self.cards[i].rank.value() == value)?;
+        self.cards.swap(self.next_card, index);
+        self.draw_card()
+    }
+
+    // The next card to be dealt, as seen by a first baser
+    pub fn peek_card(&self) -> Option<&Card> {
+        self.cards.get(self.next_card)
+    }
+
+    // A card further down the shoe, seen without dealing it
+    pub fn peek_ahead(&self, offset: usize) -> Option<&Card> {
+        let index = self.next_card + offset;
+        if index < self.number_of_cards { self.cards.get(index) } else { None }
+    }
+
+    // Follow the discards through every shuffle from now on
+    pub fn enable_tracking(&mut self) {
+        self.tracker = Some(Tracker::new());
+    }
+
+    pub fn ace_predicted(&self) -> bool {
+        self.tracker.as_ref().is_some_and(|tracker| tracker.ace_next())
+    }
+
+    // Index of the next card to be dealt
+    pub fn position(&self) -> usize {
+        self.next_card
+    }
+
+    pub fn get_cut_card(&self) -> usize {
+        self.cut_card
+    }
+
+    // Replace the perfect random shuffle and the single burn card
+    pub fn set_shuffle_model(&mut self, shuffle_model: ShuffleModel, burn_cards: usize) {
+        self.shuffle_model = shuffle_model;
+        self.burn_card = burn_cards.min(self.cut_card);
+        self.shuffle();
+    }
+
+    // Deal the recorded shoes instead, the first one is loaded by the next shuffle
+    pub fn set_replay(&mut self, replay: Replay) {
+        self.replay = Some(replay);
+        self.cards.clear();
+        self.number_of_cards = 0;
+        self.cut_card = 0;
+        self.next_card = 0;
+        self.last_discard = 0;
+    }
+
+    pub fn is_replay(&self) -> bool {
+        self.replay.is_some()
+    }
+
+    // A recording that does not loop has been played to the end
+    pub fn replay_finished(&self) -> bool {
+        self.replay.as_ref().is_some_and(|replay| replay.finished())
+    }
+
     // Method to shuffle the deck
     pub fn shuffle(&mut self) {
         self.last_discard = self.number_of_cards;
+        // Running out of a recorded shoe mid round already moved on to the next one
+        let reloaded = self.force_shuffle && self.replay.is_some();
         self.force_shuffle = false;
-        self.shuffle_random();
+        if !reloaded {
+            self.shuffle_cards();
+        }
     }
 
-    // Random shuffling method using the RNG
-    fn shuffle_random(&mut self) {
-        let mut rng = rand::rng();
-        self.cards[..self.last_discard].shuffle(&mut rng);
+    // Shuffle the discards with the configured model
+    fn shuffle_cards(&mut self) {
+        if let Some(replay) = &mut self.replay {
+            // Once a recording is finished the shoe is left empty, a round still in progress runs out of cards
+            let cards = replay.next_shoe().unwrap_or_default();
+            self.number_of_cards = cards.len();
+            self.cut_card = cards.len();
+            self.cards = cards;
+            self.next_card = 0;
+            self.number_of_shuffles += 1;
+            return;
+        }
+        if let Some(tracker) = &mut self.tracker {
+            tracker.track(&self.cards[..self.last_discard], &self.shuffle_model.nominal_order(self.last_discard));
+        }
+        self.shuffle_model.shuffle(&mut self.cards[..self.last_discard]);
         self.next_card = self.burn_card;
         self.number_of_shuffles += 1;
     }
@@ -76,7 +171,9 @@ impl Shoe {
     // Method to check if the deck should be shuffled
     pub fn should_shuffle(&mut self) -> bool {
         self.last_discard = self.next_card;
-        (self.next_card >= self.cut_card) || self.force_shuffle
+        // A continuous shuffling machine takes the discards back after every round
+        let csm = self.shuffle_model.csm && self.next_card > self.burn_card;
+        (self.next_card >= self.cut_card) || self.force_shuffle || csm
     }
 }
 
@@ -108,6 +205,109 @@ mod shoe_tests {
         }
     }
 
+    #[test]
+    fn test_spanish_21_shoe() {
+        let shoe = Shoe::new_spanish_21(6, 0.75);
+        assert_eq!(shoe.number_of_cards, 48 * 6);
+        assert!(shoe.cards.iter().all(|card| card.rank != Rank::Ten));
+        assert_eq!(shoe.cards.iter().filter(|card| card.rank.value() == 10).count(), 12 * 6);
+    }
+
+    #[test]
+    fn test_draw_value() {
+        let mut shoe = Shoe::new(1, 0.5);
+        let aces = shoe.cards[shoe.next_card..].iter().filter(|card| card.is_ace()).count();
+        for _ in 0..aces {
+            let card = shoe.draw_value(Rank::Ace.value());
+            assert!(card.is_some_and(|card| card.is_ace()));
+        }
+        assert!(shoe.draw_value(Rank::Ace.value()).is_none());
+    }
+
+    #[test]
+    fn test_shuffle_model_and_burn_cards() {
+        let mut shoe = Shoe::new(1, 0.75);
+        shoe.set_shuffle_model(ShuffleModel { csm: true, ..Default::default() }, 3);
+        assert_eq!(shoe.next_card, 3);
+        assert!(!shoe.should_shuffle());
+        shoe.draw_card();
+        assert!(shoe.should_shuffle());
+
+        shoe.set_shuffle_model(ShuffleModel::new(), 100);
+        assert_eq!(shoe.next_card, shoe.cut_card);
+    }
+
+    #[test]
+    fn test_peek_card() {
+        let mut shoe = Shoe::new(1, 0.75);
+        let card = shoe.peek_card().cloned();
+        assert_eq!(shoe.draw_card(), card);
+        let position = shoe.position();
+        assert_eq!(shoe.peek_ahead(1), shoe.cards.get(position + 1));
+        assert_eq!(shoe.position(), position);
+        shoe.next_card = shoe.number_of_cards;
+        assert!(shoe.peek_card().is_none());
+        assert!(shoe.peek_ahead(0).is_none());
+    }
+
+    #[test]
+    fn test_tracking_perfect_shuffle() {
+        let mut shoe = Shoe::new(1, 0.75);
+        let model = ShuffleModel::fetch_table(&serde_json::json!({ "deterministic": true, "steps": ["riffle", "riffle"], "cutVariance": 0.0 }));
+        shoe.set_shuffle_model(model, 0);
+        shoe.enable_tracking();
+        shoe.shuffle();
+
+        // Every ace that follows a predicted key card is found
+        let mut predicted = 0;
+        while shoe.next_card < shoe.number_of_cards {
+            let ace_predicted = shoe.ace_predicted();
+            let card = shoe.draw_card().unwrap();
+            if ace_predicted {
+                predicted += 1;
+                assert!(card.is_ace());
+            }
+        }
+        assert!(predicted > 0);
+    }
+
+    #[test]
+    fn test_replay() {
+        let shoes = Replay::parse("A 2 3 4\n5 6 7\n").unwrap();
+        let mut shoe = Shoe::new(1, 0.75);
+        shoe.set_replay(Replay::new(shoes.clone(), false));
+        shoe.shuffle();
+        for card in &shoes[0] {
+            assert!(!shoe.should_shuffle());
+            assert_eq!(shoe.draw_card().as_ref(), Some(card));
+        }
+        assert!(shoe.should_shuffle());
+        shoe.shuffle();
+        assert_eq!(shoe.number_of_cards, 3);
+
+        // Running out of the last recorded shoe mid round leaves nothing to deal
+        shoe.draw_card();
+        shoe.draw_card();
+        shoe.draw_card();
+        assert!(!shoe.replay_finished());
+        assert_eq!(shoe.draw_card(), None);
+        assert!(shoe.replay_finished());
+        assert_eq!(shoe.draw_card(), None);
+        assert!(shoe.should_shuffle());
+    }
+
+    #[test]
+    fn test_replay_loop() {
+        let shoes = Replay::parse("A 2\n").unwrap();
+        let mut shoe = Shoe::new(1, 0.75);
+        shoe.set_replay(Replay::new(shoes, true));
+        for _ in 0..3 {
+            shoe.shuffle();
+            assert_eq!(shoe.draw_card().map(|card| card.rank), Some(Rank::Ace));
+            assert!(!shoe.replay_finished());
+        }
+    }
+
     #[test]
     fn test_force_shuffle() {
         for decks in [1, 2, 6] {
@@ -0,0 +1,324 @@
+use crate::constants::constants::{SHUFFLE_BOX, SHUFFLE_BOX_PILES, SHUFFLE_CUT_VARIANCE, SHUFFLE_RIFFLE, SHUFFLE_STRIP, SHUFFLE_STRIP_PACKETS};
+use rand::Rng;
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::fmt;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ShuffleStep {
+    Riffle,
+    Strip,
+    Box,
+}
+
+impl ShuffleStep {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ShuffleStep::Riffle => SHUFFLE_RIFFLE,
+            ShuffleStep::Strip => SHUFFLE_STRIP,
+            ShuffleStep::Box => SHUFFLE_BOX,
+        }
+    }
+}
+
+// How the shoe is shuffled: a perfect random shuffle, a continuous shuffling machine or a human procedure
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(from = "Value", into = "Value")]
+pub struct ShuffleModel {
+    pub csm: bool,
+    pub deterministic: bool,
+    pub steps: Vec<ShuffleStep>,
+    pub clumping: f64,
+    pub cut_variance: f64,
+    pub strip_packets: usize,
+    pub box_piles: usize,
+}
+
+impl Default for ShuffleModel {
+    fn default() -> Self {
+        Self {
+            csm: false,
+            deterministic: false,
+            steps: Vec::new(),
+            clumping: 0.0,
+            cut_variance: SHUFFLE_CUT_VARIANCE,
+            strip_packets: SHUFFLE_STRIP_PACKETS,
+            box_piles: SHUFFLE_BOX_PILES,
+        }
+    }
+}
+
+impl ShuffleModel {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Load a model in the form { "csm": false, "deterministic": true, "steps": ["riffle", "strip", "riffle", "box"], "clumping": 0.3, ... }
+    pub fn fetch_table(data: &Value) -> Self {
+        let mut model = Self::new();
+        model.csm = data.get("csm").and_then(|v| v.as_bool()).unwrap_or(false);
+        model.deterministic = data.get("deterministic").and_then(|v| v.as_bool()).unwrap_or(false);
+        if let Some(steps) = data.get("steps").and_then(|v| v.as_array()) {
+            model.steps = steps
+                .iter()
+                .filter_map(|step| match step.as_str()?.to_lowercase().as_str() {
+                    SHUFFLE_RIFFLE => Some(ShuffleStep::Riffle),
+                    SHUFFLE_STRIP => Some(ShuffleStep::Strip),
+                    SHUFFLE_BOX => Some(ShuffleStep::Box),
+                    _ => None,
+                })
+                .collect();
+        }
+        model.clumping = data.get("clumping").and_then(|v| v.as_f64()).unwrap_or(model.clumping).clamp(0.0, 1.0);
+        model.cut_variance = data.get("cutVariance").and_then(|v| v.as_f64()).unwrap_or(model.cut_variance).clamp(0.0, 0.5);
+        model.strip_packets = data.get("stripPackets").and_then(|v| v.as_u64()).map_or(model.strip_packets, |v| v.max(1) as usize);
+        model.box_piles = data.get("boxPiles").and_then(|v| v.as_u64()).map_or(model.box_piles, |v| v.max(1) as usize);
+        model
+    }
+
+    // Model in the same layout it is loaded from
+    pub fn to_json_object(&self) -> Value {
+        json!({
+            "csm": self.csm,
+            "deterministic": self.deterministic,
+            "steps": self.steps.iter().map(|step| step.name()).collect::<Vec<&str>>(),
+            "clumping": self.clumping,
+            "cutVariance": self.cut_variance,
+            "stripPackets": self.strip_packets,
+            "boxPiles": self.box_piles,
+        })
+    }
+
+    pub fn shuffle<T: Clone>(&self, cards: &mut [T]) {
+        let mut rng = rand::rng();
+        if self.steps.is_empty() {
+            cards.shuffle(&mut rng);
+            return;
+        }
+        for step in &self.steps {
+            match step {
+                ShuffleStep::Riffle => self.riffle(cards, &mut rng),
+                ShuffleStep::Strip => self.strip(cards, &mut rng),
+                ShuffleStep::Box => self.box_cut(cards),
+            }
+        }
+    }
+
+    // Where each card ends up when the procedure is carried out without any imperfection, as indexes into the old order
+    pub fn nominal_order(&self, length: usize) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..length).collect();
+        if !self.steps.is_empty() {
+            let perfect = ShuffleModel {
+                deterministic: true,
+                clumping: 0.0,
+                cut_variance: 0.0,
+                ..self.clone()
+            };
+            perfect.shuffle(&mut order);
+        }
+        order
+    }
+
+    // Cut near the middle and interleave the halves, clumping keeps dropping from the same half
+    fn riffle<T: Clone>(&self, cards: &mut [T], rng: &mut impl Rng) {
+        let length = cards.len();
+        if length < 2 {
+            return;
+        }
+        let cut = self.cut_point(length, rng);
+        let (left, right) = cards.split_at(cut);
+        let (mut i, mut j) = (0, 0);
+        let mut from_left = true;
+        let mut riffled = Vec::with_capacity(length);
+        while i < left.len() || j < right.len() {
+            from_left = if i == left.len() {
+                false
+            } else if j == right.len() {
+                true
+            } else if !riffled.is_empty() && rng.random::<f64>() < self.clumping {
+                from_left
+            } else if self.deterministic {
+                riffled.is_empty() || !from_left
+            } else {
+                rng.random_range(0..left.len() - i + right.len() - j) < left.len() - i
+            };
+            if from_left {
+                riffled.push(left[i].clone());
+                i += 1;
+            } else {
+                riffled.push(right[j].clone());
+                j += 1;
+            }
+        }
+        cards.clone_from_slice(&riffled);
+    }
+
+    // Pull packets off the top and stack them, reversing the packet order
+    fn strip<T: Clone>(&self, cards: &mut [T], rng: &mut impl Rng) {
+        let length = cards.len();
+        let packet = (length / self.strip_packets).max(1);
+        let mut stripped = Vec::with_capacity(length);
+        let mut top = 0;
+        while top < length {
+            let spread = (packet as f64 * self.cut_variance).round() as usize;
+            let size = (packet + rng.random_range(0..=spread * 2)).saturating_sub(spread).max(1).min(length - top);
+            stripped.splice(0..0, cards[top..top + size].iter().cloned());
+            top += size;
+        }
+        cards.clone_from_slice(&stripped);
+    }
+
+    // Split into piles and restack them bottom to top
+    fn box_cut<T: Clone>(&self, cards: &mut [T]) {
+        let pile = cards.len().div_ceil(self.box_piles).max(1);
+        let boxed: Vec<T> = cards.chunks(pile).rev().flatten().cloned().collect();
+        cards.clone_from_slice(&boxed);
+    }
+
+    fn cut_point(&self, length: usize, rng: &mut impl Rng) -> usize {
+        let spread = (length as f64 * self.cut_variance / 2.0).round() as usize;
+        let cut = length / 2 - spread + rng.random_range(0..=spread * 2);
+        cut.clamp(1, length - 1)
+    }
+}
+
+impl From<Value> for ShuffleModel {
+    fn from(data: Value) -> Self {
+        Self::fetch_table(&data)
+    }
+}
+
+impl From<ShuffleModel> for Value {
+    fn from(model: ShuffleModel) -> Self {
+        model.to_json_object()
+    }
+}
+
+impl fmt::Display for ShuffleModel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.csm {
+            return write!(f, "continuous shuffling machine");
+        }
+        if self.steps.is_empty() {
+            return write!(f, "random");
+        }
+        let steps: Vec<&str> = self.steps.iter().map(|step| step.name()).collect();
+        let deterministic = if self.deterministic { "deterministic " } else { "" };
+        write!(
+            f,
+            "{}{} (clumping {:.2}, cut variance {:.2})",
+            deterministic,
+            steps.join(", "),
+            self.clumping,
+            self.cut_variance
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::{Card, Rank, Suit};
+    use serde_json::json;
+
+    fn deck() -> Vec<Card> {
+        Suit::iter().flat_map(|suit| Rank::iter().map(move |rank| Card::new(rank, suit))).collect()
+    }
+
+    fn same_cards(a: &[Card], b: &[Card]) -> bool {
+        let key = |card: &Card| (card.rank as usize, card.suit as usize);
+        let mut a: Vec<_> = a.iter().map(key).collect();
+        let mut b: Vec<_> = b.iter().map(key).collect();
+        a.sort();
+        b.sort();
+        a == b
+    }
+
+    #[test]
+    fn test_fetch_table() {
+        let model = ShuffleModel::fetch_table(&json!({ "steps": ["riffle", "Strip", "box", "bogus"], "clumping": 0.3, "boxPiles": 3 }));
+        assert!(!model.csm);
+        assert_eq!(model.steps, vec![ShuffleStep::Riffle, ShuffleStep::Strip, ShuffleStep::Box]);
+        assert_eq!(model.clumping, 0.3);
+        assert_eq!(model.box_piles, 3);
+        assert_eq!(model.strip_packets, SHUFFLE_STRIP_PACKETS);
+        assert_eq!(format!("{}", model), "riffle, strip, box (clumping 0.30, cut variance 0.10)");
+
+        let model = ShuffleModel::fetch_table(&Value::Null);
+        assert_eq!(model, ShuffleModel::default());
+        assert_eq!(format!("{}", model), "random");
+        assert!(ShuffleModel::fetch_table(&json!({ "csm": true })).csm);
+    }
+
+    #[test]
+    fn test_serde_round_trip() {
+        let model = ShuffleModel::fetch_table(&json!({ "deterministic": true, "steps": ["riffle", "box"], "clumping": 0.5, "stripPackets": 4 }));
+        let value = serde_json::to_value(&model).unwrap();
+        assert_eq!(value["steps"], json!(["riffle", "box"]));
+        assert_eq!(value["stripPackets"], 4);
+        assert_eq!(serde_json::from_value::<ShuffleModel>(value).unwrap(), model);
+    }
+
+    #[test]
+    fn test_steps_keep_the_cards() {
+        for step in [ShuffleStep::Riffle, ShuffleStep::Strip, ShuffleStep::Box] {
+            let model = ShuffleModel {
+                steps: vec![step],
+                clumping: 0.5,
+                ..Default::default()
+            };
+            let original = deck();
+            let mut cards = original.clone();
+            model.shuffle(&mut cards);
+            assert!(same_cards(&original, &cards), "{:?}", step);
+            assert_ne!(original, cards, "{:?}", step);
+        }
+    }
+
+    #[test]
+    fn test_nominal_order() {
+        let model = ShuffleModel {
+            steps: vec![ShuffleStep::Riffle, ShuffleStep::Box],
+            box_piles: 2,
+            ..Default::default()
+        };
+        assert_eq!(model.nominal_order(6), vec![4, 2, 5, 0, 3, 1]);
+        assert_eq!(ShuffleModel::default().nominal_order(4), vec![0, 1, 2, 3]);
+
+        // Without imperfections a deterministic shuffle is its nominal order
+        let model = ShuffleModel {
+            deterministic: true,
+            cut_variance: 0.0,
+            steps: vec![ShuffleStep::Riffle, ShuffleStep::Strip, ShuffleStep::Riffle],
+            ..Default::default()
+        };
+        let mut order: Vec<usize> = (0..52).collect();
+        model.shuffle(&mut order);
+        assert_eq!(order, model.nominal_order(52));
+    }
+
+    #[test]
+    fn test_box_cut() {
+        let model = ShuffleModel::default();
+        let original = deck();
+        let mut cards = original.clone();
+        model.box_cut(&mut cards);
+        assert_eq!(cards[..13], original[39..]);
+        assert_eq!(cards[39..], original[..13]);
+    }
+
+    #[test]
+    fn test_full_clumping_riffle_keeps_halves_together() {
+        let model = ShuffleModel {
+            steps: vec![ShuffleStep::Riffle],
+            clumping: 1.0,
+            cut_variance: 0.0,
+            ..Default::default()
+        };
+        let original = deck();
+        let mut cards = original.clone();
+        model.shuffle(&mut cards);
+        assert!(cards == original || (cards[..26] == original[26..] && cards[26..] == original[..26]));
+    }
+}
@@ -0,0 +1,244 @@
+use crate::cards::{Rank, Suit, card::Card, hand::Hand};
+use crate::constants::constants::{
+    BUSTER_PAYS, LUCKY_LADIES_PAYS, PERFECT_PAIRS_PAYS, SIDE_BET_BUSTER, SIDE_BET_LUCKY_LADIES, SIDE_BET_PERFECT_PAIRS, SIDE_BET_TWENTY_ONE_PLUS_THREE, TWENTY_ONE_PLUS_THREE_PAYS,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+
+// Pay tables of the side bets in play, each outcome pays to one and anything else loses the unit bet
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(from = "Value", into = "Value")]
+pub struct SideBets {
+    tables: BTreeMap<String, BTreeMap<String, usize>>,
+}
+
+impl SideBets {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Load the side bets in the form { "perfect-pairs": true, "21+3": { "flush": 4 }, ... }
+    pub fn fetch_table(data: &Value) -> Self {
+        let mut side_bets = Self::new();
+        if let Some(map) = data.as_object() {
+            for (name, value) in map.iter() {
+                let defaults: &[(&str, usize)] = match name.as_str() {
+                    SIDE_BET_PERFECT_PAIRS => &PERFECT_PAIRS_PAYS,
+                    SIDE_BET_TWENTY_ONE_PLUS_THREE => &TWENTY_ONE_PLUS_THREE_PAYS,
+                    SIDE_BET_LUCKY_LADIES => &LUCKY_LADIES_PAYS,
+                    SIDE_BET_BUSTER => &BUSTER_PAYS,
+                    _ => continue,
+                };
+                if value.as_bool() == Some(false) {
+                    continue;
+                }
+
+                let mut table: BTreeMap<String, usize> = defaults.iter().map(|(outcome, pays)| (outcome.to_string(), *pays)).collect();
+                if let Some(pays) = value.as_object() {
+                    for (outcome, pay) in pays.iter() {
+                        if let (true, Some(pay)) = (table.contains_key(outcome), pay.as_u64()) {
+                            table.insert(outcome.clone(), pay as usize);
+                        }
+                    }
+                }
+                side_bets.tables.insert(name.clone(), table);
+            }
+        }
+        side_bets
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tables.is_empty()
+    }
+
+    pub fn has(&self, name: &str) -> bool {
+        self.tables.contains_key(name)
+    }
+
+    // Amount won on a unit bet, None when the side bet is not in play
+    pub fn settle(&self, name: &str, outcome: Option<&str>) -> Option<isize> {
+        let table = self.tables.get(name)?;
+        Some(outcome.and_then(|outcome| table.get(outcome)).map_or(-1, |pays| *pays as isize))
+    }
+
+    // Player's first two cards
+    pub fn perfect_pairs(first: &Card, second: &Card) -> Option<&'static str> {
+        if first.rank != second.rank {
+            None
+        } else if first.suit == second.suit {
+            Some("perfect-pair")
+        } else if first.suit.is_red() == second.suit.is_red() {
+            Some("colored-pair")
+        } else {
+            Some("mixed-pair")
+        }
+    }
+
+    // Player's first two cards and the dealer up card as a three card poker hand
+    pub fn twenty_one_plus_three(first: &Card, second: &Card, up: &Card) -> Option<&'static str> {
+        let cards = [first, second, up];
+        let flush = cards.iter().all(|card| card.suit == first.suit);
+        let trips = cards.iter().all(|card| card.rank == first.rank);
+        let mut ranks: Vec<usize> = cards.iter().map(|card| card.rank as usize).collect();
+        ranks.sort();
+        let straight = (ranks[1] == ranks[0] + 1 && ranks[2] == ranks[1] + 1) || ranks == [Rank::Two as usize, Rank::Three as usize, Rank::Ace as usize];
+
+        match (trips, straight, flush) {
+            (true, _, true) => Some("suited-trips"),
+            (_, true, true) => Some("straight-flush"),
+            (true, _, _) => Some("three-of-a-kind"),
+            (_, true, _) => Some("straight"),
+            (_, _, true) => Some("flush"),
+            _ => None,
+        }
+    }
+
+    // Player's first two cards totalling 20, the top award needs a dealer blackjack
+    pub fn lucky_ladies(first: &Card, second: &Card, dealer_blackjack: bool) -> Option<&'static str> {
+        if first.rank.value() + second.rank.value() != 20 {
+            return None;
+        }
+        let queen_of_hearts = |card: &Card| card.rank == Rank::Queen && card.suit == Suit::Hearts;
+        if queen_of_hearts(first) && queen_of_hearts(second) {
+            Some(if dealer_blackjack { "queen-hearts-blackjack" } else { "queen-hearts" })
+        } else if first == second {
+            Some("matched-20")
+        } else if first.suit == second.suit {
+            Some("suited-20")
+        } else {
+            Some("any-20")
+        }
+    }
+
+    // Dealer busts, paid by the number of cards in the busted hand
+    pub fn buster(dealer: &Hand) -> Option<String> {
+        if !dealer.is_busted() {
+            return None;
+        }
+        Some(dealer.cards.len().clamp(3, 8).to_string())
+    }
+
+    pub fn to_json_object(&self) -> Value {
+        let mut map = Map::new();
+        for (name, table) in &self.tables {
+            let pays: Map<String, Value> = table.iter().map(|(outcome, pays)| (outcome.clone(), Value::from(*pays))).collect();
+            map.insert(name.clone(), Value::Object(pays));
+        }
+        Value::Object(map)
+    }
+}
+
+impl From<Value> for SideBets {
+    fn from(data: Value) -> Self {
+        Self::fetch_table(&data)
+    }
+}
+
+impl From<SideBets> for Value {
+    fn from(side_bets: SideBets) -> Self {
+        side_bets.to_json_object()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn card(rank: Rank, suit: Suit) -> Card {
+        Card::new(rank, suit)
+    }
+
+    #[test]
+    fn test_fetch_table() {
+        let side_bets = SideBets::fetch_table(&json!({ "perfect-pairs": true, "21+3": { "flush": 4, "bogus": 9 }, "buster": false, "unknown": true }));
+        assert!(side_bets.has(SIDE_BET_PERFECT_PAIRS));
+        assert!(side_bets.has(SIDE_BET_TWENTY_ONE_PLUS_THREE));
+        assert!(!side_bets.has(SIDE_BET_BUSTER));
+        assert!(!side_bets.has("unknown"));
+        assert_eq!(side_bets.settle(SIDE_BET_TWENTY_ONE_PLUS_THREE, Some("flush")), Some(4));
+        assert_eq!(side_bets.settle(SIDE_BET_TWENTY_ONE_PLUS_THREE, Some("straight")), Some(10));
+        assert_eq!(side_bets.settle(SIDE_BET_PERFECT_PAIRS, None), Some(-1));
+        assert_eq!(side_bets.settle(SIDE_BET_BUSTER, Some("3")), None);
+        assert_eq!(side_bets.to_json_object()["21+3"].as_object().unwrap().len(), 5);
+        assert!(SideBets::fetch_table(&Value::Null).is_empty());
+    }
+
+    #[test]
+    fn test_serde_round_trip() {
+        let side_bets: SideBets = serde_json::from_value(json!({ "perfect-pairs": true, "buster": { "8": 300 } })).unwrap();
+        assert_eq!(side_bets.settle(SIDE_BET_BUSTER, Some("8")), Some(300));
+        let value = serde_json::to_value(&side_bets).unwrap();
+        assert_eq!(value["buster"]["8"], 300);
+        assert_eq!(serde_json::from_value::<SideBets>(value).unwrap(), side_bets);
+    }
+
+    #[test]
+    fn test_perfect_pairs() {
+        assert_eq!(
+            SideBets::perfect_pairs(&card(Rank::Eight, Suit::Clubs), &card(Rank::Eight, Suit::Clubs)),
+            Some("perfect-pair")
+        );
+        assert_eq!(
+            SideBets::perfect_pairs(&card(Rank::Eight, Suit::Clubs), &card(Rank::Eight, Suit::Spades)),
+            Some("colored-pair")
+        );
+        assert_eq!(
+            SideBets::perfect_pairs(&card(Rank::Eight, Suit::Clubs), &card(Rank::Eight, Suit::Hearts)),
+            Some("mixed-pair")
+        );
+        assert_eq!(SideBets::perfect_pairs(&card(Rank::King, Suit::Clubs), &card(Rank::Queen, Suit::Clubs)), None);
+    }
+
+    #[test]
+    fn test_twenty_one_plus_three() {
+        let cases = [
+            ((Rank::Seven, Suit::Hearts), (Rank::Seven, Suit::Hearts), (Rank::Seven, Suit::Hearts), Some("suited-trips")),
+            ((Rank::Ace, Suit::Hearts), (Rank::Two, Suit::Hearts), (Rank::Three, Suit::Hearts), Some("straight-flush")),
+            (
+                (Rank::Seven, Suit::Hearts),
+                (Rank::Seven, Suit::Clubs),
+                (Rank::Seven, Suit::Hearts),
+                Some("three-of-a-kind"),
+            ),
+            ((Rank::Queen, Suit::Hearts), (Rank::King, Suit::Clubs), (Rank::Ace, Suit::Hearts), Some("straight")),
+            ((Rank::Two, Suit::Spades), (Rank::Nine, Suit::Spades), (Rank::King, Suit::Spades), Some("flush")),
+            ((Rank::King, Suit::Spades), (Rank::Ace, Suit::Spades), (Rank::Two, Suit::Hearts), None),
+        ];
+        for (first, second, up, expected) in cases {
+            assert_eq!(
+                SideBets::twenty_one_plus_three(&card(first.0, first.1), &card(second.0, second.1), &card(up.0, up.1)),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_lucky_ladies() {
+        let queen = card(Rank::Queen, Suit::Hearts);
+        assert_eq!(SideBets::lucky_ladies(&queen, &queen, true), Some("queen-hearts-blackjack"));
+        assert_eq!(SideBets::lucky_ladies(&queen, &queen, false), Some("queen-hearts"));
+        assert_eq!(
+            SideBets::lucky_ladies(&card(Rank::King, Suit::Clubs), &card(Rank::King, Suit::Clubs), false),
+            Some("matched-20")
+        );
+        assert_eq!(
+            SideBets::lucky_ladies(&card(Rank::Ace, Suit::Clubs), &card(Rank::Nine, Suit::Clubs), false),
+            Some("suited-20")
+        );
+        assert_eq!(SideBets::lucky_ladies(&card(Rank::Ten, Suit::Clubs), &queen, false), Some("any-20"));
+        assert_eq!(SideBets::lucky_ladies(&card(Rank::Ten, Suit::Clubs), &card(Rank::Nine, Suit::Clubs), false), None);
+    }
+
+    #[test]
+    fn test_buster() {
+        let mut hand = Hand::new();
+        for rank in [Rank::Ten, Rank::Six] {
+            hand.add_card(card(rank, Suit::Clubs));
+        }
+        assert_eq!(SideBets::buster(&hand), None);
+        hand.add_card(card(Rank::Nine, Suit::Clubs));
+        assert_eq!(SideBets::buster(&hand).as_deref(), Some("3"));
+    }
+}
@@ -17,6 +17,31 @@ impl Suit {
     }
 }
 
+impl Suit {
+    pub fn key(&self) -> char {
+        match self {
+            Suit::Hearts => 'h',
+            Suit::Diamonds => 'd',
+            Suit::Clubs => 'c',
+            Suit::Spades => 's',
+        }
+    }
+
+    pub fn from_key(key: char) -> Option<Suit> {
+        match key.to_ascii_lowercase() {
+            'h' => Some(Suit::Hearts),
+            'd' => Some(Suit::Diamonds),
+            'c' => Some(Suit::Clubs),
+            's' => Some(Suit::Spades),
+            _ => None,
+        }
+    }
+
+    pub fn is_red(&self) -> bool {
+        matches!(self, Suit::Hearts | Suit::Diamonds)
+    }
+}
+
 // Implementing Iterator for Suit
 impl Suit {
     pub fn iter() -> impl Iterator<Item = Suit> {
@@ -35,4 +60,22 @@ mod tests {
         assert_eq!(Suit::Clubs.to_string(), "Clubs");
         assert_eq!(Suit::Spades.to_string(), "Spades");
     }
+
+    #[test]
+    fn test_suit_is_red() {
+        assert!(Suit::Hearts.is_red());
+        assert!(Suit::Diamonds.is_red());
+        assert!(!Suit::Clubs.is_red());
+        assert!(!Suit::Spades.is_red());
+    }
+
+    #[test]
+    fn test_suit_from_key() {
+        assert_eq!(Suit::from_key('h'), Some(Suit::Hearts));
+        assert_eq!(Suit::from_key('D'), Some(Suit::Diamonds));
+        assert_eq!(Suit::from_key('c'), Some(Suit::Clubs));
+        assert_eq!(Suit::from_key('s'), Some(Suit::Spades));
+        assert_eq!(Suit::from_key('x'), None);
+        assert!(Suit::iter().all(|suit| Suit::from_key(suit.key()) == Some(suit)));
+    }
 }
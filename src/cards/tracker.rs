@@ -0,0 +1,64 @@
+use crate::cards::card::Card;
+use crate::constants::constants::TRACKER_KEY_CARD_WINDOW;
+
+// Ace sequencing: follow the discards through the known shuffle procedure and remember the card expected just before each ace
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct Tracker {
+    key_cards: Vec<(Card, usize)>,
+    ace_next: bool,
+}
+
+impl Tracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // The discards in the order they were dealt and where the shuffle procedure nominally moves each of them
+    pub fn track(&mut self, discards: &[Card], order: &[usize]) {
+        self.key_cards.clear();
+        self.ace_next = false;
+        for position in 1..order.len() {
+            if discards[order[position]].is_ace() {
+                self.key_cards.push((discards[order[position - 1]].clone(), position - 1));
+            }
+        }
+    }
+
+    // A key card dealt close to where it was expected predicts an ace as the next card
+    pub fn observe(&mut self, card: &Card, position: usize) {
+        self.ace_next = self
+            .key_cards
+            .iter()
+            .any(|(key, expected)| key == card && position.abs_diff(*expected) <= TRACKER_KEY_CARD_WINDOW);
+    }
+
+    pub fn ace_next(&self) -> bool {
+        self.ace_next
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::{Rank, Suit};
+
+    #[test]
+    fn test_track_and_observe() {
+        let discards = vec![
+            Card::new(Rank::Ace, Suit::Hearts),
+            Card::new(Rank::Two, Suit::Hearts),
+            Card::new(Rank::Three, Suit::Hearts),
+            Card::new(Rank::Four, Suit::Hearts),
+        ];
+        let mut tracker = Tracker::new();
+        tracker.track(&discards, &[2, 0, 3, 1]);
+        assert_eq!(tracker.key_cards, vec![(discards[2].clone(), 0)]);
+
+        tracker.observe(&discards[1], 0);
+        assert!(!tracker.ace_next());
+        tracker.observe(&discards[2], 0);
+        assert!(tracker.ace_next());
+        tracker.observe(&discards[2], TRACKER_KEY_CARD_WINDOW + 1);
+        assert!(!tracker.ace_next());
+    }
+}
@@ -1,4 +1,5 @@
 use crate::cards::hand::Hand;
+use crate::errors::errors::StrikerError;
 
 #[derive(Debug, Clone)]
 pub struct Wager {
@@ -9,6 +10,10 @@ pub struct Wager {
     pub amount_won: isize,
     pub insurance_bet: usize,
     pub insurance_won: isize,
+    pub doubled: bool,
+    pub surrendered: bool,
+    pub free_bet: usize,
+    pub switched: bool,
 }
 
 impl Wager {
@@ -21,6 +26,10 @@ impl Wager {
             amount_won: 0,
             insurance_bet: 0,
             insurance_won: 0,
+            doubled: false,
+            surrendered: false,
+            free_bet: 0,
+            switched: false,
         }
     }
 
@@ -30,6 +39,10 @@ impl Wager {
         self.amount_won = 0;
         self.insurance_bet = 0;
         self.insurance_won = 0;
+        self.doubled = false;
+        self.surrendered = false;
+        self.free_bet = 0;
+        self.switched = false;
     }
 
     pub fn place_insurance_bet(&mut self) {
@@ -38,20 +51,67 @@ impl Wager {
 
     pub fn double_bet(&mut self) {
         self.amount_bet *= 2;
+        self.doubled = true;
+    }
+
+    // Free Bet: the house matches the whole hand, the player risks nothing more
+    pub fn free_double(&mut self) {
+        let stake = self.amount_bet + self.free_bet;
+        self.free_bet += stake;
+        self.doubled = true;
+    }
+
+    // Free Bet: the split hand is played on house money
+    pub fn free_split(&mut self) {
+        self.free_bet += self.amount_bet;
+        self.amount_bet = 0;
+    }
+
+    // A two card 21 made by switching cards is not a blackjack
+    pub fn is_blackjack(&self) -> bool {
+        self.hand.is_blackjack() && !self.switched
+    }
+
+    // The bet before any double down, a free double is matched by the house and leaves the player's bet as it was
+    pub fn original_bet(&self) -> usize {
+        if self.doubled && self.free_bet == 0 { self.amount_bet / 2 } else { self.amount_bet }
     }
 
     pub fn won_blackjack(&mut self, pays: usize, bet: usize) {
         self.amount_won = ((self.amount_bet * pays) / bet) as isize;
     }
 
+    // Bonus payouts for special hands, paid as pays to bets
+    pub fn won_bonus(&mut self, pays: usize, bets: usize) {
+        self.amount_won = ((self.amount_bet * pays) / bets) as isize;
+    }
+
+    pub fn surrender(&mut self) {
+        self.surrendered = true;
+    }
+
+    // A surrendered hand gives up the original bet, half of it when it was not doubled
+    pub fn lost_surrender(&mut self) {
+        if self.doubled {
+            self.lost_amount(self.original_bet());
+        } else {
+            self.lost_amount(self.amount_bet / 2);
+        }
+    }
+
     pub fn won(&mut self) {
-        self.amount_won = self.amount_bet as isize;
+        self.amount_won = (self.amount_bet + self.free_bet) as isize;
     }
 
     pub fn lost(&mut self) {
         self.amount_won = -(self.amount_bet as isize);
     }
 
+    // Lose only part of the bet, the rest is returned
+    pub fn lost_amount(&mut self, amount: usize) {
+        self.amount_won = -(amount.min(self.amount_bet) as isize);
+    }
+
     pub fn push(&mut self) {
         // No action needed
     }
@@ -64,14 +124,12 @@ impl Wager {
         self.insurance_won = -(self.insurance_bet as isize);
     }
 
-    pub fn split_hand(&mut self, split: &mut Wager) {
-        if !self.hand.is_pair() {
-            panic!("Cannot split a non-pair hand");
-        }
-
+    pub fn split_hand(&mut self, split: &mut Wager) -> Result<(), StrikerError> {
+        let split_card = self.hand.split_pair()?;
         split.amount_bet = self.amount_bet;
-        let split_card = self.hand.split_pair().expect("Hand must be a pair to split");
-        split.hand.draw_card(Some(split_card)); // Draw the split card into the new wager
+        split.free_bet = self.free_bet;
+        split.hand.add_card(split_card); // Draw the split card into the new wager
+        Ok(())
     }
 }
 
@@ -128,6 +186,101 @@ mod tests {
 
         wager.double_bet();
         assert_eq!(wager.amount_bet, DOUBLE_MIN_BET);
+        assert!(wager.doubled);
+        assert_eq!(wager.original_bet(), MINIMUM_BET);
+    }
+
+    #[test]
+    fn test_surrender_and_rescue() {
+        let mut wager = new_test_wager();
+        wager.place_bet(DOUBLE_MIN_BET);
+        wager.surrender();
+        assert!(wager.surrendered);
+        wager.lost_surrender();
+        assert_eq!(wager.amount_won, -(MINIMUM_BET as isize));
+
+        wager.place_bet(DOUBLE_MIN_BET);
+        assert!(!wager.surrendered);
+        wager.double_bet();
+        wager.surrender();
+        wager.lost_surrender();
+        assert_eq!(wager.amount_won, -(DOUBLE_MIN_BET as isize));
+    }
+
+    #[test]
+    fn test_won_bonus() {
+        let mut wager = new_test_wager();
+        wager.place_bet(DOUBLE_MIN_BET);
+        wager.won_bonus(3, 1);
+        assert_eq!(wager.amount_won, (DOUBLE_MIN_BET * 3) as isize);
+    }
+
+    #[test]
+    fn test_lost_amount() {
+        let mut wager = new_test_wager();
+        wager.place_bet(MINIMUM_BET);
+        wager.double_bet();
+        wager.lost_amount(wager.original_bet());
+        assert_eq!(wager.amount_won, -(MINIMUM_BET as isize));
+
+        wager.lost_amount(DOUBLE_MIN_BET * 2);
+        assert_eq!(wager.amount_won, -(DOUBLE_MIN_BET as isize));
+
+        wager.place_bet(MINIMUM_BET);
+        assert!(!wager.doubled);
+    }
+
+    #[test]
+    fn test_free_double_and_split() {
+        let mut wager = new_test_wager();
+        wager.place_bet(DOUBLE_MIN_BET);
+        wager.free_double();
+        assert!(wager.doubled);
+        assert_eq!(wager.amount_bet, DOUBLE_MIN_BET);
+        assert_eq!(wager.original_bet(), DOUBLE_MIN_BET);
+        wager.won();
+        assert_eq!(wager.amount_won, (DOUBLE_MIN_BET * 2) as isize);
+        wager.lost();
+        assert_eq!(wager.amount_won, -(DOUBLE_MIN_BET as isize));
+
+        wager.place_bet(DOUBLE_MIN_BET);
+        assert_eq!(wager.free_bet, 0);
+        wager.free_split();
+        wager.lost();
+        assert_eq!(wager.amount_won, 0);
+        wager.won();
+        assert_eq!(wager.amount_won, DOUBLE_MIN_BET as isize);
+    }
+
+    #[test]
+    fn test_free_double_rescue() {
+        // Rescuing a free double gives up the player's whole bet, the house's half was never at risk for the player
+        let mut wager = new_test_wager();
+        wager.place_bet(DOUBLE_MIN_BET);
+        wager.free_double();
+        wager.surrender();
+        wager.lost_surrender();
+        assert_eq!(wager.amount_won, -(DOUBLE_MIN_BET as isize));
+
+        // A free split hand doubled for free risks nothing of the player's
+        wager.place_bet(DOUBLE_MIN_BET);
+        wager.free_split();
+        wager.free_double();
+        assert_eq!(wager.original_bet(), 0);
+        wager.surrender();
+        wager.lost_surrender();
+        assert_eq!(wager.amount_won, 0);
+    }
+
+    #[test]
+    fn test_switched_blackjack() {
+        let mut wager = new_test_wager();
+        wager.place_bet(DOUBLE_MIN_BET);
+        wager.hand.add_card(Card::new(Rank::Ace, Suit::Hearts));
+        wager.hand.add_card(Card::new(Rank::King, Suit::Spades));
+        assert!(wager.is_blackjack());
+        wager.switched = true;
+        assert!(!wager.is_blackjack());
     }
 
     #[test]
@@ -180,11 +333,11 @@ mod tests {
 
         let ace1 = Card::new(Rank::Ace, Suit::Hearts);
         let ace2 = Card::new(Rank::Ace, Suit::Spades);
-        wager.hand.draw_card(Some(ace1.clone()));
-        wager.hand.draw_card(Some(ace2));
+        wager.hand.add_card(ace1.clone());
+        wager.hand.add_card(ace2);
 
         let mut split_wager = new_test_wager();
-        wager.split_hand(&mut split_wager);
+        wager.split_hand(&mut split_wager).unwrap();
 
         assert_eq!(wager.hand.total, 11);
         assert_eq!(split_wager.hand.total, 11);
@@ -193,14 +346,15 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(expected = "Cannot split a non-pair hand")]
-    fn test_split_hand_invalid_pair_panics() {
+    fn test_split_hand_invalid_pair_is_an_error() {
         let mut wager = new_test_wager();
         wager.place_bet(DOUBLE_MIN_BET);
-        wager.hand.draw_card(Some(Card::new(Rank::Ace, Suit::Hearts)));
-        wager.hand.draw_card(Some(Card::new(Rank::King, Suit::Spades)));
+        wager.hand.add_card(Card::new(Rank::Ace, Suit::Hearts));
+        wager.hand.add_card(Card::new(Rank::King, Suit::Spades));
 
         let mut split_wager = new_test_wager();
-        wager.split_hand(&mut split_wager);
+        assert!(matches!(wager.split_hand(&mut split_wager), Err(StrikerError::NotAPair(_))));
+        assert!(split_wager.hand.cards.is_empty());
+        assert_eq!(split_wager.amount_bet, 0);
     }
 }
@@ -11,12 +11,25 @@ pub const NUMBER_OF_HANDS_MAXIMUM: usize = 10 * BILLION;
 pub const NUMBER_OF_HANDS_MINIMUM: usize = 100;
 pub const NUMBER_OF_HANDS_DEFAULT: usize = 500 * MILLION;
 pub const NUMBER_OF_HANDS_DATABASE: usize = 10 * MILLION;
+pub const NUMBER_OF_HANDS_BATCH: usize = 10000;
+pub const NUMBER_OF_BATCHES_PER_THREAD: usize = 8;
+
+// Stopping once the standard error of the advantage, in percent, is under a target and enough rounds back it
+pub const TARGET_STDERR_MINIMUM: f64 = 0.0001;
+pub const TARGET_STDERR_MAXIMUM: f64 = 10.0;
+pub const TARGET_STDERR_MINIMUM_ROUNDS: usize = 10000;
 pub const NUMBER_OF_CARDS_IN_DECK: usize = 52;
-pub const NUMBER_OF_CORES_MINIMUM: usize = 1;
-pub const NUMBER_OF_CORES_PHYSICAL: usize = 24;
-pub const NUMBER_OF_CORES_LOGICAL: usize = 32;
-pub const NUMBER_OF_CORES_DEFAULT: usize = NUMBER_OF_CORES_PHYSICAL;
-pub const NUMBER_OF_CORES_MAXIMUM: usize = NUMBER_OF_CORES_LOGICAL;
+pub const NUMBER_OF_CARDS_IN_SPANISH_DECK: usize = 48;
+pub const NUMBER_OF_THREADS_MINIMUM: usize = 1;
+pub const NUMBER_OF_THREADS_MAXIMUM: usize = 1024;
+pub const NUMBER_OF_THREADS_AUTO: &str = "auto";
+
+// Commands, simulate unless another one is given first
+pub const COMMAND_SIMULATE: &str = "simulate";
+pub const COMMAND_PLAY: &str = "play";
+pub const COMMAND_TRAIN: &str = "train";
+pub const COMMAND_SWEEP: &str = "sweep";
+pub const COMMAND_REPORT: &str = "report";
 
 //
 pub const STRATEGY_MIMIC: &str = "mimic";
@@ -29,12 +42,90 @@ pub const STRATEGY_WONG: &str = "wong";
 pub const DECKS_SINGLE_DECK: &str = "single-deck";
 pub const DECKS_DOUBLE_DECK: &str = "double-deck";
 pub const DECKS_SIX_SHOE: &str = "six-shoe";
+pub const DECKS_SPANISH_21: &str = "spanish-21";
+
+// No hole card losses on a dealer blackjack
+pub const NO_HOLE_CARD_ALL: &str = "all";
+pub const NO_HOLE_CARD_OBO: &str = "obo";
+pub const NO_HOLE_CARD_OBBO: &str = "obbo";
+
+// Spanish 21 bonus payouts as (pays, bets)
+pub const SPANISH_21_FIVE_CARD_21: (usize, usize) = (3, 2);
+pub const SPANISH_21_SIX_CARD_21: (usize, usize) = (2, 1);
+pub const SPANISH_21_SEVEN_CARD_21: (usize, usize) = (3, 1);
+pub const SPANISH_21_MIXED_21: (usize, usize) = (3, 2);
+pub const SPANISH_21_SUITED_21: (usize, usize) = (2, 1);
+pub const SPANISH_21_SPADED_21: (usize, usize) = (3, 1);
+
+// Advantage play techniques that reveal the dealer hole card or the next card
+pub const ADVANTAGE_PLAY_HOLE_CARD: &str = "hole-card";
+pub const ADVANTAGE_PLAY_WARP: &str = "warp";
+pub const ADVANTAGE_PLAY_FIRST_BASE: &str = "first-base";
+pub const ADVANTAGE_PLAY_ACE_SEQUENCING: &str = "ace-sequencing";
+pub const TRACKER_KEY_CARD_WINDOW: usize = 8;
+
+// Shuffle models, a human shuffle is a sequence of riffle, strip and box steps
+pub const SHUFFLE_RIFFLE: &str = "riffle";
+pub const SHUFFLE_STRIP: &str = "strip";
+pub const SHUFFLE_BOX: &str = "box";
+pub const SHUFFLE_CUT_VARIANCE: f64 = 0.1;
+pub const SHUFFLE_STRIP_PACKETS: usize = 6;
+pub const SHUFFLE_BOX_PILES: usize = 4;
+pub const BURN_CARDS_DEFAULT: usize = 1;
+
+// Side bet names and default pay tables as (outcome, pays to one)
+pub const SIDE_BET_PERFECT_PAIRS: &str = "perfect-pairs";
+pub const SIDE_BET_TWENTY_ONE_PLUS_THREE: &str = "21+3";
+pub const SIDE_BET_LUCKY_LADIES: &str = "lucky-ladies";
+pub const SIDE_BET_BUSTER: &str = "buster";
+pub const PERFECT_PAIRS_PAYS: [(&str, usize); 3] = [("perfect-pair", 25), ("colored-pair", 12), ("mixed-pair", 6)];
+pub const TWENTY_ONE_PLUS_THREE_PAYS: [(&str, usize); 5] = [("suited-trips", 100), ("straight-flush", 40), ("three-of-a-kind", 30), ("straight", 10), ("flush", 5)];
+pub const LUCKY_LADIES_PAYS: [(&str, usize); 5] = [("queen-hearts-blackjack", 1000), ("queen-hearts", 125), ("matched-20", 19), ("suited-20", 9), ("any-20", 4)];
+pub const BUSTER_PAYS: [(&str, usize); 6] = [("3", 1), ("4", 2), ("5", 9), ("6", 50), ("7", 100), ("8", 250)];
+
+// Hand history, actions logged per round and how many rounds to skip between logged ones
+pub const ACTION_HIT: &str = "hit";
+pub const ACTION_STAND: &str = "stand";
+pub const ACTION_DOUBLE: &str = "double";
+pub const ACTION_SPLIT: &str = "split";
+pub const ACTION_SURRENDER: &str = "surrender";
+pub const ACTION_INSURANCE: &str = "insurance";
+pub const ACTION_SWITCH: &str = "switch";
+pub const HAND_HISTORY_SAMPLE_MINIMUM: usize = 1;
+pub const HAND_HISTORY_SAMPLE_MAXIMUM: usize = BILLION;
+
+// Trainer drills, how many questions each, cards per count drill and how long each card is shown
+pub const TRAINER_QUESTIONS: usize = 10;
+pub const TRAINER_DRILL_CARDS: usize = 20;
+pub const TRAINER_FLASH_MILLISECONDS: u64 = 700;
+pub const TRAINER_USER_DEFAULT: &str = "player";
+pub const TRAINER_PROGRESS_FILE: &str = "striker-progress.json";
+
+// Sweeps, where the per cell reports and the comparison table go, and the table's file name
+pub const SWEEP_OUTPUT_DEFAULT: &str = "striker-sweep";
+pub const SWEEP_COMPARISON_FILE: &str = "comparison.csv";
+
+// Checkpoints, seconds between writes of the partial results
+pub const CHECKPOINT_INTERVAL_DEFAULT: usize = 300;
+pub const CHECKPOINT_INTERVAL_MINIMUM: usize = 1;
+pub const CHECKPOINT_INTERVAL_MAXIMUM: usize = 86400;
+
+// Saved reports, the p value under which two playbooks differ
+pub const REPORT_SIGNIFICANCE: f64 = 0.05;
 
 // Betting constants
 pub const MINIMUM_BET: usize = 2;
 pub const MAXIMUM_BET: usize = 20;
 pub const TRUE_COUNT_BET: usize = 2;
 pub const TRUE_COUNT_MULTIPLIER: usize = 26;
+pub const BET_RAMP_BANKROLL: usize = 1000;
+pub const BET_RAMP_KELLY_FRACTION: f64 = 0.5;
+pub const BET_RAMP_MINIMUM_HANDS: usize = 1000;
+
+// Index generation constants
+pub const INDEX_TRUE_COUNT_MINIMUM: isize = -10;
+pub const INDEX_TRUE_COUNT_MAXIMUM: isize = 10;
+pub const INDEX_MINIMUM_SAMPLES: usize = 100;
 
 #[cfg(test)]
 mod tests {
@@ -43,6 +134,11 @@ mod tests {
     #[test]
     fn test_constants_values() {
         assert_eq!(STRIKER_WHO_AM_I, "striker-rust");
+        assert_eq!(COMMAND_SIMULATE, "simulate");
+        assert_eq!(COMMAND_PLAY, "play");
+        assert_eq!(COMMAND_TRAIN, "train");
+        assert_eq!(COMMAND_SWEEP, "sweep");
+        assert_eq!(COMMAND_REPORT, "report");
         assert_eq!(STRIKER_VERSION, "v3.00.00");
         assert_eq!(TIME_LAYOUT, "%Y-%m-%d %H:%M:%S %z");
         assert_eq!(STATUS_ROUNDS, 1000000);
@@ -53,14 +149,68 @@ mod tests {
         assert_eq!(NUMBER_OF_HANDS_MINIMUM, 100);
         assert_eq!(NUMBER_OF_HANDS_DEFAULT, 500 * MILLION);
         assert_eq!(NUMBER_OF_HANDS_DATABASE, 10 * MILLION);
+        assert_eq!(NUMBER_OF_HANDS_BATCH, 10000);
+        assert_eq!(NUMBER_OF_BATCHES_PER_THREAD, 8);
+        assert_eq!(TARGET_STDERR_MINIMUM, 0.0001);
+        assert_eq!(TARGET_STDERR_MAXIMUM, 10.0);
+        assert_eq!(TARGET_STDERR_MINIMUM_ROUNDS, 10000);
         assert_eq!(NUMBER_OF_CARDS_IN_DECK, 52);
-        assert_eq!(NUMBER_OF_CORES_PHYSICAL, 24);
-        assert_eq!(NUMBER_OF_CORES_LOGICAL, 32);
-        assert_eq!(NUMBER_OF_CORES_DEFAULT, 24);
+        assert_eq!(NUMBER_OF_CARDS_IN_SPANISH_DECK, 48);
+        assert_eq!(NUMBER_OF_THREADS_MINIMUM, 1);
+        assert_eq!(NUMBER_OF_THREADS_MAXIMUM, 1024);
+        assert_eq!(NUMBER_OF_THREADS_AUTO, "auto");
 
         assert_eq!(MINIMUM_BET, 2);
         assert_eq!(MAXIMUM_BET, 20);
         assert_eq!(TRUE_COUNT_BET, 2);
         assert_eq!(TRUE_COUNT_MULTIPLIER, 26);
+        assert_eq!(BET_RAMP_BANKROLL, 1000);
+        assert_eq!(BET_RAMP_KELLY_FRACTION, 0.5);
+        assert_eq!(BET_RAMP_MINIMUM_HANDS, 1000);
+
+        assert_eq!(INDEX_TRUE_COUNT_MINIMUM, -10);
+        assert_eq!(INDEX_TRUE_COUNT_MAXIMUM, 10);
+        assert_eq!(INDEX_MINIMUM_SAMPLES, 100);
+
+        assert_eq!(ADVANTAGE_PLAY_HOLE_CARD, "hole-card");
+        assert_eq!(ADVANTAGE_PLAY_WARP, "warp");
+        assert_eq!(ADVANTAGE_PLAY_FIRST_BASE, "first-base");
+        assert_eq!(ADVANTAGE_PLAY_ACE_SEQUENCING, "ace-sequencing");
+        assert_eq!(TRACKER_KEY_CARD_WINDOW, 8);
+        assert_eq!(ACTION_HIT, "hit");
+        assert_eq!(ACTION_STAND, "stand");
+        assert_eq!(ACTION_DOUBLE, "double");
+        assert_eq!(ACTION_SPLIT, "split");
+        assert_eq!(ACTION_SURRENDER, "surrender");
+        assert_eq!(ACTION_INSURANCE, "insurance");
+        assert_eq!(ACTION_SWITCH, "switch");
+        assert_eq!(HAND_HISTORY_SAMPLE_MINIMUM, 1);
+        assert_eq!(HAND_HISTORY_SAMPLE_MAXIMUM, BILLION);
+        assert_eq!(TRAINER_QUESTIONS, 10);
+        assert_eq!(TRAINER_DRILL_CARDS, 20);
+        assert_eq!(TRAINER_FLASH_MILLISECONDS, 700);
+        assert_eq!(TRAINER_USER_DEFAULT, "player");
+        assert_eq!(TRAINER_PROGRESS_FILE, "striker-progress.json");
+        assert_eq!(SWEEP_OUTPUT_DEFAULT, "striker-sweep");
+        assert_eq!(SWEEP_COMPARISON_FILE, "comparison.csv");
+        assert_eq!(CHECKPOINT_INTERVAL_DEFAULT, 300);
+        assert_eq!(CHECKPOINT_INTERVAL_MINIMUM, 1);
+        assert_eq!(CHECKPOINT_INTERVAL_MAXIMUM, 86400);
+        assert_eq!(REPORT_SIGNIFICANCE, 0.05);
+
+        assert_eq!(SHUFFLE_RIFFLE, "riffle");
+        assert_eq!(SHUFFLE_STRIP, "strip");
+        assert_eq!(SHUFFLE_BOX, "box");
+        assert_eq!(SHUFFLE_CUT_VARIANCE, 0.1);
+        assert_eq!(SHUFFLE_STRIP_PACKETS, 6);
+        assert_eq!(SHUFFLE_BOX_PILES, 4);
+        assert_eq!(BURN_CARDS_DEFAULT, 1);
+
+        assert_eq!(SIDE_BET_PERFECT_PAIRS, "perfect-pairs");
+        assert_eq!(SIDE_BET_TWENTY_ONE_PLUS_THREE, "21+3");
+        assert_eq!(SIDE_BET_LUCKY_LADIES, "lucky-ladies");
+        assert_eq!(SIDE_BET_BUSTER, "buster");
+        assert_eq!(PERFECT_PAIRS_PAYS[0], ("perfect-pair", 25));
+        assert_eq!(BUSTER_PAYS[5], ("8", 250));
     }
 }
@@ -0,0 +1,124 @@
+use std::any::Any;
+use std::fmt;
+use std::io;
+
+// Everything that can go wrong loading the tables or playing the rounds, with enough context to find the cause
+#[derive(Debug)]
+pub enum StrikerError {
+    MissingUrl(&'static str),
+    Fetch { url: String, message: String },
+    Send { url: String, message: String },
+    MissingField { table: String, field: &'static str },
+    ConflictingRules { table: String, message: &'static str },
+    UnknownChart(String),
+    MissingChartValue { chart: String, key: String, up: usize },
+    OutOfCards,
+    NotAPair(String),
+    File { action: &'static str, path: String, message: String },
+    InvalidCard { token: String, line: usize },
+    EmptyReplay(String),
+    InvalidGrid(String),
+    Checkpoint(String),
+    InvalidReport(String),
+    WorkerPanic(String),
+    Io(io::Error),
+}
+
+impl fmt::Display for StrikerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StrikerError::MissingUrl(variable) => write!(f, "Missing {} in the environment", variable),
+            StrikerError::Fetch { url, message } => write!(f, "Cannot fetch {}: {}", url, message),
+            StrikerError::Send { url, message } => write!(f, "Cannot send to {}: {}", url, message),
+            StrikerError::MissingField { table, field } => write!(f, "Missing {} in the {} rules", field, table),
+            StrikerError::ConflictingRules { table, message } => write!(f, "Conflicting {} rules: {}", table, message),
+            StrikerError::UnknownChart(chart) => write!(f, "Unknown chart {}", chart),
+            StrikerError::MissingChartValue { chart, key, up } => write!(f, "Cannot find value in {} for {} vs {}", chart, key, up),
+            StrikerError::OutOfCards => write!(f, "The shoe ran out of cards"),
+            StrikerError::NotAPair(cards) => write!(f, "Cannot split a non-pair hand ({})", cards),
+            StrikerError::File { action, path, message } => write!(f, "Cannot {} {}: {}", action, path, message),
+            StrikerError::InvalidCard { token, line } => write!(f, "Invalid card '{}' on line {}", token, line),
+            StrikerError::EmptyReplay(path) => write!(f, "Replay file {} has no shoes", path),
+            StrikerError::InvalidGrid(message) => write!(f, "Invalid sweep grid: {}", message),
+            StrikerError::Checkpoint(message) => write!(f, "Cannot resume: {}", message),
+            StrikerError::InvalidReport(message) => write!(f, "Invalid report: {}", message),
+            StrikerError::WorkerPanic(message) => write!(f, "A worker thread panicked: {}", message),
+            StrikerError::Io(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for StrikerError {}
+
+impl From<io::Error> for StrikerError {
+    fn from(e: io::Error) -> Self {
+        StrikerError::Io(e)
+    }
+}
+
+impl StrikerError {
+    // A worker thread that panicked fails the run instead of quietly taking its share of the work with it
+    pub fn worker(payload: Box<dyn Any + Send>) -> Self {
+        let message = match payload.downcast::<String>() {
+            Ok(message) => *message,
+            Err(payload) => payload.downcast_ref::<&str>().map(|message| message.to_string()).unwrap_or_default(),
+        };
+        StrikerError::WorkerPanic(message)
+    }
+
+    pub fn file(action: &'static str, path: &str, e: impl fmt::Display) -> Self {
+        StrikerError::File {
+            action,
+            path: path.to_string(),
+            message: e.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let missing = StrikerError::MissingChartValue {
+            chart: "Hard Stand".to_string(),
+            key: "16".to_string(),
+            up: 10,
+        };
+        assert_eq!(missing.to_string(), "Cannot find value in Hard Stand for 16 vs 10");
+        assert_eq!(
+            StrikerError::MissingField {
+                table: "single-deck".to_string(),
+                field: "hitSoft17"
+            }
+            .to_string(),
+            "Missing hitSoft17 in the single-deck rules"
+        );
+        assert_eq!(
+            StrikerError::ConflictingRules {
+                table: "single-deck".to_string(),
+                message: "Blackjack Switch pays blackjack even money"
+            }
+            .to_string(),
+            "Conflicting single-deck rules: Blackjack Switch pays blackjack even money"
+        );
+        assert_eq!(StrikerError::MissingUrl("STRIKER_URL_RULES").to_string(), "Missing STRIKER_URL_RULES in the environment");
+        assert_eq!(StrikerError::file("open", "history.jsonl", "denied").to_string(), "Cannot open history.jsonl: denied");
+    }
+
+    #[test]
+    fn test_worker_panic() {
+        let error = std::thread::spawn(|| panic!("dealt {} cards", 53)).join().map_err(StrikerError::worker).unwrap_err();
+        assert!(matches!(&error, StrikerError::WorkerPanic(message) if message == "dealt 53 cards"));
+        let error = std::thread::spawn(|| panic!("boom")).join().map_err(StrikerError::worker).unwrap_err();
+        assert_eq!(error.to_string(), "A worker thread panicked: boom");
+    }
+
+    #[test]
+    fn test_from_io_error() {
+        let error: StrikerError = io::Error::other("closed").into();
+        assert!(matches!(error, StrikerError::Io(_)));
+        assert_eq!(error.to_string(), "closed");
+    }
+}
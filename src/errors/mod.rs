@@ -0,0 +1,2 @@
+// errors module
+pub mod errors;
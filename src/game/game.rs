@@ -0,0 +1,462 @@
+use crate::arguments::parameters::Parameters;
+use crate::cards::{card::Card, dealer::Dealer, hand::Hand, shoe::Shoe, wager::Wager};
+use crate::constants::constants::{ACTION_DOUBLE, ACTION_HIT, ACTION_SPLIT, ACTION_STAND, ACTION_SURRENDER, MAXIMUM_BET, MINIMUM_BET, STRATEGY_MIMIC};
+use crate::errors::errors::StrikerError;
+use crate::strategy::rules::Rules;
+use crate::strategy::strategy::Strategy;
+use std::io::{BufRead, Write};
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Action {
+    Hit,
+    Stand,
+    Double,
+    Split,
+    Surrender,
+}
+
+impl Action {
+    pub fn from_input(input: &str) -> Option<Action> {
+        match input.to_lowercase().as_str() {
+            "h" | ACTION_HIT => Some(Action::Hit),
+            "s" | ACTION_STAND => Some(Action::Stand),
+            "d" | ACTION_DOUBLE => Some(Action::Double),
+            "p" | ACTION_SPLIT => Some(Action::Split),
+            "r" | ACTION_SURRENDER => Some(Action::Surrender),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            Action::Hit => ACTION_HIT,
+            Action::Stand => ACTION_STAND,
+            Action::Double => ACTION_DOUBLE,
+            Action::Split => ACTION_SPLIT,
+            Action::Surrender => ACTION_SURRENDER,
+        }
+    }
+
+    pub fn prompt(&self) -> &str {
+        match self {
+            Action::Hit => "[h]it",
+            Action::Stand => "[s]tand",
+            Action::Double => "[d]ouble",
+            Action::Split => "s[p]lit",
+            Action::Surrender => "su[r]render",
+        }
+    }
+}
+
+// Interactive play at the terminal, every decision is checked against the loaded strategy
+pub struct Game {
+    rules: Rules,
+    strategy: Strategy,
+    mimic: bool,
+    shoe: Shoe,
+    dealer: Dealer,
+    seen_cards: [usize; 13],
+    pub bankroll: isize,
+    pub rounds: usize,
+    pub decisions: usize,
+    pub deviations: usize,
+}
+
+impl Game {
+    pub fn new(parameters: &Parameters, rules: &Rules, strategy: &Strategy) -> Self {
+        let mut shoe = if rules.spanish_21 {
+            Shoe::new_spanish_21(parameters.number_of_decks, rules.penetration)
+        } else {
+            Shoe::new(parameters.number_of_decks, rules.penetration)
+        };
+        shoe.set_shuffle_model(rules.shuffle_model.clone(), rules.burn_cards);
+        Self {
+            rules: rules.clone(),
+            strategy: strategy.clone(),
+            mimic: parameters.strategy == STRATEGY_MIMIC,
+            shoe,
+            dealer: Dealer::new(rules.hit_soft_17),
+            seen_cards: [0; 13],
+            bankroll: 0,
+            rounds: 0,
+            decisions: 0,
+            deviations: 0,
+        }
+    }
+
+    // Play rounds until the player quits or the input ends
+    pub fn run(&mut self, input: &mut impl BufRead, output: &mut impl Write) -> Result<(), StrikerError> {
+        writeln!(output, "Commands: [c]ount shows the running and true count, [q]uit ends the session")?;
+        while self.play_round(input, output)? {}
+        self.print_summary(output)
+    }
+
+    fn play_round(&mut self, input: &mut impl BufRead, output: &mut impl Write) -> Result<bool, StrikerError> {
+        if self.shoe.should_shuffle() {
+            self.shoe.shuffle();
+            self.seen_cards = [0; 13];
+            writeln!(output, "\n*** Shuffling ***")?;
+        }
+
+        let recommended = if self.mimic { MINIMUM_BET } else { self.strategy.get_bet(&self.seen_cards) };
+        let mut wager = Wager::new(MINIMUM_BET, MAXIMUM_BET);
+        wager.place_bet(recommended);
+        let recommended = wager.amount_bet;
+        let bet = loop {
+            let Some(line) = self.read_command(input, output, &format!("\nBet {}-{} (Enter for {})", MINIMUM_BET, MAXIMUM_BET, recommended))? else {
+                return Ok(false);
+            };
+            if line.is_empty() {
+                break recommended;
+            }
+            match line.parse::<usize>() {
+                Ok(bet) if (MINIMUM_BET..=MAXIMUM_BET).contains(&bet) => break bet,
+                _ => writeln!(output, "Bet between {} and {}", MINIMUM_BET, MAXIMUM_BET)?,
+            }
+        };
+        wager.place_bet(bet);
+        self.rounds += 1;
+
+        // Deal, the hole card stays hidden
+        self.dealer.hand.reset();
+        self.deal(&mut wager.hand)?;
+        let down = self.shoe.draw_card();
+        self.dealer.hand.draw_card(down)?;
+        self.deal(&mut wager.hand)?;
+        let up = self.shoe.draw_card().ok_or(StrikerError::OutOfCards)?;
+        self.dealer.hand.add_card(up.clone());
+        self.show(&up);
+        writeln!(output, "Dealer shows {}", up)?;
+        writeln!(output, "You have {}", Self::describe(&wager.hand))?;
+
+        if up.is_ace() {
+            let Some(line) = self.read_command(input, output, "Insurance? [y/n]")? else {
+                return Ok(false);
+            };
+            let insure = matches!(line.to_lowercase().as_str(), "y" | "yes");
+            let chart = !self.mimic && self.strategy.get_insurance(&self.seen_cards);
+            self.check_decision(
+                output,
+                if insure { "insure" } else { "decline insurance" },
+                if chart { "insure" } else { "decline insurance" },
+            )?;
+            if insure {
+                wager.place_insurance_bet();
+            }
+        }
+
+        let mut hands = vec![wager];
+        if !self.dealer.hand.is_blackjack() && !hands[0].hand.is_blackjack() {
+            let mut index = 0;
+            while index < hands.len() {
+                if !self.play_hand(input, output, &mut hands, index, &up)? {
+                    return Ok(false);
+                }
+                index += 1;
+            }
+        }
+
+        // Reveal the hole card and finish the dealer hand when any bet is still live
+        let down = self.dealer.hand.cards[0].clone();
+        self.show(&down);
+        let single = hands.len() == 1;
+        let settled = |wager: &Wager| wager.hand.is_busted() || wager.surrendered || (single && wager.hand.is_blackjack());
+        let live = !hands.iter().all(settled);
+        if live && !self.dealer.hand.is_blackjack() {
+            while !self.dealer.should_stand() {
+                let card = self.shoe.draw_card().ok_or(StrikerError::OutOfCards)?;
+                self.show(&card);
+                self.dealer.hand.add_card(card);
+            }
+        }
+        writeln!(output, "Dealer has {}", Self::describe(&self.dealer.hand))?;
+        self.settle(output, &mut hands)?;
+        Ok(true)
+    }
+
+    // Returns false when the player quits
+    fn play_hand(&mut self, input: &mut impl BufRead, output: &mut impl Write, hands: &mut Vec<Wager>, index: usize, up: &Card) -> Result<bool, StrikerError> {
+        // Split aces get a single card each
+        if hands.len() > 1 && hands[index].hand.cards[0].is_ace() {
+            return Ok(true);
+        }
+        loop {
+            let wager = &hands[index];
+            if wager.hand.is_busted() || wager.hand.total == 21 {
+                return Ok(true);
+            }
+            let options = self.options(wager, hands.len());
+            let recommended = self.recommended(wager, up, &options)?;
+            let prompt = options.iter().map(|action| action.prompt()).collect::<Vec<_>>().join(" ");
+            let label = if hands.len() > 1 { format!("Hand {} ", index + 1) } else { String::new() };
+            let Some(line) = self.read_command(input, output, &format!("{}{} {}", label, Self::describe(&wager.hand), prompt))? else {
+                return Ok(false);
+            };
+            let Some(action) = Action::from_input(&line).filter(|action| options.contains(action)) else {
+                writeln!(output, "Choose one of {}", prompt)?;
+                continue;
+            };
+            self.check_decision(output, action.name(), recommended.name())?;
+
+            let wager = &mut hands[index];
+            match action {
+                Action::Hit => self.deal(&mut wager.hand)?,
+                Action::Stand => return Ok(true),
+                Action::Double => {
+                    wager.double_bet();
+                    self.deal(&mut wager.hand)?;
+                    writeln!(output, "You have {}", Self::describe(&wager.hand))?;
+                    return Ok(true);
+                }
+                Action::Surrender => {
+                    wager.surrender();
+                    return Ok(true);
+                }
+                Action::Split => {
+                    let mut split = Wager::new(MINIMUM_BET, MAXIMUM_BET);
+                    wager.split_hand(&mut split)?;
+                    self.deal(&mut wager.hand)?;
+                    self.deal(&mut split.hand)?;
+                    hands.insert(index + 1, split);
+                    if hands[index].hand.cards[0].is_ace() {
+                        return Ok(true);
+                    }
+                }
+            }
+            if hands[index].hand.is_busted() {
+                writeln!(output, "Busted with {}", Self::describe(&hands[index].hand))?;
+            }
+        }
+    }
+
+    fn options(&self, wager: &Wager, hands: usize) -> Vec<Action> {
+        let mut options = vec![Action::Hit, Action::Stand];
+        let first = wager.hand.cards.len() == 2;
+        if first && (hands == 1 || self.rules.double_after_split) {
+            options.push(Action::Double);
+        }
+        if wager.hand.is_pair() {
+            options.push(Action::Split);
+        }
+        if first && hands == 1 && self.rules.surrender {
+            options.push(Action::Surrender);
+        }
+        options
+    }
+
+    // The play the loaded strategy makes, the mimic player draws to 17 like the dealer
+    pub fn recommended(&self, wager: &Wager, up: &Card, options: &[Action]) -> Result<Action, StrikerError> {
+        let hand = &wager.hand;
+        if self.mimic {
+            return Ok(if !hand.is_soft_17() && hand.total >= 17 { Action::Stand } else { Action::Hit });
+        }
+        chart_action(&self.strategy, &self.seen_cards, hand, up, options)
+    }
+
+    fn check_decision(&mut self, output: &mut impl Write, chosen: &str, recommended: &str) -> Result<(), StrikerError> {
+        self.decisions += 1;
+        if chosen != recommended {
+            self.deviations += 1;
+            writeln!(output, "  Deviation: the {} chart says {}", self.strategy.playbook, recommended)?;
+        }
+        Ok(())
+    }
+
+    fn settle(&mut self, output: &mut impl Write, hands: &mut [Wager]) -> Result<(), StrikerError> {
+        let dealer = &self.dealer.hand;
+        // Two card 21 after a split is not a blackjack
+        let single = hands.len() == 1;
+        for wager in hands.iter_mut() {
+            let blackjack = single && wager.hand.is_blackjack();
+            if dealer.is_blackjack() {
+                wager.won_insurance();
+            } else {
+                wager.lost_insurance();
+            }
+            if wager.surrendered {
+                wager.lost_surrender();
+            } else if dealer.is_blackjack() {
+                if blackjack {
+                    wager.push();
+                } else {
+                    wager.lost();
+                }
+            } else if blackjack {
+                wager.won_blackjack(self.rules.blackjack_pays, self.rules.blackjack_bets);
+            } else if wager.hand.is_busted() {
+                wager.lost();
+            } else if dealer.is_busted() || wager.hand.total > dealer.total {
+                wager.won();
+            } else if dealer.total > wager.hand.total {
+                wager.lost();
+            } else {
+                wager.push();
+            }
+            let net = wager.amount_won + wager.insurance_won;
+            self.bankroll += net;
+            writeln!(output, "  {} {:+}", Self::describe(&wager.hand), net)?;
+        }
+        writeln!(output, "Bankroll {:+}", self.bankroll)?;
+        Ok(())
+    }
+
+    fn print_summary(&self, output: &mut impl Write) -> Result<(), StrikerError> {
+        let accuracy = if self.decisions > 0 {
+            100.0 * (self.decisions - self.deviations) as f64 / self.decisions as f64
+        } else {
+            100.0
+        };
+        writeln!(
+            output,
+            "\nRounds {}, decisions {}, deviations {}, accuracy {:.1} %, bankroll {:+}",
+            self.rounds, self.decisions, self.deviations, accuracy, self.bankroll
+        )?;
+        Ok(())
+    }
+
+    // Read a line, answering count requests along the way, None once the player quits
+    fn read_command(&self, input: &mut impl BufRead, output: &mut impl Write, prompt: &str) -> Result<Option<String>, StrikerError> {
+        loop {
+            write!(output, "{}: ", prompt)?;
+            output.flush()?;
+            let mut line = String::new();
+            if input.read_line(&mut line)? == 0 {
+                return Ok(None);
+            }
+            match line.trim().to_lowercase().as_str() {
+                "q" | "quit" => return Ok(None),
+                "c" | "count" => {
+                    let running_count = self.strategy.get_running_count(&self.seen_cards);
+                    let true_count = self.strategy.get_true_count(&self.seen_cards, running_count);
+                    writeln!(output, "  Running count {}, true count {}", running_count, true_count)?;
+                }
+                _ => return Ok(Some(line.trim().to_string())),
+            }
+        }
+    }
+
+    fn deal(&mut self, hand: &mut Hand) -> Result<(), StrikerError> {
+        let card = self.shoe.draw_card().ok_or(StrikerError::OutOfCards)?;
+        self.show(&card);
+        hand.add_card(card);
+        Ok(())
+    }
+
+    fn show(&mut self, card: &Card) {
+        self.seen_cards[card.rank.value()] += 1;
+    }
+
+    fn describe(hand: &Hand) -> String {
+        let cards: Vec<String> = hand.cards.iter().map(|card| card.to_string()).collect();
+        let soft = if hand.is_soft() { "soft " } else { "" };
+        format!("{} ({}{})", cards.join(", "), soft, hand.total)
+    }
+}
+
+// The chart play for the cards seen, in the same order the simulated player decides
+pub fn chart_action(strategy: &Strategy, seen_cards: &[usize], hand: &Hand, up: &Card, options: &[Action]) -> Result<Action, StrikerError> {
+    if options.contains(&Action::Surrender) && !hand.is_soft() && strategy.get_surrender(seen_cards, hand.total, up) {
+        return Ok(Action::Surrender);
+    }
+    if options.contains(&Action::Double) && strategy.get_double(seen_cards, hand.total, hand.is_soft(), up)? {
+        return Ok(Action::Double);
+    }
+    if options.contains(&Action::Split) && strategy.get_split(seen_cards, &hand.cards[0], up)? {
+        return Ok(Action::Split);
+    }
+    Ok(if strategy.get_stand(seen_cards, hand.total, hand.is_soft(), up)? {
+        Action::Stand
+    } else {
+        Action::Hit
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arguments::arguments::Arguments;
+    use crate::cards::{Rank, Suit};
+    use crate::constants::constants::STRATEGY_BASIC;
+    use crate::resources::single_deck_basic::SINGLE_DECK_BASIC_JSON;
+    use std::io::Cursor;
+
+    fn make_game(strategy_name: &str) -> Game {
+        let arguments = Arguments {
+            strategy: strategy_name.to_string(),
+            ..Default::default()
+        };
+        let mut rules = Rules::new();
+        rules.blackjack_bets = 2;
+        rules.blackjack_pays = 3;
+        rules.penetration = 0.75;
+        rules.surrender = true;
+        let mut strategy = Strategy::new();
+        strategy.fetch_table(SINGLE_DECK_BASIC_JSON.clone());
+        Game::new(&Parameters::new(&arguments), &rules, &strategy)
+    }
+
+    fn wager(ranks: &[Rank]) -> Wager {
+        let mut wager = Wager::new(MINIMUM_BET, MAXIMUM_BET);
+        wager.place_bet(MINIMUM_BET);
+        for rank in ranks {
+            wager.hand.add_card(Card::new(*rank, Suit::Hearts));
+        }
+        wager
+    }
+
+    #[test]
+    fn test_action_from_input() {
+        assert_eq!(Action::from_input("H"), Some(Action::Hit));
+        assert_eq!(Action::from_input("stand"), Some(Action::Stand));
+        assert_eq!(Action::from_input("p"), Some(Action::Split));
+        assert_eq!(Action::from_input("r"), Some(Action::Surrender));
+        assert_eq!(Action::from_input("x"), None);
+    }
+
+    #[test]
+    fn test_recommended() {
+        let game = make_game(STRATEGY_BASIC);
+        let six = Card::new(Rank::Six, Suit::Clubs);
+        let all = [Action::Hit, Action::Stand, Action::Double, Action::Split, Action::Surrender];
+        assert_eq!(game.recommended(&wager(&[Rank::Six, Rank::Five]), &six, &all).unwrap(), Action::Double);
+        assert_eq!(game.recommended(&wager(&[Rank::Six, Rank::Five]), &six, &all[..2]).unwrap(), Action::Hit);
+        assert_eq!(game.recommended(&wager(&[Rank::Eight, Rank::Eight]), &six, &all).unwrap(), Action::Split);
+        assert_eq!(game.recommended(&wager(&[Rank::Ten, Rank::Two, Rank::Four]), &six, &all[..2]).unwrap(), Action::Stand);
+
+        let game = make_game(STRATEGY_MIMIC);
+        assert_eq!(game.recommended(&wager(&[Rank::Ten, Rank::Six]), &six, &all).unwrap(), Action::Hit);
+        assert_eq!(game.recommended(&wager(&[Rank::Ten, Rank::Seven]), &six, &all).unwrap(), Action::Stand);
+    }
+
+    #[test]
+    fn test_options() {
+        let game = make_game(STRATEGY_BASIC);
+        assert_eq!(game.options(&wager(&[Rank::Eight, Rank::Eight]), 1).len(), 5);
+        assert_eq!(game.options(&wager(&[Rank::Eight, Rank::Two, Rank::Two]), 1), vec![Action::Hit, Action::Stand]);
+        assert!(!game.options(&wager(&[Rank::Eight, Rank::Nine]), 2).contains(&Action::Surrender));
+    }
+
+    #[test]
+    fn test_run_scripted_session() {
+        let mut game = make_game(STRATEGY_BASIC);
+        let mut input = Cursor::new("c\n\ns\nn\n".repeat(20));
+        let mut output = Vec::new();
+        game.run(&mut input, &mut output).unwrap();
+        let text = String::from_utf8(output).unwrap();
+
+        assert!(game.rounds > 0);
+        assert!(game.deviations <= game.decisions);
+        assert!(text.contains("Running count"));
+        assert!(text.contains("Dealer shows"));
+        assert!(text.contains(&format!("bankroll {:+}", game.bankroll)));
+    }
+
+    #[test]
+    fn test_quit() {
+        let mut game = make_game(STRATEGY_BASIC);
+        let mut output = Vec::new();
+        game.run(&mut Cursor::new("q\n"), &mut output).unwrap();
+        assert_eq!(game.rounds, 0);
+        assert!(String::from_utf8(output).unwrap().contains("Rounds 0"));
+    }
+}
@@ -0,0 +1,2 @@
+// game module
+pub mod game;
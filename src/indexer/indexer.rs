@@ -0,0 +1,417 @@
+use crate::arguments::parameters::Parameters;
+use crate::cards::{Card, Rank, dealer::Dealer, hand::Hand, shoe::Shoe};
+use crate::constants::constants::{INDEX_MINIMUM_SAMPLES, INDEX_TRUE_COUNT_MAXIMUM, INDEX_TRUE_COUNT_MINIMUM, NUMBER_OF_HANDS_MINIMUM};
+use crate::errors::errors::StrikerError;
+use crate::strategy::rules::Rules;
+use crate::strategy::strategy::Strategy;
+use crate::utilities::topology::Topology;
+use rand::Rng;
+use std::collections::BTreeMap;
+use std::thread;
+
+const INSURANCE: &str = "insurance";
+const CHARTS: [&str; 5] = ["soft-double", "hard-double", "pair-split", "soft-stand", "hard-stand"];
+
+// A single chart entry to be indexed: the chart name, row key and dealer up card value
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cell {
+    pub chart: String,
+    pub key: String,
+    pub up: usize,
+}
+
+// The dealt situation for one trial; cloned for each of the two choices so both see the same cards
+#[derive(Clone)]
+struct Trial {
+    hand: Hand,
+    dealer: Dealer,
+    up: Card,
+    shoe: Shoe,
+    seen_cards: [usize; 13],
+}
+
+pub struct Indexer {
+    parameters: Parameters,
+    rules: Rules,
+    strategy: Strategy,
+}
+
+impl Indexer {
+    pub fn new(parameters: &Parameters, rules: &Rules, strategy: &Strategy) -> Self {
+        Self {
+            parameters: parameters.clone(),
+            rules: rules.clone(),
+            strategy: strategy.clone(),
+        }
+    }
+
+    // Simulate every chart cell and return a copy of the strategy with the computed indices
+    pub fn run(&self) -> Result<Strategy, StrikerError> {
+        let cells = self.cells();
+        let trials = (self.parameters.number_of_hands / cells.len().max(1)).max(NUMBER_OF_HANDS_MINIMUM);
+        let threads = self.parameters.number_of_threads.max(1);
+        let mut handles = Vec::new();
+
+        for thread_index in 0..threads {
+            let share: Vec<Cell> = cells.iter().skip(thread_index).step_by(threads).cloned().collect();
+            let parameters = self.parameters.clone();
+            let rules = self.rules.clone();
+            let strategy = self.strategy.clone();
+
+            handles.push(thread::spawn(move || {
+                if parameters.pin_threads {
+                    Topology::pin_thread(thread_index);
+                }
+                let indexer = Indexer::new(&parameters, &rules, &strategy);
+                share.into_iter().map(|cell| (indexer.index_cell(&cell, trials), cell)).collect::<Vec<_>>()
+            }));
+        }
+
+        let mut strategy = self.strategy.clone();
+        for handle in handles {
+            for (value, cell) in handle.join().map_err(StrikerError::worker)? {
+                if let Some(value) = value? {
+                    Self::apply(&mut strategy, &cell, &value);
+                }
+            }
+        }
+        Ok(strategy)
+    }
+
+    // Every cell of the loaded charts that can be dealt from two cards, plus insurance
+    pub fn cells(&self) -> Vec<Cell> {
+        let mut cells = Vec::new();
+        for name in CHARTS {
+            let chart = self.strategy.get_chart(name).unwrap();
+            for key in chart.keys() {
+                for up in Rank::Two.value()..=Rank::Ace.value() {
+                    let cell = Cell {
+                        chart: name.to_string(),
+                        key: key.clone(),
+                        up,
+                    };
+                    if Self::starting_values(&cell).is_some() {
+                        cells.push(cell);
+                    }
+                }
+            }
+        }
+        cells.push(Cell {
+            chart: INSURANCE.to_string(),
+            key: "--".to_string(),
+            up: Rank::Ace.value(),
+        });
+        cells
+    }
+
+    fn apply(strategy: &mut Strategy, cell: &Cell, value: &str) {
+        if cell.chart == INSURANCE {
+            strategy.insurance = value.to_string();
+        } else if let Some(chart) = strategy.get_chart_mut(&cell.chart) {
+            chart.insert(&cell.key, cell.up, value);
+        }
+    }
+
+    // The two player card values that make up the row key of a cell
+    fn starting_values(cell: &Cell) -> Option<(usize, usize)> {
+        let ace = Rank::Ace.value();
+        if cell.chart == INSURANCE {
+            return Some((Rank::Ten.value(), Rank::Ten.value()));
+        }
+        if cell.chart == "pair-split" {
+            let value = match cell.key.as_str() {
+                "A" => ace,
+                "X" => Rank::Ten.value(),
+                key => key.parse().ok()?,
+            };
+            return (2..=ace).contains(&value).then_some((value, value));
+        }
+        let total: usize = cell.key.parse().ok()?;
+        if cell.chart.starts_with("soft") {
+            return match total {
+                12 => Some((ace, ace)),
+                13..=20 => Some((ace, total - ace)),
+                _ => None,
+            };
+        }
+        let first = total.saturating_sub(Rank::Ten.value()).max(2);
+        let second = total.checked_sub(first)?;
+        let range = 2..=Rank::Ten.value();
+        (range.contains(&first) && range.contains(&second)).then_some((first, second))
+    }
+
+    // Simulate one cell and return the chart value for it, or None when too few trials landed
+    pub fn index_cell(&self, cell: &Cell, trials: usize) -> Result<Option<String>, StrikerError> {
+        let Some((first, second)) = Self::starting_values(cell) else {
+            return Ok(None);
+        };
+        let mut shoe = if self.rules.spanish_21 {
+            Shoe::new_spanish_21(self.parameters.number_of_decks, self.rules.penetration)
+        } else {
+            Shoe::new(self.parameters.number_of_decks, self.rules.penetration)
+        };
+        let mut buckets: BTreeMap<isize, (usize, f64)> = BTreeMap::new();
+        let mut rng = rand::rng();
+
+        for _ in 0..trials {
+            shoe.shuffle();
+            let mut seen_cards = [0; 13];
+            for _ in 0..rng.random_range(0..shoe.get_cut_card().max(1)) {
+                if let Some(card) = shoe.draw_card() {
+                    seen_cards[card.rank.value()] += 1;
+                }
+            }
+
+            let Some(trial) = self.deal(&mut shoe, seen_cards, first, second, cell.up) else {
+                continue;
+            };
+            let Some(gain) = self.gain(cell, &trial)? else {
+                continue;
+            };
+            let running_count = self.strategy.get_running_count(&trial.seen_cards);
+            let true_count = self
+                .strategy
+                .get_true_count(&trial.seen_cards, running_count)
+                .clamp(INDEX_TRUE_COUNT_MINIMUM, INDEX_TRUE_COUNT_MAXIMUM);
+            let bucket = buckets.entry(true_count).or_insert((0, 0.0));
+            bucket.0 += 1;
+            bucket.1 += gain;
+        }
+
+        let means: Vec<(isize, f64)> = buckets
+            .into_iter()
+            .filter(|(_, (samples, _))| *samples >= INDEX_MINIMUM_SAMPLES)
+            .map(|(true_count, (samples, total))| (true_count, total / samples as f64))
+            .collect();
+        Ok(crossover(&means))
+    }
+
+    fn deal(&self, shoe: &mut Shoe, mut seen_cards: [usize; 13], first: usize, second: usize, up: usize) -> Option<Trial> {
+        let mut hand = Hand::new();
+        let mut dealer = Dealer::new(self.rules.hit_soft_17);
+        let first = shoe.draw_value(first)?;
+        let second = shoe.draw_value(second)?;
+        let up = shoe.draw_value(up)?;
+        let down = shoe.draw_card()?;
+
+        for card in [&first, &second, &up] {
+            seen_cards[card.rank.value()] += 1;
+        }
+        hand.add_card(first);
+        hand.add_card(second);
+        dealer.hand.add_card(down);
+        dealer.hand.add_card(up.clone());
+
+        Some(Trial {
+            hand,
+            dealer,
+            up,
+            shoe: shoe.clone(),
+            seen_cards,
+        })
+    }
+
+    // Expected gain, in units of the original bet, of answering yes to the cell rather than no
+    fn gain(&self, cell: &Cell, trial: &Trial) -> Result<Option<f64>, StrikerError> {
+        if cell.chart == INSURANCE {
+            return Ok(Some(if trial.dealer.hand.is_blackjack() { 1.0 } else { -0.5 }));
+        }
+        if trial.dealer.hand.is_blackjack() {
+            return Ok(None);
+        }
+
+        let (yes, no) = match cell.chart.as_str() {
+            "soft-stand" | "hard-stand" => (self.stand(trial.clone())?, self.hit(trial.clone())?),
+            "soft-double" | "hard-double" => (self.double(trial.clone())?, self.play(trial.clone())?),
+            _ => (self.split(trial.clone())?, self.play(trial.clone())?),
+        };
+        Ok(Some(yes - no))
+    }
+
+    fn stand(&self, mut trial: Trial) -> Result<f64, StrikerError> {
+        let hand = trial.hand.clone();
+        self.settle(&mut trial, &[hand])
+    }
+
+    fn hit(&self, mut trial: Trial) -> Result<f64, StrikerError> {
+        self.draw(&mut trial.hand, &mut trial.shoe, &mut trial.seen_cards)?;
+        self.play(trial)
+    }
+
+    fn double(&self, mut trial: Trial) -> Result<f64, StrikerError> {
+        self.draw(&mut trial.hand, &mut trial.shoe, &mut trial.seen_cards)?;
+        Ok(self.stand(trial)? * 2.0)
+    }
+
+    fn play(&self, mut trial: Trial) -> Result<f64, StrikerError> {
+        let mut hand = trial.hand.clone();
+        self.hit_until_stand(&mut hand, &mut trial)?;
+        self.settle(&mut trial, &[hand])
+    }
+
+    fn split(&self, mut trial: Trial) -> Result<f64, StrikerError> {
+        let mut first = trial.hand.clone();
+        let mut second = Hand::new();
+        let Ok(card) = first.split_pair() else {
+            return self.play(trial);
+        };
+        let aces = card.is_ace();
+        second.add_card(card);
+
+        let mut hands = [first, second];
+        for hand in hands.iter_mut() {
+            self.draw(hand, &mut trial.shoe, &mut trial.seen_cards)?;
+            if !aces {
+                self.hit_until_stand(hand, &mut trial)?;
+            }
+        }
+        self.settle(&mut trial, &hands)
+    }
+
+    fn hit_until_stand(&self, hand: &mut Hand, trial: &mut Trial) -> Result<(), StrikerError> {
+        while !hand.is_busted() && !self.strategy.get_stand(&trial.seen_cards, hand.total, hand.is_soft(), &trial.up)? {
+            self.draw(hand, &mut trial.shoe, &mut trial.seen_cards)?;
+        }
+        Ok(())
+    }
+
+    fn draw(&self, hand: &mut Hand, shoe: &mut Shoe, seen_cards: &mut [usize; 13]) -> Result<(), StrikerError> {
+        let card = shoe.draw_card();
+        if let Some(card) = &card {
+            seen_cards[card.rank.value()] += 1;
+        }
+        hand.draw_card(card)
+    }
+
+    // Play out the dealer and return the combined result of the hands
+    fn settle(&self, trial: &mut Trial, hands: &[Hand]) -> Result<f64, StrikerError> {
+        if hands.iter().any(|hand| !hand.is_busted()) {
+            while !trial.dealer.should_stand() {
+                trial.dealer.hand.draw_card(trial.shoe.draw_card())?;
+            }
+        }
+
+        let dealer = &trial.dealer.hand;
+        Ok(hands
+            .iter()
+            .map(|hand| {
+                if hand.is_busted() {
+                    -1.0
+                } else if dealer.is_busted() || hand.total > dealer.total {
+                    1.0
+                } else if dealer.total > hand.total {
+                    -1.0
+                } else {
+                    0.0
+                }
+            })
+            .sum())
+    }
+}
+
+// Turn the mean gain at each true count into a chart value: Y, N, an index, or a reverse index
+pub fn crossover(means: &[(isize, f64)]) -> Option<String> {
+    if means.is_empty() {
+        return None;
+    }
+    if means.iter().all(|(_, mean)| *mean >= 0.0) {
+        return Some("Y".to_string());
+    }
+    if means.iter().all(|(_, mean)| *mean < 0.0) {
+        return Some("N".to_string());
+    }
+
+    if slope(means) >= 0.0 {
+        // Answer yes at and above the index
+        let last_negative = means.iter().rposition(|(_, mean)| *mean < 0.0).unwrap();
+        match means.get(last_negative + 1) {
+            Some((true_count, _)) => Some(true_count.to_string()),
+            None => Some("N".to_string()),
+        }
+    } else {
+        // Answer yes at and below the index
+        let first_negative = means.iter().position(|(_, mean)| *mean < 0.0).unwrap();
+        match first_negative.checked_sub(1) {
+            Some(index) => Some(format!("R{}", means[index].0)),
+            None => Some("N".to_string()),
+        }
+    }
+}
+
+fn slope(means: &[(isize, f64)]) -> f64 {
+    let n = means.len() as f64;
+    let mean_x = means.iter().map(|(x, _)| *x as f64).sum::<f64>() / n;
+    let mean_y = means.iter().map(|(_, y)| *y).sum::<f64>() / n;
+    means.iter().map(|(x, y)| (*x as f64 - mean_x) * (y - mean_y)).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arguments::arguments::Arguments;
+    use crate::resources::rules_table::RULES_JSON;
+    use crate::resources::single_deck_basic::SINGLE_DECK_BASIC_JSON;
+
+    fn make_indexer() -> Indexer {
+        let arguments = Arguments {
+            number_of_threads: 2,
+            number_of_hands: 1000,
+            ..Default::default()
+        };
+        let parameters = Parameters::new(&arguments);
+        let mut rules = Rules::new();
+        rules.penetration = RULES_JSON["penetration"].as_f64().unwrap();
+        let mut strategy = Strategy::new();
+        strategy.fetch_table(SINGLE_DECK_BASIC_JSON.clone());
+        Indexer::new(&parameters, &rules, &strategy)
+    }
+
+    fn make_cell(chart: &str, key: &str, up: usize) -> Cell {
+        Cell {
+            chart: chart.to_string(),
+            key: key.to_string(),
+            up,
+        }
+    }
+
+    #[test]
+    fn test_crossover() {
+        assert_eq!(crossover(&[]), None);
+        assert_eq!(crossover(&[(-1, 0.1), (0, 0.2), (1, 0.3)]), Some("Y".to_string()));
+        assert_eq!(crossover(&[(-1, -0.1), (0, -0.2), (1, -0.3)]), Some("N".to_string()));
+        assert_eq!(crossover(&[(-1, -0.2), (0, -0.1), (1, 0.0), (2, 0.1)]), Some("1".to_string()));
+        assert_eq!(crossover(&[(-2, 0.2), (-1, 0.1), (0, -0.1), (1, -0.2)]), Some("R-1".to_string()));
+    }
+
+    #[test]
+    fn test_starting_values() {
+        assert_eq!(Indexer::starting_values(&make_cell("hard-stand", "16", 10)), Some((6, 10)));
+        assert_eq!(Indexer::starting_values(&make_cell("hard-stand", "5", 10)), Some((2, 3)));
+        assert_eq!(Indexer::starting_values(&make_cell("hard-stand", "21", 10)), None);
+        assert_eq!(Indexer::starting_values(&make_cell("soft-double", "18", 6)), Some((11, 7)));
+        assert_eq!(Indexer::starting_values(&make_cell("soft-stand", "12", 6)), Some((11, 11)));
+        assert_eq!(Indexer::starting_values(&make_cell("soft-stand", "21", 6)), None);
+        assert_eq!(Indexer::starting_values(&make_cell("pair-split", "X", 6)), Some((10, 10)));
+        assert_eq!(Indexer::starting_values(&make_cell("pair-split", "A", 6)), Some((11, 11)));
+    }
+
+    #[test]
+    fn test_cells() {
+        let indexer = make_indexer();
+        let cells = indexer.cells();
+        assert!(cells.contains(&make_cell("hard-stand", "16", 10)));
+        assert!(cells.contains(&make_cell("pair-split", "8", 11)));
+        assert!(!cells.contains(&make_cell("hard-stand", "21", 10)));
+        assert_eq!(cells.last().unwrap().chart, INSURANCE);
+    }
+
+    #[test]
+    fn test_index_cell() {
+        let indexer = make_indexer();
+        assert_eq!(indexer.index_cell(&make_cell("hard-stand", "20", 10), 2000).unwrap(), Some("Y".to_string()));
+        assert_eq!(indexer.index_cell(&make_cell("hard-stand", "5", 10), 2000).unwrap(), Some("N".to_string()));
+        assert!(indexer.index_cell(&make_cell("hard-double", "11", 6), 2000).unwrap().is_some());
+        assert!(indexer.index_cell(&make_cell("pair-split", "8", 6), 2000).unwrap().is_some());
+        assert!(indexer.index_cell(&make_cell(INSURANCE, "--", 11), 2000).unwrap().is_some());
+    }
+}
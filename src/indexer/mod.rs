@@ -0,0 +1,2 @@
+// indexer module
+pub mod indexer;
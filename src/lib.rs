@@ -0,0 +1,34 @@
+// Striker blackjack simulator as a library
+//
+// Build the rules, strategy and parameters, then run them for a report:
+//
+//     let arguments = Arguments { number_of_hands: 1_000_000, ..Default::default() };
+//     let parameters = Parameters::new(&arguments);
+//     let rules = Rules::builder()?.hit_soft_17(false).penetration(0.8).build();
+//     let strategy = Strategy::new();
+//     let report = Simulator::simulate(&parameters, &rules, &strategy)?;
+pub mod arguments;
+pub mod cards;
+pub mod constants;
+pub mod errors;
+pub mod game;
+pub mod indexer;
+pub mod player;
+pub mod report;
+pub mod resources;
+pub mod simulator;
+pub mod strategy;
+pub mod sweep;
+pub mod trainer;
+pub mod traits;
+pub mod utilities;
+
+pub use arguments::arguments::Arguments;
+pub use arguments::parameters::Parameters;
+pub use errors::errors::StrikerError;
+pub use report::comparison::Comparison;
+pub use report::report::Report;
+pub use simulator::simulator::Simulator;
+pub use strategy::rules::Rules;
+pub use strategy::strategy::Strategy;
+pub use sweep::sweep::Sweep;
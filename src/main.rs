@@ -1,67 +1,136 @@
-mod arguments;
-mod cards;
-mod constants;
-mod player;
-mod report;
-mod resources;
-mod simulator;
-mod strategy;
-mod traits;
-mod utilities;
+use num_format::{Locale, ToFormattedString};
+use std::process::ExitCode;
+use striker::constants::constants::{COMMAND_PLAY, COMMAND_REPORT, COMMAND_SWEEP, COMMAND_TRAIN, STRIKER_WHO_AM_I};
+use striker::game::game::Game;
+use striker::indexer::indexer::Indexer;
+use striker::simulator::checkpoint::Checkpoint;
+use striker::simulator::history::HandHistory;
+use striker::strategy::bet_ramp::BetRamp;
+use striker::trainer::trainer::Trainer;
+use striker::utilities::utilities::Utility;
+use striker::{Arguments, Comparison, Parameters, Rules, Simulator, Strategy, StrikerError, Sweep};
 
-use arguments::arguments::Arguments;
-use arguments::parameters::Parameters;
-use constants::constants::STRIKER_WHO_AM_I;
-use report::report::Report;
-use simulator::simulator::Simulator;
-use std::thread;
-use strategy::rules::Rules;
-use strategy::strategy::Strategy;
-use utilities::utilities::Utility;
+//
+fn main() -> ExitCode {
+    match run() {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
 
 //
-fn main() {
-    let mut handles = Vec::new();
+fn run() -> Result<(), StrikerError> {
     let arguments = Arguments::new();
-    let parameters = Parameters::new(&arguments);
+    let mut parameters = Parameters::new(&arguments);
     let mut rules = Rules::new();
     let mut strategy = Strategy::new();
-    let mut final_report = Report::new();
     let utility = Utility::default();
 
-    rules.init(&utility, &arguments.decks);
-    strategy.init(&utility, &arguments);
+    if arguments.command == COMMAND_SWEEP {
+        return sweep(&arguments, &utility);
+    }
+
+    if arguments.command == COMMAND_REPORT {
+        return report(&arguments);
+    }
+
+    // A resumed run takes its name, epoch and remaining hands from the checkpoint
+    let checkpoint = if arguments.resume {
+        Checkpoint::resume(&mut parameters)?
+    } else {
+        Checkpoint::new(&parameters)
+    };
+
+    rules.init(&utility, &arguments.decks)?;
+    strategy.init(&utility, &arguments, &rules)?;
     println!("Start: {}", STRIKER_WHO_AM_I);
     println!("  -- {:<10} {}", "arguments", "-".repeat(66));
     println!("{}", parameters);
     println!("{}", rules);
     println!("  {}", "-".repeat(80));
 
-    // Spawn worker threads
-    final_report.init(&parameters);
-    for _ in 0..parameters.number_of_threads {
-        let parameters = parameters.clone();
-        let rules = rules.clone();
-        let strategy = strategy.clone();
+    if arguments.command == COMMAND_PLAY {
+        let mut game = Game::new(&parameters, &rules, &strategy);
+        return game.run(&mut std::io::stdin().lock(), &mut std::io::stdout());
+    }
 
-        handles.push(thread::spawn(move || {
-            let simulator = Simulator::new(&parameters, &rules, &strategy);
-            simulator.run_once()
-        }));
+    if arguments.command == COMMAND_TRAIN {
+        let mut trainer = Trainer::new(parameters.number_of_decks, &rules, &strategy, &arguments.user, &arguments.progress);
+        return trainer.run(&mut std::io::stdin().lock(), &mut std::io::stdout());
     }
 
-    // Merge simulation results
-    for handle in handles {
-        if let Ok(simulator) = handle.join() {
-            final_report.merge(&simulator.get_report());
-        }
+    if arguments.index {
+        let indexer = Indexer::new(&parameters, &rules, &strategy);
+        let indexed = indexer.run()?;
+        println!("  -- {:<10} {}", "index", "-".repeat(66));
+        println!("{}", serde_json::to_string_pretty(&indexed.to_json_object()).unwrap());
+        println!("  {}", "-".repeat(80));
+        return Ok(());
     }
-    final_report.finish();
+
+    if let Some(path) = &arguments.hand_history {
+        HandHistory::create(path).map_err(|e| StrikerError::file("create", path, e))?;
+    }
+
+    if arguments.resume {
+        println!(
+            "    Resuming with {} hands already played",
+            checkpoint.earlier().total_hands.to_formatted_string(&Locale::en)
+        );
+    }
+    let mut final_report = Simulator::simulate_from(&parameters, &rules, &strategy, checkpoint)?;
 
     println!("  -- {:<10} {}", "results", "-".repeat(66));
     final_report.print();
     println!("  {}", "-".repeat(80));
+    if let Some(path) = &arguments.decisions_csv {
+        match std::fs::write(path, final_report.to_decisions_csv()) {
+            Ok(()) => println!("    Decisions written to {}", path),
+            Err(e) => println!("    Error: Cannot write decisions to {}: {}", path, e),
+        }
+    }
+    if arguments.bet_ramp {
+        let bet_ramp = BetRamp::from_report(&final_report);
+        println!("  -- {:<10} {}", "bet ramp", "-".repeat(66));
+        println!("{}", serde_json::to_string_pretty(&serde_json::json!({ "bet-ramp": bet_ramp.to_json_object() })).unwrap());
+        println!("  {}", "-".repeat(80));
+    }
     println!("  -- {:<10} {}", "insert", "-".repeat(66));
-    final_report.insert(&utility);
+    final_report.insert(&utility)?;
+    println!("  {}", "-".repeat(80));
+    Ok(())
+}
+
+// Every cell of the grid gets its own tables, so none are fetched for the command line ones
+fn sweep(arguments: &Arguments, utility: &Utility) -> Result<(), StrikerError> {
+    let path = arguments.grid.as_deref().ok_or_else(|| StrikerError::InvalidGrid("no grid file, use -J".to_string()))?;
+    let mut sweep = Sweep::load(path, arguments)?;
+    println!("Start: {}", STRIKER_WHO_AM_I);
+    println!("  -- {:<10} {}", "sweep", "-".repeat(66));
+    println!("    Grid of {} cells from {}", sweep.cells.len(), path);
+    let reports = sweep.run(utility)?;
+    println!("  -- {:<10} {}", "comparison", "-".repeat(66));
+    sweep.print(&reports);
+    sweep.write(&reports, &arguments.sweep_output)?;
+    println!("    Reports written to {}", arguments.sweep_output);
+    println!("  {}", "-".repeat(80));
+    Ok(())
+}
+
+// Saved reports need no tables, they are merged and compared as they are
+fn report(arguments: &Arguments) -> Result<(), StrikerError> {
+    let comparison = Comparison::load(&arguments.reports)?;
+    println!("Start: {}", STRIKER_WHO_AM_I);
+    println!("  -- {:<10} {}", "report", "-".repeat(66));
+    println!("    {} reports of {} playbooks", arguments.reports.len(), comparison.merged.len());
+    comparison.print();
+    if let Some(directory) = &arguments.report_output {
+        comparison.write(directory)?;
+        println!("    Merged reports written to {}", directory);
+    }
     println!("  {}", "-".repeat(80));
+    Ok(())
 }
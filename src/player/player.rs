@@ -1,16 +1,36 @@
-use crate::cards::{card::Card, shoe::Shoe, wager::Wager};
-use crate::constants::constants::{MAXIMUM_BET, MINIMUM_BET};
+use crate::cards::{Rank, Suit, card::Card, hand::Hand, shoe::Shoe, wager::Wager};
+use crate::constants::constants::{
+    ACTION_DOUBLE, ACTION_HIT, ACTION_INSURANCE, ACTION_SPLIT, ACTION_SURRENDER, ACTION_SWITCH, ADVANTAGE_PLAY_HOLE_CARD, ADVANTAGE_PLAY_WARP, MAXIMUM_BET, MINIMUM_BET,
+    NO_HOLE_CARD_OBBO, NO_HOLE_CARD_OBO, SPANISH_21_FIVE_CARD_21, SPANISH_21_MIXED_21, SPANISH_21_SEVEN_CARD_21, SPANISH_21_SIX_CARD_21, SPANISH_21_SPADED_21,
+    SPANISH_21_SUITED_21,
+};
+use crate::errors::errors::StrikerError;
 use crate::report::report::Report;
 use crate::strategy::rules::Rules;
 use crate::strategy::strategy::Strategy;
+use crate::strategy::switch::SwitchValues;
 
 pub struct Player {
     pub rules: Rules,
     pub strategy: Strategy,
     pub wager: Wager,
     pub splits: Vec<Wager>,
+    pub switch: Wager,
+    switch_played: Option<(Wager, Vec<Wager>)>,
     pub report: Report,
     pub seen_cards: [usize; 13],
+    pub true_count: isize,
+    pub decisions: Vec<(&'static str, String, usize)>,
+    pub keep_decisions: bool,
+    pub advantage_play: Option<String>,
+    pub hole: Option<Card>,
+    pub next_card: Option<Card>,
+    pub ace_predicted: bool,
+    pub keep_history: bool,
+    pub actions: Vec<String>,
+    pub settled: Vec<Wager>,
+    pub round_bet: usize,
+    pub round_won: isize,
 }
 
 impl Player {
@@ -20,8 +40,22 @@ impl Player {
             strategy: strategy.clone(),
             wager: Wager::new(MINIMUM_BET, MAXIMUM_BET),
             splits: Vec::new(),
+            switch: Wager::new(MINIMUM_BET, MAXIMUM_BET),
+            switch_played: None,
             report: Report::new(),
             seen_cards: [0; 13],
+            true_count: 0,
+            decisions: Vec::new(),
+            keep_decisions: false,
+            advantage_play: None,
+            hole: None,
+            next_card: None,
+            ace_predicted: false,
+            keep_history: false,
+            actions: Vec::new(),
+            settled: Vec::new(),
+            round_bet: 0,
+            round_won: 0,
         }
     }
 
@@ -29,12 +63,22 @@ impl Player {
         self.seen_cards = [0; 13];
     }
 
-    pub fn draw_card(&mut self, maybe_card: Option<Card>) {
-        self.wager.hand.draw_card(maybe_card.clone());
-        self.show_card(&maybe_card.clone());
+    pub fn draw_card(&mut self, maybe_card: Option<Card>) -> Result<(), StrikerError> {
+        self.wager.hand.draw_card(maybe_card.clone())?;
+        self.show_card(&maybe_card);
+        Ok(())
+    }
+
+    // Blackjack Switch: the second hand is dealt alongside the first
+    pub fn draw_switch_card(&mut self, maybe_card: Option<Card>) -> Result<(), StrikerError> {
+        self.switch.hand.draw_card(maybe_card.clone())?;
+        self.show_card(&maybe_card);
+        Ok(())
     }
 
     pub fn show_card(&mut self, card: &Option<Card>) {
+        // Once another card is out the first based card is no longer next
+        self.next_card = None;
         if let Some(card) = card {
             let value = card.rank.value() as usize;
             self.seen_cards[value] += 1;
@@ -46,25 +90,48 @@ impl Player {
     }
 
     pub fn busted_or_blackjack(&self) -> bool {
-        if self.splits.is_empty() {
-            return self.wager.hand.is_busted() || self.wager.hand.is_blackjack();
+        if let Some((wager, splits)) = &self.switch_played {
+            return Self::wagers_busted_or_blackjack(wager, splits) && Self::wagers_busted_or_blackjack(&self.wager, &self.splits);
+        }
+        Self::wagers_busted_or_blackjack(&self.wager, &self.splits)
+    }
+
+    fn wagers_busted_or_blackjack(wager: &Wager, splits: &[Wager]) -> bool {
+        if splits.is_empty() {
+            return wager.hand.is_busted() || wager.is_blackjack() || wager.surrendered;
         }
-        !self.splits.iter().any(|split| !split.hand.is_busted())
+        !splits.iter().any(|split| !split.hand.is_busted())
     }
 
     pub fn place_bet(&mut self, mimic: bool) {
         self.splits.clear();
+        self.switch_played = None;
+        self.decisions.clear();
+        self.actions.clear();
+        self.settled.clear();
         self.wager.hand.reset();
+        self.true_count = self.strategy.get_true_count(&self.seen_cards, self.strategy.get_running_count(&self.seen_cards));
         if mimic {
             self.wager.place_bet(MINIMUM_BET);
+        } else if self.ace_predicted {
+            self.wager.place_bet(MAXIMUM_BET);
         } else {
             let bet = self.strategy.get_bet(&self.seen_cards);
             self.wager.place_bet(bet);
         }
+        if self.rules.blackjack_switch {
+            self.switch.place_bet(self.wager.amount_bet);
+        }
     }
 
     pub fn insurance(&mut self) {
-        if self.strategy.get_insurance(&self.seen_cards) {
+        self.record_decision("insurance", || "--".to_string(), Rank::Ace.value());
+        let insure = match self.advantage_play.as_deref() {
+            Some(ADVANTAGE_PLAY_HOLE_CARD | ADVANTAGE_PLAY_WARP) => self.hole.as_ref().is_some_and(|hole| hole.rank.value() == 10),
+            _ => self.strategy.get_insurance(&self.seen_cards),
+        };
+        if insure {
+            self.record_action(ACTION_INSURANCE);
             self.wager.place_insurance_bet();
         }
     }
@@ -73,127 +140,354 @@ impl Player {
         !self.wager.hand.is_soft_17() && self.wager.hand.total >= 17
     }
 
-    pub fn play(&mut self, up: &Card, shoe: &mut Shoe, mimic: bool) {
-        if self.wager.hand.is_blackjack() {
+    pub fn play(&mut self, up: &Card, shoe: &mut Shoe, mimic: bool) -> Result<(), StrikerError> {
+        if self.rules.blackjack_switch {
+            return self.play_switch(up, shoe, mimic);
+        }
+        self.play_hand(up, shoe, mimic)
+    }
+
+    // Blackjack Switch: decide on swapping the second cards, then play both hands in turn
+    fn play_switch(&mut self, up: &Card, shoe: &mut Shoe, mimic: bool) -> Result<(), StrikerError> {
+        if !mimic && self.should_switch(up) {
+            let first = self.wager.hand.cards.clone();
+            let second = self.switch.hand.cards.clone();
+            self.wager.hand.reset();
+            self.wager.hand.add_card(first[0].clone());
+            self.wager.hand.add_card(second[1].clone());
+            self.switch.hand.reset();
+            self.switch.hand.add_card(second[0].clone());
+            self.switch.hand.add_card(first[1].clone());
+            self.wager.switched = true;
+            self.switch.switched = true;
+            self.record_action(ACTION_SWITCH);
+        }
+
+        self.play_hand(up, shoe, mimic)?;
+        let switch = std::mem::replace(&mut self.switch, Wager::new(MINIMUM_BET, MAXIMUM_BET));
+        let wager = std::mem::replace(&mut self.wager, switch);
+        let splits = std::mem::take(&mut self.splits);
+        self.play_hand(up, shoe, mimic)?;
+        self.switch_played = Some((wager, splits));
+        Ok(())
+    }
+
+    // Switch when the two swapped hands are worth more against the up card than the two dealt hands
+    fn should_switch(&self, up: &Card) -> bool {
+        let values = SwitchValues::new(&self.rules, up);
+        let first = &self.wager.hand.cards;
+        let second = &self.switch.hand.cards;
+        let dealt = values.value(&first[0], &first[1], true) + values.value(&second[0], &second[1], true);
+        let switched = values.value(&first[0], &second[1], false) + values.value(&second[0], &first[1], false);
+        switched > dealt
+    }
+
+    fn play_hand(&mut self, up: &Card, shoe: &mut Shoe, mimic: bool) -> Result<(), StrikerError> {
+        if self.wager.is_blackjack() {
             self.report.total_blackjacks += 1;
-            return;
+            return Ok(());
         }
 
         if mimic {
             while !self.mimic_stand() {
-                self.draw_card(shoe.draw_card());
+                self.record_action(ACTION_HIT);
+                self.draw_card(shoe.draw_card())?;
             }
-            return;
+            return Ok(());
         }
 
-        if self.strategy.get_double(&self.seen_cards, self.wager.hand.total, self.wager.hand.is_soft(), up) {
-            self.wager.double_bet();
-            self.draw_card(shoe.draw_card());
+        if self.rules.surrender && !self.wager.hand.is_soft() && self.get_surrender(self.wager.hand.total, up) {
+            self.record_action(ACTION_SURRENDER);
+            self.wager.surrender();
+            return Ok(());
+        }
+
+        if self.get_double(self.wager.hand.total, self.wager.hand.is_soft(), up)? {
+            self.record_action(ACTION_DOUBLE);
+            if self.rules.free_bet && !self.wager.hand.is_soft() && (9..=11).contains(&self.wager.hand.total) {
+                self.wager.free_double();
+            } else {
+                self.wager.double_bet();
+            }
+            self.draw_card(shoe.draw_card())?;
             self.report.total_doubles += 1;
-            return;
+
+            // Double down rescue: surrender the doubled hand for the original bet
+            if self.rules.double_down_rescue && !self.wager.hand.is_busted() && !self.wager.hand.is_soft() && self.get_surrender(self.wager.hand.total, up) {
+                self.record_action(ACTION_SURRENDER);
+                self.wager.surrender();
+            }
+            return Ok(());
         }
 
-        if self.wager.hand.is_pair() && self.strategy.get_split(&self.seen_cards, self.wager.hand.get_card_pair().unwrap(), up) {
-            self.handle_split(shoe, up);
-            return;
+        if self.wager.hand.is_pair() && self.get_split(&self.wager.hand.cards[0].clone(), up)? {
+            return self.handle_split(shoe, up);
         }
 
-        self.hit_until_stand_or_bust(shoe, up);
+        self.hit_until_stand_or_bust(shoe, up)
     }
 
-    fn hit_until_stand_or_bust(&mut self, shoe: &mut Shoe, up: &Card) {
-        while !self.wager.hand.is_busted() && !self.strategy.get_stand(&self.seen_cards, self.wager.hand.total, self.wager.hand.is_soft(), up) {
-            self.draw_card(shoe.draw_card());
+    fn hit_until_stand_or_bust(&mut self, shoe: &mut Shoe, up: &Card) -> Result<(), StrikerError> {
+        while !self.wager.hand.is_busted() && !self.get_stand(self.wager.hand.total, self.wager.hand.is_soft(), up)? {
+            self.record_action(ACTION_HIT);
+            self.draw_card(shoe.draw_card())?;
         }
+        Ok(())
     }
 
-    fn handle_split(&mut self, shoe: &mut Shoe, up: &Card) {
+    fn handle_split(&mut self, shoe: &mut Shoe, up: &Card) -> Result<(), StrikerError> {
         let mut wager = std::mem::replace(&mut self.wager, Wager::new(MINIMUM_BET, MAXIMUM_BET));
         let mut split = Wager::new(MINIMUM_BET, MAXIMUM_BET);
 
         self.report.total_splits += 1;
+        self.record_action(ACTION_SPLIT);
         if wager.hand.is_pair_of_aces() {
             self.report.total_splits_ace += 1;
 
-            wager.split_hand(&mut split);
+            self.split_wager(&mut wager, &mut split)?;
             let card = shoe.draw_card();
             self.show_card(&card);
-            wager.hand.draw_card(card);
+            wager.hand.draw_card(card)?;
 
             let card = shoe.draw_card();
             self.show_card(&card);
-            split.hand.draw_card(card);
+            split.hand.draw_card(card)?;
 
             self.splits.push(split);
             self.wager = wager;
-            return;
+            return Ok(());
         }
 
-        wager.split_hand(&mut split);
+        self.split_wager(&mut wager, &mut split)?;
         self.report.total_splits += 1;
 
         let card = shoe.draw_card();
         self.show_card(&card);
-        wager.hand.draw_card(card);
-        self.play_split(&mut wager, shoe, up);
+        wager.hand.draw_card(card)?;
+        self.play_split(&mut wager, shoe, up)?;
 
         let card = shoe.draw_card();
         self.show_card(&card);
-        split.hand.draw_card(card);
-        self.play_split(&mut split, shoe, up);
+        split.hand.draw_card(card)?;
+        self.play_split(&mut split, shoe, up)?;
 
         self.splits.push(split);
         self.wager = wager;
+        Ok(())
     }
 
-    pub fn play_split(&mut self, wager: &mut Wager, shoe: &mut Shoe, up: &Card) {
-        if wager.hand.is_pair() && self.strategy.get_split(&self.seen_cards, wager.hand.get_card_pair().unwrap(), up) {
+    pub fn play_split(&mut self, wager: &mut Wager, shoe: &mut Shoe, up: &Card) -> Result<(), StrikerError> {
+        if wager.hand.is_pair() && self.get_split(&wager.hand.cards[0], up)? {
             let mut split = Wager::new(MINIMUM_BET, MAXIMUM_BET);
-            wager.split_hand(&mut split);
+            self.split_wager(wager, &mut split)?;
             self.report.total_splits += 1;
+            self.record_action(ACTION_SPLIT);
 
             let card = shoe.draw_card();
             self.show_card(&card);
-            wager.hand.draw_card(card);
-            self.play_split(wager, shoe, up);
+            wager.hand.draw_card(card)?;
+            self.play_split(wager, shoe, up)?;
 
             let card = shoe.draw_card();
             self.show_card(&card);
-            split.hand.draw_card(card);
-            self.play_split(&mut split, shoe, up);
+            split.hand.draw_card(card)?;
+            self.play_split(&mut split, shoe, up)?;
 
             self.splits.push(split);
-            return;
+            return Ok(());
         }
 
-        let mut do_stand = self.strategy.get_stand(&self.seen_cards, wager.hand.total, wager.hand.is_soft(), up);
+        let mut do_stand = self.get_stand(wager.hand.total, wager.hand.is_soft(), up)?;
 
         while !wager.hand.is_busted() && !do_stand {
+            self.record_action(ACTION_HIT);
             let card = shoe.draw_card();
             self.show_card(&card);
-            wager.hand.draw_card(card);
+            wager.hand.draw_card(card)?;
             if !wager.hand.is_busted() {
-                do_stand = self.strategy.get_stand(&self.seen_cards, wager.hand.total, wager.hand.is_soft(), up);
+                do_stand = self.get_stand(wager.hand.total, wager.hand.is_soft(), up)?;
             }
         }
+        Ok(())
+    }
+
+    // Free Bet: every split but tens is free
+    fn split_wager(&self, wager: &mut Wager, split: &mut Wager) -> Result<(), StrikerError> {
+        let ten = wager.hand.cards[0].rank.value() == 10;
+        wager.split_hand(split)?;
+        if self.rules.free_bet && !ten {
+            split.free_split();
+        }
+        Ok(())
+    }
+
+    // Dealer total when the advantage play has revealed the hole card
+    fn known_dealer_total(&self, up: &Card) -> Option<usize> {
+        let hole = self.hole.as_ref()?;
+        let mut dealer = Hand::new();
+        dealer.add_card(up.clone());
+        dealer.add_card(hole.clone());
+        Some(dealer.total)
+    }
+
+    fn get_double(&mut self, total: usize, soft: bool, up: &Card) -> Result<bool, StrikerError> {
+        if let Some(dealer_total) = self.known_dealer_total(up)
+            && let Some(double) = self.strategy.get_hole_double(&self.seen_cards, total, soft, dealer_total)
+        {
+            self.record_decision("hole-double", || Strategy::hole_key(total, soft), dealer_total);
+            return Ok(double);
+        }
+        self.record_decision(if soft { "soft-double" } else { "hard-double" }, || total.to_string(), up.rank.value());
+        self.strategy.get_double(&self.seen_cards, total, soft, up)
+    }
+
+    fn get_split(&mut self, pair: &Card, up: &Card) -> Result<bool, StrikerError> {
+        self.record_decision("pair-split", || pair.rank.key().to_string(), up.rank.value());
+        self.strategy.get_split(&self.seen_cards, pair, up)
+    }
+
+    fn get_surrender(&mut self, total: usize, up: &Card) -> bool {
+        self.record_decision("surrender", || total.to_string(), up.rank.value());
+        self.strategy.get_surrender(&self.seen_cards, total, up)
+    }
+
+    fn get_stand(&mut self, total: usize, soft: bool, up: &Card) -> Result<bool, StrikerError> {
+        if let Some(dealer_total) = self.known_dealer_total(up)
+            && let Some(stand) = self.strategy.get_hole_stand(&self.seen_cards, total, soft, dealer_total)
+        {
+            self.record_decision("hole-stand", || Strategy::hole_key(total, soft), dealer_total);
+            return Ok(stand);
+        }
+        self.record_decision(if soft { "soft-stand" } else { "hard-stand" }, || total.to_string(), up.rank.value());
+        let stand = self.strategy.get_stand(&self.seen_cards, total, soft, up)?;
+        Ok(match self.next_card.take() {
+            Some(next) => Self::first_base_stand(total, soft, &next, stand),
+            None => stand,
+        })
+    }
+
+    // Never take a card that busts, and take one that improves a made hand
+    fn first_base_stand(total: usize, soft: bool, next: &Card, stand: bool) -> bool {
+        let mut aces = soft as usize + next.is_ace() as usize;
+        let mut next_total = total + next.rank.value();
+        while next_total > 21 && aces > 0 {
+            next_total -= 10;
+            aces -= 1;
+        }
+        if next_total > 21 {
+            return true;
+        }
+        stand && next_total <= total.max(16)
+    }
+
+    // Remember each chart cell consulted this round so its result can be credited at payoff, only when the cells are written out
+    fn record_decision(&mut self, chart: &'static str, key: impl FnOnce() -> String, up: usize) {
+        if !self.keep_decisions {
+            return;
+        }
+        let key = key();
+        if !self.decisions.iter().any(|decision| decision.0 == chart && decision.1 == key && decision.2 == up) {
+            self.decisions.push((chart, key, up));
+        }
+    }
+
+    // Actions are only kept for the hand history
+    fn record_action(&mut self, action: &str) {
+        if self.keep_history {
+            self.actions.push(action.to_string());
+        }
     }
 
     pub fn payoff(&mut self, dealer_blackjack: bool, dealer_busted: bool, dealer_total: usize) {
-        if self.splits.is_empty() {
+        let total_bet = self.report.total_bet;
+        let total_won = self.report.total_won;
+
+        self.payoff_wagers(dealer_blackjack, dealer_busted, dealer_total);
+        if self.rules.blackjack_switch {
+            // The second hand was never played when the dealer had blackjack
+            let (wager, splits) = self
+                .switch_played
+                .take()
+                .unwrap_or_else(|| (std::mem::replace(&mut self.switch, Wager::new(MINIMUM_BET, MAXIMUM_BET)), Vec::new()));
+            self.wager = wager;
+            self.splits = splits;
+            self.payoff_wagers(dealer_blackjack, dealer_busted, dealer_total);
+        }
+
+        let bet = self.report.total_bet - total_bet;
+        let won = self.report.total_won - total_won;
+        self.round_bet = bet;
+        self.round_won = won;
+        self.report.add_true_count_hand(self.true_count, bet, won);
+        if self.ace_predicted {
+            self.report.ace_rounds.hands += 1;
+            self.report.ace_rounds.total_bet += bet;
+            self.report.ace_rounds.total_won += won;
+        }
+        for (chart, key, up) in &self.decisions {
+            self.report.add_decision_hand(&(chart.to_string(), key.clone(), *up), won);
+        }
+    }
+
+    fn payoff_wagers(&mut self, dealer_blackjack: bool, dealer_busted: bool, dealer_total: usize) {
+        if dealer_blackjack && self.rules.no_hole_card {
+            self.payoff_no_hole_card();
+        } else if self.splits.is_empty() {
             self.payoff_hand(dealer_blackjack, dealer_busted, dealer_total);
-            return;
+        } else {
+            Self::payoff_split(&mut self.report, &mut self.wager, &self.rules, dealer_busted, dealer_total);
+            for split in &mut self.splits {
+                Self::payoff_split(&mut self.report, split, &self.rules, dealer_busted, dealer_total);
+            }
         }
+        if self.keep_history {
+            self.settled.push(self.wager.clone());
+            self.settled.extend(self.splits.iter().cloned());
+        }
+    }
 
-        Self::payoff_split(&mut self.report, &mut self.wager, dealer_busted, dealer_total);
-        for split in &mut self.splits {
-            Self::payoff_split(&mut self.report, split, dealer_busted, dealer_total);
+    // Dealer blackjack found after the player acted, the no hole card rule decides which bets are lost
+    fn payoff_no_hole_card(&mut self) {
+        self.wager.won_insurance();
+        self.report.total_bet += self.wager.insurance_bet;
+        self.report.total_won += self.wager.insurance_won;
+
+        let single = self.splits.is_empty();
+        let losses = self.rules.no_hole_card_losses.as_str();
+        for (index, wager) in std::iter::once(&mut self.wager).chain(self.splits.iter_mut()).enumerate() {
+            if single && wager.is_blackjack() {
+                wager.push();
+                self.report.total_pushes += 1;
+            } else {
+                let original = if index == 0 { wager.original_bet() } else { 0 };
+                let amount = match losses {
+                    NO_HOLE_CARD_OBO => original,
+                    NO_HOLE_CARD_OBBO if wager.hand.is_busted() => wager.amount_bet,
+                    NO_HOLE_CARD_OBBO => original,
+                    _ => wager.amount_bet,
+                };
+                wager.lost_amount(amount);
+                if amount > 0 {
+                    self.report.total_loses += 1;
+                } else {
+                    self.report.total_pushes += 1;
+                }
+            }
+            self.report.total_bet += wager.amount_bet;
+            self.report.total_won += wager.amount_won;
         }
     }
 
-    fn payoff_split(report: &mut Report, wager: &mut Wager, dealer_busted: bool, dealer_total: usize) {
+    fn payoff_split(report: &mut Report, wager: &mut Wager, rules: &Rules, dealer_busted: bool, dealer_total: usize) {
         if wager.hand.is_busted() {
             wager.lost();
             report.total_loses += 1;
+        } else if rules.dealer_22_push && dealer_busted && dealer_total == 22 {
+            wager.push();
+            report.total_pushes += 1;
+        } else if rules.spanish_21 && wager.hand.total == 21 {
+            Self::won_twenty_one(wager);
+            report.total_wins += 1;
         } else if dealer_busted || wager.hand.total > dealer_total {
             wager.won();
             report.total_wins += 1;
@@ -209,6 +503,34 @@ impl Player {
         report.total_bet += wager.amount_bet;
     }
 
+    // Spanish 21 pays a player 21 against any dealer hand, with bonuses when the hand was not doubled
+    fn won_twenty_one(wager: &mut Wager) {
+        let cards = &wager.hand.cards;
+        let mut values: Vec<usize> = cards.iter().map(|card| card.rank.value()).collect();
+        values.sort();
+
+        let (pays, bets) = if wager.doubled {
+            (1, 1)
+        } else if cards.len() >= 7 {
+            SPANISH_21_SEVEN_CARD_21
+        } else if cards.len() == 6 {
+            SPANISH_21_SIX_CARD_21
+        } else if cards.len() == 5 {
+            SPANISH_21_FIVE_CARD_21
+        } else if values == [6, 7, 8] || values == [7, 7, 7] {
+            if cards.iter().all(|card| card.suit == Suit::Spades) {
+                SPANISH_21_SPADED_21
+            } else if cards.iter().all(|card| card.suit == cards[0].suit) {
+                SPANISH_21_SUITED_21
+            } else {
+                SPANISH_21_MIXED_21
+            }
+        } else {
+            (1, 1)
+        };
+        wager.won_bonus(pays, bets);
+    }
+
     pub fn payoff_hand(&mut self, dealer_blackjack: bool, dealer_busted: bool, dealer_total: usize) {
         if dealer_blackjack {
             self.wager.won_insurance();
@@ -216,8 +538,13 @@ impl Player {
             self.wager.lost_insurance();
         }
 
-        if dealer_blackjack {
-            if self.wager.hand.is_blackjack() {
+        if self.wager.surrendered {
+            self.wager.lost_surrender();
+            self.report.total_loses += 1;
+        } else if dealer_blackjack {
+            if self.wager.is_blackjack() && self.rules.spanish_21 {
+                self.wager.won_blackjack(self.rules.blackjack_pays, self.rules.blackjack_bets);
+            } else if self.wager.is_blackjack() {
                 self.wager.push();
                 self.report.total_pushes += 1;
             } else {
@@ -225,11 +552,17 @@ impl Player {
                 self.report.total_loses += 1;
             }
         } else {
-            if self.wager.hand.is_blackjack() {
+            if self.wager.is_blackjack() {
                 self.wager.won_blackjack(self.rules.blackjack_pays, self.rules.blackjack_bets);
             } else if self.wager.hand.is_busted() {
                 self.wager.lost();
                 self.report.total_loses += 1;
+            } else if self.rules.dealer_22_push && dealer_busted && dealer_total == 22 {
+                self.wager.push();
+                self.report.total_pushes += 1;
+            } else if self.rules.spanish_21 && self.wager.hand.total == 21 {
+                Self::won_twenty_one(&mut self.wager);
+                self.report.total_wins += 1;
             } else if dealer_busted || (self.wager.hand.total > dealer_total) {
                 self.wager.won();
                 self.report.total_wins += 1;
@@ -252,6 +585,7 @@ mod tests {
     use super::*;
     use crate::arguments::arguments::Arguments;
     use crate::cards::{Card, Rank, Suit};
+    use crate::resources::single_deck_basic::SINGLE_DECK_BASIC_JSON;
     use crate::strategy::rules::Rules;
     use crate::strategy::strategy::Strategy;
     use crate::utilities::utilities::Utility;
@@ -261,7 +595,7 @@ mod tests {
         let rules = Rules::default();
         let mut strategy = Strategy::new();
         let utility = Utility::default();
-        strategy.init(&utility, &Arguments::new());
+        strategy.init(&utility, &Arguments::new(), &rules).unwrap();
         let mut player = Player::new(&rules, &strategy);
 
         player.place_bet(true);
@@ -275,11 +609,11 @@ mod tests {
         let mut player = Player::new(&rules, &strategy);
 
         player.place_bet(true);
-        player.wager.hand.draw_card(Some(Card::new(Rank::Ace, Suit::Hearts)));
-        player.wager.hand.draw_card(Some(Card::new(Rank::Six, Suit::Hearts)));
+        player.wager.hand.add_card(Card::new(Rank::Ace, Suit::Hearts));
+        player.wager.hand.add_card(Card::new(Rank::Six, Suit::Hearts));
         assert!(!player.mimic_stand());
 
-        player.wager.hand.draw_card(Some(Card::new(Rank::Ten, Suit::Hearts)));
+        player.wager.hand.add_card(Card::new(Rank::Ten, Suit::Hearts));
         assert!(player.mimic_stand());
     }
 
@@ -289,8 +623,8 @@ mod tests {
         let mut player = Player::new(&rules, &strategy);
 
         player.place_bet(true);
-        player.wager.hand.draw_card(Some(Card::new(Rank::Ten, Suit::Hearts)));
-        player.wager.hand.draw_card(Some(Card::new(Rank::Ten, Suit::Hearts)));
+        player.wager.hand.add_card(Card::new(Rank::Ten, Suit::Hearts));
+        player.wager.hand.add_card(Card::new(Rank::Ten, Suit::Hearts));
 
         player
     }
@@ -299,7 +633,7 @@ mod tests {
     fn test_payoff_player_busted() {
         let mut player = mock_player();
 
-        player.wager.hand.draw_card(Some(Card::new(Rank::Ten, Suit::Hearts)));
+        player.wager.hand.add_card(Card::new(Rank::Ten, Suit::Hearts));
         player.payoff(false, false, 21);
 
         assert_eq!(player.wager.amount_won, -(player.wager.amount_bet as isize));
@@ -321,6 +655,318 @@ mod tests {
         assert_eq!(player.wager.amount_won, -(player.wager.amount_bet as isize));
     }
 
+    #[test]
+    fn test_payoff_records_true_count() {
+        let mut player = mock_player();
+        player.true_count = 3;
+        player.payoff(false, true, 22);
+
+        let bucket = &player.report.true_counts[&3];
+        assert_eq!(bucket.hands, 1);
+        assert_eq!(bucket.total_bet, player.wager.amount_bet);
+        assert_eq!(bucket.total_won, player.wager.amount_bet as isize);
+    }
+
+    #[test]
+    fn test_payoff_records_decisions() {
+        let rules = Rules::default();
+        let mut strategy = Strategy::new();
+        strategy.fetch_table(SINGLE_DECK_BASIC_JSON.clone());
+        let mut player = Player::new(&rules, &strategy);
+        let mut shoe = Shoe::new(1, 0.75);
+        let up = Card::new(Rank::Ten, Suit::Clubs);
+
+        // Nothing is kept unless the chart cells are written out
+        player.place_bet(false);
+        player.wager.hand.add_card(Card::new(Rank::Ten, Suit::Hearts));
+        player.wager.hand.add_card(Card::new(Rank::Nine, Suit::Hearts));
+        player.play(&up, &mut shoe, false).unwrap();
+        player.payoff(false, false, 20);
+        assert!(player.decisions.is_empty() && player.report.decisions.is_empty());
+
+        player.keep_decisions = true;
+        player.place_bet(false);
+        player.wager.hand.add_card(Card::new(Rank::Ten, Suit::Hearts));
+        player.wager.hand.add_card(Card::new(Rank::Nine, Suit::Hearts));
+        player.play(&up, &mut shoe, false).unwrap();
+        player.payoff(false, false, 20);
+
+        assert_eq!(player.decisions, vec![("hard-double", "19".to_string(), 10), ("hard-stand", "19".to_string(), 10)]);
+        let stand = ("hard-stand".to_string(), "19".to_string(), 10);
+        let double = ("hard-double".to_string(), "19".to_string(), 10);
+        assert_eq!(player.report.decisions[&stand].hands, 1);
+        assert_eq!(player.report.decisions[&double].total_won, -(player.wager.amount_bet as isize));
+    }
+
+    fn spanish_21_player(cards: &[(Rank, Suit)]) -> Player {
+        let mut rules = Rules::default();
+        rules.spanish_21 = true;
+        rules.blackjack_pays = 3;
+        rules.blackjack_bets = 2;
+        let mut player = Player::new(&rules, &Strategy::new());
+        player.place_bet(true);
+        for (rank, suit) in cards {
+            player.wager.hand.add_card(Card::new(*rank, *suit));
+        }
+        player
+    }
+
+    #[test]
+    fn test_payoff_spanish_21_bonuses() {
+        let five_cards = [
+            (Rank::Two, Suit::Clubs),
+            (Rank::Three, Suit::Clubs),
+            (Rank::Four, Suit::Clubs),
+            (Rank::Five, Suit::Clubs),
+            (Rank::Seven, Suit::Hearts),
+        ];
+        let cases: Vec<(Vec<(Rank, Suit)>, isize)> = vec![
+            (vec![(Rank::Ten, Suit::Clubs), (Rank::Five, Suit::Clubs), (Rank::Six, Suit::Hearts)], 2),
+            (five_cards.to_vec(), 3),
+            (vec![(Rank::Six, Suit::Clubs), (Rank::Seven, Suit::Hearts), (Rank::Eight, Suit::Clubs)], 3),
+            (vec![(Rank::Seven, Suit::Clubs), (Rank::Seven, Suit::Clubs), (Rank::Seven, Suit::Clubs)], 4),
+            (vec![(Rank::Eight, Suit::Spades), (Rank::Seven, Suit::Spades), (Rank::Six, Suit::Spades)], 6),
+        ];
+        for (cards, expected) in cases {
+            let mut player = spanish_21_player(&cards);
+            player.payoff(false, false, 21);
+            assert_eq!(player.wager.amount_won, expected, "{:?}", cards);
+        }
+    }
+
+    #[test]
+    fn test_payoff_spanish_21_blackjack_beats_dealer_blackjack() {
+        let mut player = spanish_21_player(&[(Rank::Ace, Suit::Clubs), (Rank::King, Suit::Clubs)]);
+        player.payoff(true, false, 21);
+        assert_eq!(player.wager.amount_won, 3);
+    }
+
+    #[test]
+    fn test_play_late_surrender() {
+        let mut rules = Rules::default();
+        rules.surrender = true;
+        let mut strategy = Strategy::new();
+        let mut data = SINGLE_DECK_BASIC_JSON.clone();
+        data["surrender"] = serde_json::json!({ "16": ["N", "N", "N", "N", "N", "N", "N", "Y", "Y", "Y"] });
+        strategy.fetch_table(data);
+        let mut player = Player::new(&rules, &strategy);
+        let mut shoe = Shoe::new(1, 0.75);
+
+        player.place_bet(false);
+        player.wager.hand.add_card(Card::new(Rank::Ten, Suit::Hearts));
+        player.wager.hand.add_card(Card::new(Rank::Six, Suit::Hearts));
+        player.play(&Card::new(Rank::Ten, Suit::Clubs), &mut shoe, false).unwrap();
+        assert!(player.wager.surrendered);
+        assert!(player.busted_or_blackjack());
+
+        player.payoff(false, false, 20);
+        assert_eq!(player.wager.amount_won, -((player.wager.amount_bet / 2) as isize));
+    }
+
+    fn no_hole_card_player(losses: &str) -> Player {
+        let mut rules = Rules::default();
+        rules.no_hole_card = true;
+        rules.no_hole_card_losses = losses.to_string();
+        let mut player = Player::new(&rules, &Strategy::new());
+        player.place_bet(true);
+
+        // Doubled hand plus a busted split hand
+        player.wager.hand.add_card(Card::new(Rank::Eight, Suit::Hearts));
+        player.wager.hand.add_card(Card::new(Rank::Three, Suit::Hearts));
+        player.wager.double_bet();
+        let mut split = Wager::new(MINIMUM_BET, MAXIMUM_BET);
+        split.amount_bet = MINIMUM_BET;
+        for rank in [Rank::Eight, Rank::Ten, Rank::Nine] {
+            split.hand.add_card(Card::new(rank, Suit::Clubs));
+        }
+        player.splits.push(split);
+        player
+    }
+
+    #[test]
+    fn test_payoff_no_hole_card_losses() {
+        for (losses, expected) in [("all", 3), ("obo", 1), ("obbo", 2)] {
+            let mut player = no_hole_card_player(losses);
+            player.payoff(true, false, 21);
+            assert_eq!(player.report.total_won, -((MINIMUM_BET * expected) as isize), "{}", losses);
+        }
+    }
+
+    #[test]
+    fn test_payoff_no_hole_card_blackjack_push() {
+        let mut rules = Rules::default();
+        rules.no_hole_card = true;
+        let mut player = Player::new(&rules, &Strategy::new());
+        player.place_bet(true);
+        player.wager.hand.add_card(Card::new(Rank::Ace, Suit::Hearts));
+        player.wager.hand.add_card(Card::new(Rank::King, Suit::Hearts));
+        player.payoff(true, false, 21);
+        assert_eq!(player.report.total_won, 0);
+        assert_eq!(player.report.total_pushes, 1);
+    }
+
+    fn variant_player(rules: Rules, first: [Rank; 2], second: [Rank; 2]) -> Player {
+        let mut player = Player::new(&rules, &Strategy::new());
+        player.place_bet(true);
+        for (a, b) in first.iter().zip(second.iter()) {
+            player.draw_card(Some(Card::new(*a, Suit::Hearts))).unwrap();
+            player.draw_switch_card(Some(Card::new(*b, Suit::Clubs))).unwrap();
+        }
+        player
+    }
+
+    #[test]
+    fn test_should_switch() {
+        let rules = Rules::builder().unwrap().blackjack_switch(true).build();
+        let six = Card::new(Rank::Six, Suit::Spades);
+        let ten = Card::new(Rank::Ten, Suit::Spades);
+        let player = variant_player(rules.clone(), [Rank::Ten, Rank::Six], [Rank::Ten, Rank::Ace]);
+        assert!(!player.should_switch(&six));
+
+        let player = variant_player(rules.clone(), [Rank::Ten, Rank::Six], [Rank::Six, Rank::Ten]);
+        assert!(player.should_switch(&six));
+
+        // The same cards are kept against a six and switched against a ten
+        let player = variant_player(rules, [Rank::Two, Rank::Two], [Rank::Eight, Rank::Ace]);
+        assert!(!player.should_switch(&six));
+        assert!(player.should_switch(&ten));
+    }
+
+    #[test]
+    fn test_play_switch_plays_both_hands() {
+        let rules = Rules::builder().unwrap().blackjack_switch(true).build();
+        let mut player = variant_player(rules, [Rank::Ace, Rank::Nine], [Rank::Ten, Rank::King]);
+        let mut shoe = Shoe::new(1, 0.75);
+        assert_eq!(player.switch.amount_bet, player.wager.amount_bet);
+
+        player.play(&Card::new(Rank::Seven, Suit::Spades), &mut shoe, true).unwrap();
+        assert!(!player.busted_or_blackjack());
+        player.payoff(false, false, 17);
+        assert_eq!(player.report.total_bet, MINIMUM_BET * 2);
+        assert_eq!(player.report.total_won, (MINIMUM_BET * 2) as isize);
+    }
+
+    #[test]
+    fn test_payoff_switch_dealer_blackjack() {
+        let mut rules = Rules::default();
+        rules.blackjack_switch = true;
+        let mut player = variant_player(rules, [Rank::Ten, Rank::Nine], [Rank::Ten, Rank::Eight]);
+        player.payoff(true, false, 21);
+        assert_eq!(player.report.total_bet, MINIMUM_BET * 2);
+        assert_eq!(player.report.total_won, -((MINIMUM_BET * 2) as isize));
+    }
+
+    #[test]
+    fn test_payoff_dealer_22_push() {
+        let mut rules = Rules::default();
+        rules.dealer_22_push = true;
+        let mut player = Player::new(&rules, &Strategy::new());
+        player.place_bet(true);
+        player.wager.hand.add_card(Card::new(Rank::Ten, Suit::Hearts));
+        player.wager.hand.add_card(Card::new(Rank::Eight, Suit::Hearts));
+        player.payoff(false, true, 22);
+        assert_eq!(player.wager.amount_won, 0);
+        assert_eq!(player.report.total_pushes, 1);
+
+        player.payoff(false, true, 23);
+        assert_eq!(player.wager.amount_won, player.wager.amount_bet as isize);
+    }
+
+    #[test]
+    fn test_play_free_bet_double_and_split() {
+        let mut rules = Rules::default();
+        rules.free_bet = true;
+        let mut strategy = Strategy::new();
+        strategy.fetch_table(SINGLE_DECK_BASIC_JSON.clone());
+        let up = Card::new(Rank::Six, Suit::Clubs);
+
+        let mut player = Player::new(&rules, &strategy);
+        let mut shoe = Shoe::new(1, 0.75);
+        player.place_bet(false);
+        player.wager.hand.add_card(Card::new(Rank::Six, Suit::Hearts));
+        player.wager.hand.add_card(Card::new(Rank::Five, Suit::Hearts));
+        let bet = player.wager.amount_bet;
+        player.play(&up, &mut shoe, false).unwrap();
+        assert_eq!(player.wager.amount_bet, bet);
+        assert_eq!(player.wager.free_bet, bet);
+
+        player.place_bet(false);
+        player.wager.hand.add_card(Card::new(Rank::Eight, Suit::Hearts));
+        player.wager.hand.add_card(Card::new(Rank::Eight, Suit::Clubs));
+        player.play(&up, &mut shoe, false).unwrap();
+        assert!(!player.splits.is_empty());
+        assert!(player.splits.iter().all(|split| split.amount_bet == 0 && split.free_bet > 0));
+    }
+
+    #[test]
+    fn test_actions_kept_for_history() {
+        let mut strategy = Strategy::new();
+        strategy.fetch_table(SINGLE_DECK_BASIC_JSON.clone());
+        let up = Card::new(Rank::Six, Suit::Clubs);
+        let mut player = Player::new(&Rules::default(), &strategy);
+        let mut shoe = Shoe::new(1, 0.75);
+
+        player.place_bet(false);
+        player.wager.hand.add_card(Card::new(Rank::Six, Suit::Hearts));
+        player.wager.hand.add_card(Card::new(Rank::Five, Suit::Hearts));
+        player.play(&up, &mut shoe, false).unwrap();
+        assert!(player.actions.is_empty());
+
+        player.keep_history = true;
+        player.place_bet(false);
+        player.wager.hand.add_card(Card::new(Rank::Six, Suit::Hearts));
+        player.wager.hand.add_card(Card::new(Rank::Five, Suit::Hearts));
+        player.play(&up, &mut shoe, false).unwrap();
+        assert_eq!(player.actions, vec![ACTION_DOUBLE]);
+        player.payoff(false, true, 26);
+        assert_eq!(player.settled.len(), 1);
+        assert_eq!(player.round_bet, player.wager.amount_bet);
+        assert_eq!(player.round_won, player.wager.amount_bet as isize);
+
+        player.place_bet(false);
+        assert!(player.actions.is_empty() && player.settled.is_empty());
+    }
+
+    #[test]
+    fn test_first_base_stand() {
+        let ten = Card::new(Rank::Ten, Suit::Clubs);
+        let four = Card::new(Rank::Four, Suit::Clubs);
+        let ace = Card::new(Rank::Ace, Suit::Clubs);
+        assert!(Player::first_base_stand(16, false, &ten, false));
+        assert!(!Player::first_base_stand(16, false, &four, true));
+        assert!(!Player::first_base_stand(20, false, &ace, true));
+        assert!(Player::first_base_stand(18, true, &four, true));
+        assert!(!Player::first_base_stand(18, true, &ten, false));
+        assert!(Player::first_base_stand(13, false, &ace, true));
+    }
+
+    #[test]
+    fn test_play_hole_card() {
+        let mut strategy = Strategy::new();
+        strategy.fetch_table(SINGLE_DECK_BASIC_JSON.clone());
+        let mut player = Player::new(&Rules::default(), &strategy);
+        player.advantage_play = Some(ADVANTAGE_PLAY_HOLE_CARD.to_string());
+        player.hole = Some(Card::new(Rank::Ten, Suit::Clubs));
+        player.keep_decisions = true;
+        let mut shoe = Shoe::new(1, 0.75);
+
+        // Standing 16 against a ten up would lose to the dealer's 20
+        player.place_bet(false);
+        player.wager.hand.add_card(Card::new(Rank::Ten, Suit::Hearts));
+        player.wager.hand.add_card(Card::new(Rank::Six, Suit::Hearts));
+        player.play(&Card::new(Rank::Ten, Suit::Spades), &mut shoe, false).unwrap();
+        assert!(player.decisions.contains(&("hole-stand", "16".to_string(), 20)));
+        assert!(player.wager.hand.cards.len() > 2);
+
+        player.hole = Some(Card::new(Rank::Ace, Suit::Clubs));
+        player.place_bet(false);
+        player.insurance();
+        assert_eq!(player.wager.insurance_bet, 0);
+        player.hole = Some(Card::new(Rank::King, Suit::Clubs));
+        player.insurance();
+        assert_eq!(player.wager.insurance_bet, player.wager.amount_bet / 2);
+    }
+
     #[test]
     fn test_payoff_dealer_21() {
         let mut player = mock_player();
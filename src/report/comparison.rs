@@ -0,0 +1,320 @@
+use crate::constants::constants::REPORT_SIGNIFICANCE;
+use crate::errors::errors::StrikerError;
+use crate::report::report::Report;
+use num_format::{Locale, ToFormattedString};
+use serde_json::{Value, json};
+use std::fs;
+use std::path::Path;
+
+// The difference in advantage between two playbooks and how likely it is to be chance
+#[derive(Debug, Clone, PartialEq)]
+pub struct Difference {
+    pub advantage: f64,
+    pub standard_error: f64,
+    pub z_score: f64,
+    pub p_value: f64,
+}
+
+impl Difference {
+    // The two runs are independent, so the variances of their advantages add
+    pub fn between(first: &Report, second: &Report) -> Self {
+        let advantage = first.advantage - second.advantage;
+        let standard_error = (first.standard_error.powi(2) + second.standard_error.powi(2)).sqrt();
+        let z_score = advantage / standard_error;
+        Self {
+            advantage,
+            standard_error,
+            z_score,
+            p_value: if z_score.is_finite() { erfc(z_score.abs() / std::f64::consts::SQRT_2) } else { 1.0 },
+        }
+    }
+
+    pub fn significant(&self) -> bool {
+        self.p_value < REPORT_SIGNIFICANCE
+    }
+}
+
+// Saved reports merged into one per playbook, runs of the same playbook on different machines add up to a single run
+pub struct Comparison {
+    pub merged: Vec<Report>,
+    pub files: Vec<usize>,
+}
+
+impl Comparison {
+    pub fn load(paths: &[String]) -> Result<Self, StrikerError> {
+        let mut reports = Vec::new();
+        for path in paths {
+            let text = fs::read_to_string(path).map_err(|e| StrikerError::file("read", path, e))?;
+            let json = serde_json::from_str(&text).map_err(|e| StrikerError::file("parse", path, e))?;
+            reports.push(Report::from_json_object(&json).map_err(|e| StrikerError::InvalidReport(format!("{}: {}", path, e)))?);
+        }
+        Self::new(reports)
+    }
+
+    // The playbooks are kept in the order they first appear, one alone is merged and two are compared
+    pub fn new(reports: Vec<Report>) -> Result<Self, StrikerError> {
+        if reports.is_empty() {
+            return Err(StrikerError::InvalidReport("no report files given".to_string()));
+        }
+        let mut playbooks: Vec<Vec<Report>> = Vec::new();
+        for report in reports {
+            // The same run given twice would count its hands twice, the cells of a sweep share a name so the results are compared too
+            if playbooks.iter().flatten().any(|other| Self::same_run(other, &report)) {
+                return Err(StrikerError::InvalidReport(format!("{} of {} is given more than once", report.name, report.playbook)));
+            }
+            match playbooks.iter_mut().find(|group| group[0].playbook == report.playbook) {
+                // Runs of one playbook add up only when they were run the same way
+                Some(group) if Self::configuration(&group[0]) != Self::configuration(&report) => {
+                    return Err(StrikerError::InvalidReport(format!(
+                        "{} of {} was run with {}, not {}",
+                        report.name,
+                        report.playbook,
+                        Self::configuration(&report),
+                        Self::configuration(&group[0])
+                    )));
+                }
+                Some(group) => group.push(report),
+                None => playbooks.push(vec![report]),
+            }
+        }
+        if playbooks.len() > 2 {
+            let names: Vec<&str> = playbooks.iter().map(|group| group[0].playbook.as_str()).collect();
+            return Err(StrikerError::InvalidReport(format!("can only compare two playbooks, not {}", names.join(", "))));
+        }
+        Ok(Self {
+            files: playbooks.iter().map(Vec::len).collect(),
+            merged: playbooks.iter().map(|group| Self::merge(group)).collect(),
+        })
+    }
+
+    // The sweep cell when the report has one, and the decks and version either way
+    fn configuration(report: &Report) -> Value {
+        json!({ "decks": report.decks, "version": report.version, "cell": report.cell })
+    }
+
+    fn same_run(first: &Report, second: &Report) -> bool {
+        first.name == second.name
+            && first.playbook == second.playbook
+            && first.start == second.start
+            && first.total_hands == second.total_hands
+            && first.total_won == second.total_won
+    }
+
+    // Named after the earliest run, the time is the total spent simulating and the topology only kept when every run shares it
+    fn merge(reports: &[Report]) -> Report {
+        let first = reports.iter().min_by_key(|report| report.start).unwrap();
+        let mut merged = Report {
+            name: first.name.clone(),
+            version: first.version.clone(),
+            simulator: first.simulator.clone(),
+            playbook: first.playbook.clone(),
+            strategy: first.strategy.clone(),
+            decks: first.decks.clone(),
+            epoch: first.epoch.clone(),
+            pinned_threads: reports.iter().all(|report| report.pinned_threads),
+            topology: first
+                .topology
+                .clone()
+                .filter(|topology| reports.iter().all(|report| report.topology.as_ref() == Some(topology))),
+            target_stderr: first.target_stderr,
+            cell: first.cell.clone(),
+            start: first.start,
+            end: reports.iter().map(|report| report.end).max().unwrap_or_default(),
+            ..Default::default()
+        };
+        for report in reports {
+            merged.merge(report);
+            merged.total_threads += report.total_threads;
+            merged.duration += report.duration;
+        }
+        merged.rates();
+        merged
+    }
+
+    pub fn difference(&self) -> Option<Difference> {
+        match self.merged.as_slice() {
+            [first, second] => Some(Difference::between(first, second)),
+            _ => None,
+        }
+    }
+
+    pub fn print(&self) {
+        if let [report] = self.merged.as_slice() {
+            println!("    {:<26}: {:>17}", "Playbook", report.playbook);
+            println!("    {:<26}: {:>17}", "Reports merged", self.files[0]);
+            report.print();
+            return;
+        }
+        let line = |label: &str, values: Vec<String>| println!("    {:<26}: {:>17} {:>17}", label, values[0], values[1]);
+        line("Playbook", self.merged.iter().map(|report| report.playbook.clone()).collect());
+        line("Reports merged", self.files.iter().map(|files| files.to_string()).collect());
+        line(
+            "Number of hands",
+            self.merged.iter().map(|report| report.total_hands.to_formatted_string(&Locale::en)).collect(),
+        );
+        line("Total bet", self.merged.iter().map(|report| report.total_bet.to_formatted_string(&Locale::en)).collect());
+        line("Total won", self.merged.iter().map(|report| report.total_won.to_formatted_string(&Locale::en)).collect());
+        line("Player advantage (%)", self.merged.iter().map(|report| format!("{:+08.3}", report.advantage)).collect());
+        line("Standard error (%)", self.merged.iter().map(|report| format!("{:+08.3}", report.standard_error)).collect());
+        if let Some(difference) = self.difference() {
+            println!("    {:<26}: {:>17} {:+08.3} %", "Difference in advantage", "", difference.advantage);
+            println!("    {:<26}: {:>17} {:+08.3} %", "Standard error", "", difference.standard_error);
+            println!("    {:<26}: {:>17} {:+08.3}", "Z score", "", difference.z_score);
+            println!(
+                "    {:<26}: {:>17} {:.4} ({} at the {} % level)",
+                "P value",
+                "",
+                difference.p_value,
+                if difference.significant() { "significant" } else { "not significant" },
+                REPORT_SIGNIFICANCE * 100.0
+            );
+        }
+    }
+
+    // One merged report per playbook, in the same schema so it can be merged again
+    pub fn write(&self, directory: &str) -> Result<(), StrikerError> {
+        fs::create_dir_all(directory).map_err(|e| StrikerError::file("create", directory, e))?;
+        for report in &self.merged {
+            let path = Path::new(directory).join(format!("{}.json", report.playbook));
+            fs::write(&path, serde_json::to_string_pretty(&report.to_json_object()).unwrap_or_default()).map_err(|e| StrikerError::file("write", &path.to_string_lossy(), e))?;
+        }
+        Ok(())
+    }
+}
+
+// Complementary error function, Numerical Recipes' Chebyshev fit, good to about 1.2e-7 everywhere
+fn erfc(x: f64) -> f64 {
+    let t = 1.0 / (1.0 + 0.5 * x.abs());
+    let polynomial = -x * x - 1.26551223
+        + t * (1.00002368 + t * (0.37409196 + t * (0.09678418 + t * (-0.18628806 + t * (0.27886807 + t * (-1.13520398 + t * (1.48851587 + t * (-0.82215223 + t * 0.17087277))))))));
+    let value = t * polynomial.exp();
+    if x >= 0.0 { value } else { 2.0 - value }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_report(playbook: &str, hands: usize, won: isize, start: usize) -> Report {
+        let mut report = Report {
+            name: format!("{}-{}", playbook, start),
+            playbook: playbook.to_string(),
+            total_hands: hands,
+            total_bet: hands * 2,
+            total_won: won,
+            total_threads: 4,
+            start,
+            end: start + 10,
+            duration: 10,
+            ..Default::default()
+        };
+        // Every hand bets 2 and wins or loses 2, with the given total
+        let wins = (hands as isize + won / 2) / 2;
+        for hand in 0..hands as isize {
+            report.add_true_count_hand(0, 2, if hand < wins { 2 } else { -2 });
+        }
+        report.rates();
+        report
+    }
+
+    #[test]
+    fn test_erfc() {
+        assert!((erfc(0.0) - 1.0).abs() < 1e-7);
+        assert!((erfc(1.0) - 0.157299207).abs() < 1e-7);
+        assert!((erfc(-1.0) - 1.842700793).abs() < 1e-7);
+        assert!((erfc(1.959964 / std::f64::consts::SQRT_2) - 0.05).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_merge() {
+        let first = make_report("single-deck-basic", 1000, 20, 200);
+        let second = make_report("single-deck-basic", 3000, -60, 100);
+        let comparison = Comparison::new(vec![first.clone(), second.clone()]).unwrap();
+        assert_eq!(comparison.files, vec![2]);
+        assert!(comparison.difference().is_none());
+        comparison.print();
+
+        let merged = &comparison.merged[0];
+        assert_eq!(merged.name, second.name);
+        assert_eq!((merged.start, merged.end, merged.duration), (100, 210, 20));
+        assert_eq!(merged.total_threads, 8);
+        assert_eq!(merged.total_hands, 4000);
+        assert_eq!(merged.total_won, -40);
+        assert_eq!(merged.true_counts[&0].hands, 4000);
+        assert!((merged.advantage - -0.5).abs() < 1e-9);
+        assert!(merged.standard_error < first.standard_error);
+    }
+
+    #[test]
+    fn test_compare() {
+        let reports = vec![
+            make_report("single-deck-basic", 10000, 400, 1),
+            make_report("single-deck-mimic", 10000, -400, 1),
+            make_report("single-deck-basic", 10000, 400, 2),
+        ];
+        let comparison = Comparison::new(reports).unwrap();
+        assert_eq!(comparison.files, vec![2, 1]);
+        comparison.print();
+
+        let difference = comparison.difference().unwrap();
+        assert!((difference.advantage - 4.0).abs() < 1e-9);
+        assert!((difference.z_score - difference.advantage / difference.standard_error).abs() < 1e-9);
+        assert!(difference.significant());
+
+        let close = Comparison::new(vec![make_report("a", 10000, 20, 1), make_report("b", 10000, 0, 1)]).unwrap();
+        assert!(!close.difference().unwrap().significant());
+
+        let three = vec![make_report("a", 10, 0, 1), make_report("b", 10, 0, 1), make_report("c", 10, 0, 1)];
+        assert!(matches!(Comparison::new(three), Err(StrikerError::InvalidReport(_))));
+        assert!(Comparison::new(Vec::new()).is_err());
+        assert!(Comparison::new(vec![make_report("a", 10, 0, 1), make_report("a", 10, 0, 1)]).is_err());
+        let mut sweep = vec![make_report("a", 10, 0, 1), make_report("b", 10, 0, 1)];
+        sweep[1].name = sweep[0].name.clone();
+        assert!(Comparison::new(sweep).is_ok());
+    }
+
+    #[test]
+    fn test_configuration() {
+        let cell = |penetration: f64| Some(json!({ "decks": "single-deck", "strategy": "basic", "penetration": penetration, "rules": {} }));
+        let mut first = make_report("single-deck-basic", 1000, 20, 1);
+        let mut second = make_report("single-deck-basic", 1000, -20, 2);
+        (first.cell, second.cell) = (cell(0.7), cell(0.7));
+        let comparison = Comparison::new(vec![first.clone(), second.clone()]).unwrap();
+        assert_eq!(comparison.files, vec![2]);
+        assert_eq!(comparison.merged[0].cell, cell(0.7));
+        assert_eq!(comparison.merged[0].total_hands, 2000);
+
+        // Two cells of one sweep share the playbook but not the penetration
+        second.cell = cell(0.8);
+        assert!(matches!(Comparison::new(vec![first.clone(), second.clone()]), Err(StrikerError::InvalidReport(_))));
+        second.cell = None;
+        assert!(Comparison::new(vec![first.clone(), second.clone()]).is_err());
+        second.cell = cell(0.7);
+        second.version = "other".to_string();
+        assert!(Comparison::new(vec![first, second]).is_err());
+    }
+
+    #[test]
+    fn test_load_and_write() {
+        let directory = std::env::temp_dir().join(format!("striker_comparison_{}", std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+        let mut paths = Vec::new();
+        for (index, report) in [make_report("six-shoe-basic", 1000, 10, 1), make_report("six-shoe-basic", 1000, -30, 2)].iter().enumerate() {
+            let path = directory.join(format!("run-{}.json", index));
+            fs::write(&path, report.to_json_object().to_string()).unwrap();
+            paths.push(path.to_string_lossy().to_string());
+        }
+        let comparison = Comparison::load(&paths).unwrap();
+        assert_eq!(comparison.merged[0].total_hands, 2000);
+
+        let output = directory.join("merged");
+        comparison.write(&output.to_string_lossy()).unwrap();
+        let merged = fs::read_to_string(output.join("six-shoe-basic.json")).unwrap();
+        let merged = Report::from_json_object(&serde_json::from_str(&merged).unwrap()).unwrap();
+        assert_eq!(merged.total_won, -20);
+
+        paths.push(directory.join("missing.json").to_string_lossy().to_string());
+        assert!(Comparison::load(&paths).is_err());
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
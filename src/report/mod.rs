@@ -1,2 +1,3 @@
 // report module
+pub mod comparison;
 pub mod report;
@@ -1,11 +1,112 @@
 use crate::arguments::parameters::Parameters;
 use crate::constants::constants::{BILLION, NUMBER_OF_HANDS_DATABASE, STRIKER_VERSION};
+use crate::errors::errors::StrikerError;
 use crate::traits::traits::JsonSender;
+use crate::utilities::topology::Topology;
 use crate::utilities::utilities::get_simulations_url;
 use num_format::{Locale, ToFormattedString};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::time::SystemTime;
 
-#[derive(Default, Debug, Clone)]
+// Results of the hands played at a single true count, measured when the bet is placed
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TrueCountBucket {
+    pub hands: usize,
+    pub total_bet: usize,
+    pub total_won: isize,
+    pub total_squares: usize,
+}
+
+impl TrueCountBucket {
+    pub fn merge(&mut self, other: &TrueCountBucket) {
+        self.hands += other.hands;
+        self.total_bet += other.total_bet;
+        self.total_won += other.total_won;
+        self.total_squares += other.total_squares;
+    }
+
+    pub fn advantage(&self) -> f64 {
+        self.total_won as f64 / self.total_bet as f64 * 100.0
+    }
+
+    // Variance of the amount won per hand
+    pub fn variance(&self) -> f64 {
+        let mean = self.total_won as f64 / self.hands as f64;
+        self.total_squares as f64 / self.hands as f64 - mean * mean
+    }
+
+    // Standard error of the advantage in percent, infinite until there are hands to measure
+    pub fn standard_error(&self) -> f64 {
+        if self.hands < 2 || self.total_bet == 0 {
+            return f64::INFINITY;
+        }
+        let average_bet = self.total_bet as f64 / self.hands as f64;
+        (self.variance().max(0.0) / self.hands as f64).sqrt() / average_bet * 100.0
+    }
+
+    // What was added since an earlier copy of the same bucket
+    pub fn since(&self, earlier: &TrueCountBucket) -> TrueCountBucket {
+        TrueCountBucket {
+            hands: self.hands - earlier.hands,
+            total_bet: self.total_bet - earlier.total_bet,
+            total_won: self.total_won - earlier.total_won,
+            total_squares: self.total_squares - earlier.total_squares,
+        }
+    }
+
+    pub fn from_json_object(json: &serde_json::Value) -> (isize, TrueCountBucket) {
+        let count = |key: &str| json[key].as_u64().unwrap_or_default() as usize;
+        let bucket = TrueCountBucket {
+            hands: count("hands"),
+            total_bet: count("total_bet"),
+            total_won: json["total_won"].as_i64().unwrap_or_default() as isize,
+            total_squares: count("total_squares"),
+        };
+        (json["true_count"].as_i64().unwrap_or_default() as isize, bucket)
+    }
+
+    pub fn to_json_object(&self, true_count: isize) -> serde_json::Value {
+        serde_json::json!({
+            "true_count": true_count,
+            "hands": self.hands,
+            "total_bet": self.total_bet,
+            "total_won": self.total_won,
+            "total_squares": self.total_squares,
+        })
+    }
+}
+
+// Results of the hands that consulted a single chart cell
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DecisionBucket {
+    pub hands: usize,
+    pub total_won: isize,
+}
+
+// Dealer final results: totals 17 through 21, bust and blackjack
+pub const DEALER_OUTCOMES: [&str; 7] = ["17", "18", "19", "20", "21", "bust", "blackjack"];
+
+// Chart name, row key and dealer up card value of a chart cell
+pub type DecisionKey = (String, String, usize);
+
+// JSON objects only take string keys, so the chart cells are kept as a list of key and bucket pairs
+mod decision_entries {
+    use super::{DecisionBucket, DecisionKey};
+    use serde::{Deserialize, Deserializer, Serializer};
+    use std::collections::BTreeMap;
+
+    pub fn serialize<S: Serializer>(decisions: &BTreeMap<DecisionKey, DecisionBucket>, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(decisions.iter())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<BTreeMap<DecisionKey, DecisionBucket>, D::Error> {
+        Ok(Vec::<(DecisionKey, DecisionBucket)>::deserialize(deserializer)?.into_iter().collect())
+    }
+}
+
+// Serialized whole for a checkpoint, the rates are worked out again when the run finishes
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
 pub struct Report {
     pub name: String,
     pub version: String,
@@ -28,11 +129,29 @@ pub struct Report {
     pub out_of_cards: usize,
     pub total_shuffles: usize,
     pub total_threads: usize,
+    pub pinned_threads: bool,
+    pub topology: Option<Topology>,
     pub start: usize,
     pub end: usize,
     pub duration: usize,
+    #[serde(skip)]
     pub advantage: f64,
+    #[serde(skip)]
+    pub standard_error: f64,
+    pub target_stderr: Option<f64>,
+    #[serde(skip)]
     pub per_billion: f64,
+    pub true_counts: BTreeMap<isize, TrueCountBucket>,
+    #[serde(with = "decision_entries")]
+    pub decisions: BTreeMap<DecisionKey, DecisionBucket>,
+    pub dealer_outcomes: [[usize; 7]; 12],
+    pub side_bets: BTreeMap<String, BTreeMap<isize, TrueCountBucket>>,
+    pub ace_predictions: usize,
+    pub ace_hits: usize,
+    pub ace_rounds: TrueCountBucket,
+    // The sweep cell a saved report was run as, kept so only runs of the same cell are merged
+    #[serde(skip)]
+    pub cell: Option<serde_json::Value>,
 }
 
 impl Report {
@@ -49,6 +168,9 @@ impl Report {
         self.decks = parameters.decks.clone();
         self.epoch = parameters.epoch.clone();
         self.total_threads = parameters.number_of_threads;
+        self.pinned_threads = parameters.pin_threads;
+        self.topology = Some(parameters.topology.clone());
+        self.target_stderr = parameters.target_stderr;
         self.start = SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs() as usize;
     }
 
@@ -66,55 +188,145 @@ impl Report {
         self.total_pushes += other.total_pushes;
         self.total_shuffles += other.total_shuffles;
         self.out_of_cards += other.out_of_cards;
+        for (true_count, bucket) in &other.true_counts {
+            self.true_counts.entry(*true_count).or_default().merge(bucket);
+        }
+        for (key, bucket) in &other.decisions {
+            let decision = self.decisions.entry(key.clone()).or_default();
+            decision.hands += bucket.hands;
+            decision.total_won += bucket.total_won;
+        }
+        self.ace_predictions += other.ace_predictions;
+        self.ace_hits += other.ace_hits;
+        self.ace_rounds.merge(&other.ace_rounds);
+        for (name, true_counts) in &other.side_bets {
+            let side_bet = self.side_bets.entry(name.clone()).or_default();
+            for (true_count, bucket) in true_counts {
+                side_bet.entry(*true_count).or_default().merge(bucket);
+            }
+        }
+        for (up, outcomes) in other.dealer_outcomes.iter().enumerate() {
+            for (outcome, count) in outcomes.iter().enumerate() {
+                self.dealer_outcomes[up][outcome] += count;
+            }
+        }
+    }
+
+    // Every round across the true counts
+    pub fn totals(&self) -> TrueCountBucket {
+        let mut totals = TrueCountBucket::default();
+        for bucket in self.true_counts.values() {
+            totals.merge(bucket);
+        }
+        totals
+    }
+
+    pub fn add_true_count_hand(&mut self, true_count: isize, bet: usize, won: isize) {
+        let bucket = self.true_counts.entry(true_count).or_default();
+        bucket.hands += 1;
+        bucket.total_bet += bet;
+        bucket.total_won += won;
+        bucket.total_squares += won.unsigned_abs().pow(2);
+    }
+
+    // Side bets are a unit bet, settled at the true count of the round
+    pub fn add_side_bet(&mut self, name: &str, true_count: isize, won: isize) {
+        let bucket = self.side_bets.entry(name.to_string()).or_default().entry(true_count).or_default();
+        bucket.hands += 1;
+        bucket.total_bet += 1;
+        bucket.total_won += won;
+        bucket.total_squares += won.unsigned_abs().pow(2);
+    }
+
+    pub fn add_dealer_outcome(&mut self, up: usize, total: usize, blackjack: bool) {
+        let outcome = if blackjack {
+            6
+        } else if total > 21 {
+            5
+        } else {
+            total.clamp(17, 21) - 17
+        };
+        self.dealer_outcomes[up][outcome] += 1;
+    }
+
+    pub fn add_decision_hand(&mut self, key: &DecisionKey, won: isize) {
+        let decision = self.decisions.entry(key.clone()).or_default();
+        decision.hands += 1;
+        decision.total_won += won;
+    }
+
+    // One line per chart cell used, ready to pivot into a chart shaped heatmap
+    pub fn to_decisions_csv(&self) -> String {
+        let mut csv = String::from("chart,key,up,hands,total_won,average_won\n");
+        for ((chart, key, up), decision) in &self.decisions {
+            let up = Self::up_label(*up);
+            csv.push_str(&format!(
+                "{},{},{},{},{},{:.6}\n",
+                chart,
+                key,
+                up,
+                decision.hands,
+                decision.total_won,
+                decision.total_won as f64 / decision.hands as f64
+            ));
+        }
+        csv
     }
 
     pub fn finish(&mut self) {
         self.end = SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs() as usize;
         self.duration = self.end - self.start;
+        self.rates();
+    }
+
+    // The advantage, its standard error and the time per billion hands from the totals
+    pub fn rates(&mut self) {
         self.advantage = (self.total_won as f64 / self.total_bet as f64) * 100.0;
+        self.standard_error = self.totals().standard_error();
         self.per_billion = self.duration as f64 * BILLION as f64 / self.total_hands as f64;
     }
 
-    pub fn insert(&mut self, sender: &dyn JsonSender) {
+    // Too few hands is not an error, the results are simply kept out of the database
+    pub fn insert(&mut self, sender: &dyn JsonSender) -> Result<(), StrikerError> {
         if self.total_hands < NUMBER_OF_HANDS_DATABASE {
             println!(
                 "    Error: Not enough hands played ({}). Minimum required is {}",
                 self.total_hands.to_formatted_string(&Locale::en),
                 NUMBER_OF_HANDS_DATABASE.to_formatted_string(&Locale::en)
             );
-            return;
+            return Ok(());
         }
 
         let json = self.to_json_object();
         let url = format!(
             "http://{}/{}/{}/{}",
-            get_simulations_url().expect("Missing simulation URL"),
+            get_simulations_url().ok_or(StrikerError::MissingUrl("STRIKER_URL_SIMULATIONS"))?,
             self.simulator,
             self.playbook,
             self.name
         );
-        match sender.send_json(&url, json) {
-            Ok(value) => {
-                if let Some(status) = value.get("status") {
-                    if status == "success" {
-                        println!("Insert successful");
-                    } else {
-                        println!("Request failed with status: {}", status);
-                    }
-                }
-            }
-            Err(e) => {
-                println!("HTTP error: {}", e);
+        let value = sender.send_json(&url, json).map_err(|e| StrikerError::Send {
+            url: url.clone(),
+            message: e.to_string(),
+        })?;
+        if let Some(status) = value.get("status") {
+            if status == "success" {
+                println!("Insert successful");
+            } else {
+                println!("Request failed with status: {}", status);
             }
         }
+        Ok(())
     }
 
     pub fn to_json_object(&self) -> serde_json::Value {
-        serde_json::json!({
+        let mut value = serde_json::json!({
             "guid": self.name,
             "version": STRIKER_VERSION,
             "simulator": self.simulator,
             "threads": self.total_threads,
+            "pinned_threads": self.pinned_threads,
+            "topology": self.topology.as_ref().map(|topology| topology.to_json_object()),
             "playbook": self.playbook,
             "decks": self.decks,
             "strategy": self.strategy,
@@ -132,12 +344,159 @@ impl Report {
             "total_loses": self.total_loses,
             "total_pushes": self.total_pushes,
             "advantage": self.advantage,
+            "standard_error": if self.standard_error.is_finite() { Some(self.standard_error) } else { None },
+            "target_stderr": self.target_stderr,
             "epoch": self.epoch,
             "start": self.start,
             "end": self.end,
             "duration": self.duration,
             "per_billion": self.per_billion,
-        })
+            "dealer_outcomes": self.dealer_outcomes_to_json(),
+            "true_counts": self.true_counts.iter().map(|(true_count, bucket)| bucket.to_json_object(*true_count)).collect::<Vec<_>>(),
+            "side_bets": self.side_bets_to_json(),
+            "decisions": self.decisions_to_json(),
+            "ace_sequencing": {
+                "predictions": self.ace_predictions,
+                "hits": self.ace_hits,
+                "hands": self.ace_rounds.hands,
+                "total_bet": self.ace_rounds.total_bet,
+                "total_won": self.ace_rounds.total_won,
+            },
+        });
+        if let Some(cell) = &self.cell {
+            value["cell"] = cell.clone();
+        }
+        value
+    }
+
+    // The inverse of to_json_object for a saved report
+    pub fn from_json_object(json: &serde_json::Value) -> Result<Self, StrikerError> {
+        for key in ["playbook", "hands", "total_bet", "total_won"] {
+            if json.get(key).is_none() {
+                return Err(StrikerError::InvalidReport(format!("missing {}", key)));
+            }
+        }
+        let text = |key: &str| json[key].as_str().unwrap_or_default().to_string();
+        let count = |key: &str| json[key].as_u64().unwrap_or_default() as usize;
+        let mut report = Report {
+            name: text("guid"),
+            version: text("version"),
+            simulator: text("simulator"),
+            playbook: text("playbook"),
+            strategy: text("strategy"),
+            decks: text("decks"),
+            epoch: text("epoch"),
+            total_rounds: count("rounds"),
+            total_hands: count("hands"),
+            total_bet: count("total_bet"),
+            total_won: json["total_won"].as_i64().unwrap_or_default() as isize,
+            total_blackjacks: count("total_blackjacks"),
+            total_doubles: count("total_doubles"),
+            total_splits: count("total_splits"),
+            total_splits_ace: count("total_splits_ace"),
+            total_wins: count("total_wins"),
+            total_loses: count("total_loses"),
+            total_pushes: count("total_pushes"),
+            out_of_cards: count("out_of_cards"),
+            total_shuffles: count("total_shuffles"),
+            total_threads: count("threads"),
+            pinned_threads: json["pinned_threads"].as_bool().unwrap_or_default(),
+            topology: serde_json::from_value(json["topology"].clone()).ok(),
+            start: count("start"),
+            end: count("end"),
+            duration: count("duration"),
+            target_stderr: json["target_stderr"].as_f64(),
+            ace_predictions: json["ace_sequencing"]["predictions"].as_u64().unwrap_or_default() as usize,
+            ace_hits: json["ace_sequencing"]["hits"].as_u64().unwrap_or_default() as usize,
+            cell: json.get("cell").cloned(),
+            ..Default::default()
+        };
+        report.ace_rounds.hands = json["ace_sequencing"]["hands"].as_u64().unwrap_or_default() as usize;
+        report.ace_rounds.total_bet = json["ace_sequencing"]["total_bet"].as_u64().unwrap_or_default() as usize;
+        report.ace_rounds.total_won = json["ace_sequencing"]["total_won"].as_i64().unwrap_or_default() as isize;
+        for bucket in json["true_counts"].as_array().into_iter().flatten() {
+            let (true_count, bucket) = TrueCountBucket::from_json_object(bucket);
+            report.true_counts.insert(true_count, bucket);
+        }
+        for (name, buckets) in json["side_bets"].as_object().into_iter().flatten() {
+            let side_bet = report.side_bets.entry(name.clone()).or_default();
+            for bucket in buckets.as_array().into_iter().flatten() {
+                let (true_count, bucket) = TrueCountBucket::from_json_object(bucket);
+                side_bet.insert(true_count, bucket);
+            }
+        }
+        for decision in json["decisions"].as_array().into_iter().flatten() {
+            let key = (
+                decision["chart"].as_str().unwrap_or_default().to_string(),
+                decision["key"].as_str().unwrap_or_default().to_string(),
+                decision["up"].as_u64().unwrap_or_default() as usize,
+            );
+            let bucket = report.decisions.entry(key).or_default();
+            bucket.hands += decision["hands"].as_u64().unwrap_or_default() as usize;
+            bucket.total_won += decision["total_won"].as_i64().unwrap_or_default() as isize;
+        }
+        for (label, outcomes) in json["dealer_outcomes"].as_object().into_iter().flatten() {
+            let Some(up) = Self::up_from_label(label) else {
+                return Err(StrikerError::InvalidReport(format!("unknown dealer up card {}", label)));
+            };
+            for (outcome, name) in DEALER_OUTCOMES.iter().enumerate() {
+                report.dealer_outcomes[up][outcome] = outcomes[*name].as_u64().unwrap_or_default() as usize;
+            }
+        }
+        report.rates();
+        Ok(report)
+    }
+
+    // The up card of a hole card chart cell is the dealer's total, so it is kept as a number
+    fn decisions_to_json(&self) -> serde_json::Value {
+        let decisions: Vec<serde_json::Value> = self
+            .decisions
+            .iter()
+            .map(|((chart, key, up), decision)| {
+                serde_json::json!({
+                    "chart": chart,
+                    "key": key,
+                    "up": up,
+                    "hands": decision.hands,
+                    "total_won": decision.total_won,
+                })
+            })
+            .collect();
+        serde_json::Value::Array(decisions)
+    }
+
+    fn side_bets_to_json(&self) -> serde_json::Value {
+        let mut map = serde_json::Map::new();
+        for (name, true_counts) in &self.side_bets {
+            let buckets: Vec<serde_json::Value> = true_counts.iter().map(|(true_count, bucket)| bucket.to_json_object(*true_count)).collect();
+            map.insert(name.clone(), serde_json::Value::Array(buckets));
+        }
+        serde_json::Value::Object(map)
+    }
+
+    fn dealer_outcomes_to_json(&self) -> serde_json::Value {
+        let mut map = serde_json::Map::new();
+        for (up, outcomes) in self.dealer_outcomes.iter().enumerate().skip(2) {
+            let row: serde_json::Map<String, serde_json::Value> = DEALER_OUTCOMES.iter().zip(outcomes).map(|(name, count)| (name.to_string(), (*count).into())).collect();
+            map.insert(Self::up_label(up), serde_json::Value::Object(row));
+        }
+        serde_json::Value::Object(map)
+    }
+
+    fn up_from_label(label: &str) -> Option<usize> {
+        match label {
+            "X" => Some(10),
+            "A" => Some(11),
+            label => label.parse().ok().filter(|up| (2..10).contains(up)),
+        }
+    }
+
+    fn up_label(up: usize) -> String {
+        match up {
+            10 => "X".to_string(),
+            11 => "A".to_string(),
+            up => up.to_string(),
+        }
     }
 
     pub fn print(&self) {
@@ -201,6 +560,12 @@ impl Report {
         );
         println!("    {:<26}: {:>17} seconds", "Total time", self.duration.to_formatted_string(&Locale::en));
         println!("    {:<26}: {:>17} threads", "Number of threads", self.total_threads.to_formatted_string(&Locale::en));
+        if let Some(topology) = &self.topology {
+            println!(
+                "    {:<26}: {:>17} available, {} physical cores",
+                "Number of CPUs", topology.available_cpus, topology.physical_cores
+            );
+        }
         println!(
             "    {:<26}: {:>17} seconds per {} hands",
             "Average time",
@@ -208,6 +573,97 @@ impl Report {
             BILLION.to_formatted_string(&Locale::en)
         );
         println!("    {:<26}: {:>17} {:+08.3} %", "Player advantage", "", self.advantage);
+        println!("    {:<26}: {:>17} {:+08.3} %", "Standard error", "", self.standard_error);
+        if let Some(target) = self.target_stderr {
+            println!("    {:<26}: {:>17} {:+08.3} %", "Target standard error", "", target);
+        }
+        self.print_true_counts();
+        self.print_dealer_outcomes();
+        self.print_side_bets();
+        self.print_ace_sequencing();
+    }
+
+    // How often a predicted ace arrived and what betting on the predictions earned
+    pub fn print_ace_sequencing(&self) {
+        if self.ace_predictions == 0 {
+            return;
+        }
+        println!(
+            "    {:<26}: {:>17} {:+08.3} % aces found",
+            "Ace predictions",
+            self.ace_predictions.to_formatted_string(&Locale::en),
+            self.ace_hits as f64 / self.ace_predictions as f64 * 100.0
+        );
+        println!("    {:<26}: {:>17} {:+08.3} %", "Ace prediction advantage", "", self.ace_rounds.advantage());
+    }
+
+    // House edge of each side bet, then its expectation by true count to spot countable bets
+    pub fn print_side_bets(&self) {
+        for (name, true_counts) in &self.side_bets {
+            let mut total = TrueCountBucket::default();
+            for bucket in true_counts.values() {
+                total.merge(bucket);
+            }
+            println!(
+                "    {:<26}: {:>17} {:+08.3} % house edge",
+                format!("Side bet {}", name),
+                total.hands.to_formatted_string(&Locale::en),
+                -total.advantage()
+            );
+            println!("    {:>6} {:>17} {:>10} {:>10}", "Count", "Hands", "EV %", "Variance");
+            for (true_count, bucket) in true_counts {
+                println!(
+                    "    {:>6} {:>17} {:>+10.3} {:>10.3}",
+                    true_count,
+                    bucket.hands.to_formatted_string(&Locale::en),
+                    bucket.advantage(),
+                    bucket.variance()
+                );
+            }
+        }
+    }
+
+    pub fn print_dealer_outcomes(&self) {
+        if self.dealer_outcomes.iter().all(|outcomes| outcomes.iter().all(|count| *count == 0)) {
+            return;
+        }
+        println!("    {:<26}", "Dealer outcomes (%)");
+        print!("    {:>6}", "Up");
+        for name in DEALER_OUTCOMES {
+            print!(" {:>9}", name);
+        }
+        println!(" {:>17}", "Hands");
+        for (up, outcomes) in self.dealer_outcomes.iter().enumerate().skip(2) {
+            let hands: usize = outcomes.iter().sum();
+            print!("    {:>6}", Self::up_label(up));
+            for count in outcomes {
+                print!(" {:>9.3}", *count as f64 / hands.max(1) as f64 * 100.0);
+            }
+            println!(" {:>17}", hands.to_formatted_string(&Locale::en));
+        }
+    }
+
+    pub fn print_true_counts(&self) {
+        if self.true_counts.is_empty() {
+            return;
+        }
+        println!("    {:<26}", "True counts");
+        println!(
+            "    {:>6} {:>17} {:>9} {:>10} {:>17} {:>10} {:>10}",
+            "Count", "Hands", "Freq %", "Avg bet", "Total won", "Adv %", "Variance"
+        );
+        for (true_count, bucket) in &self.true_counts {
+            println!(
+                "    {:>6} {:>17} {:>9.3} {:>10.3} {:>17} {:>+10.3} {:>10.3}",
+                true_count,
+                bucket.hands.to_formatted_string(&Locale::en),
+                bucket.hands as f64 / self.total_hands as f64 * 100.0,
+                bucket.total_bet as f64 / bucket.hands as f64,
+                bucket.total_won.to_formatted_string(&Locale::en),
+                bucket.advantage(),
+                bucket.variance()
+            );
+        }
     }
 }
 
@@ -250,6 +706,160 @@ mod tests {
         report.print();
     }
 
+    #[test]
+    fn test_true_counts_print_and_to_json() {
+        let mut report = Report::default();
+        report.add_true_count_hand(-1, 2, -2);
+        report.add_true_count_hand(3, 8, 8);
+        report.add_true_count_hand(3, 8, 0);
+        report.total_hands = 3;
+        report.print();
+
+        let bucket = &report.true_counts[&3];
+        assert!((bucket.advantage() - 50.0).abs() < 1e-9);
+        assert!((bucket.variance() - 16.0).abs() < 1e-9);
+        assert!((bucket.standard_error() - 100.0 / 8.0_f64.sqrt()).abs() < 1e-9);
+        let totals = report.totals();
+        assert_eq!((totals.hands, totals.total_bet, totals.total_won, totals.total_squares), (3, 18, 6, 68));
+        assert_eq!(totals.since(bucket), report.true_counts[&-1]);
+        assert!(TrueCountBucket::default().standard_error().is_infinite());
+
+        let json = report.to_json_object();
+        assert_eq!(json["true_counts"].as_array().unwrap().len(), 2);
+        assert_eq!(json["true_counts"][1]["true_count"], 3);
+        assert_eq!(json["true_counts"][1]["hands"], 2);
+        assert_eq!(json["true_counts"][1]["total_squares"], 64);
+    }
+
+    #[test]
+    fn test_decisions_merge_and_csv() {
+        let mut report1 = Report::default();
+        let mut report2 = Report::default();
+        let stand = ("hard-stand".to_string(), "16".to_string(), 10);
+        let split = ("pair-split".to_string(), "8".to_string(), 11);
+        report1.add_decision_hand(&stand, -2);
+        report2.add_decision_hand(&stand, 2);
+        report2.add_decision_hand(&stand, -4);
+        report2.add_decision_hand(&split, 4);
+        report1.merge(&report2);
+
+        assert_eq!(report1.decisions[&stand], DecisionBucket { hands: 3, total_won: -4 });
+        assert_eq!(report1.decisions[&split], DecisionBucket { hands: 1, total_won: 4 });
+        let csv = report1.to_decisions_csv();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "chart,key,up,hands,total_won,average_won");
+        assert_eq!(lines[1], "hard-stand,16,X,3,-4,-1.333333");
+        assert_eq!(lines[2], "pair-split,8,A,1,4,4.000000");
+    }
+
+    #[test]
+    fn test_dealer_outcomes() {
+        let mut report1 = Report::default();
+        let mut report2 = Report::default();
+        report1.add_dealer_outcome(6, 26, false);
+        report2.add_dealer_outcome(6, 17, false);
+        report2.add_dealer_outcome(11, 21, true);
+        report2.add_dealer_outcome(10, 21, false);
+        report1.merge(&report2);
+        report1.print_dealer_outcomes();
+
+        assert_eq!(report1.dealer_outcomes[6], [1, 0, 0, 0, 0, 1, 0]);
+        assert_eq!(report1.dealer_outcomes[11][6], 1);
+        assert_eq!(report1.dealer_outcomes[10][4], 1);
+        let json = report1.to_json_object();
+        assert_eq!(json["dealer_outcomes"]["6"]["bust"], 1);
+        assert_eq!(json["dealer_outcomes"]["A"]["blackjack"], 1);
+        assert_eq!(json["dealer_outcomes"]["X"]["21"], 1);
+    }
+
+    #[test]
+    fn test_side_bets_merge_and_json() {
+        let mut report1 = Report::default();
+        let mut report2 = Report::default();
+        report1.add_side_bet("perfect-pairs", 0, -1);
+        report2.add_side_bet("perfect-pairs", 0, 25);
+        report2.add_side_bet("perfect-pairs", 2, -1);
+        report2.add_side_bet("21+3", -1, 5);
+        report1.merge(&report2);
+        report1.print_side_bets();
+
+        let bucket = &report1.side_bets["perfect-pairs"][&0];
+        assert_eq!(bucket.hands, 2);
+        assert_eq!(bucket.total_won, 24);
+        assert!((bucket.advantage() - 1200.0).abs() < 1e-9);
+        let json = report1.to_json_object();
+        assert_eq!(json["side_bets"]["perfect-pairs"].as_array().unwrap().len(), 2);
+        assert_eq!(json["side_bets"]["21+3"][0]["total_won"], 5);
+    }
+
+    #[test]
+    fn test_ace_sequencing() {
+        let mut report1 = Report::default();
+        let mut report2 = Report::default();
+        report1.ace_predictions = 2;
+        report1.ace_hits = 1;
+        report2.ace_predictions = 2;
+        report2.ace_rounds.hands = 1;
+        report2.ace_rounds.total_bet = 20;
+        report2.ace_rounds.total_won = 30;
+        report1.merge(&report2);
+        report1.print_ace_sequencing();
+
+        let json = report1.to_json_object();
+        assert_eq!(json["ace_sequencing"]["predictions"], 4);
+        assert_eq!(json["ace_sequencing"]["hits"], 1);
+        assert_eq!(json["ace_sequencing"]["total_won"], 30);
+    }
+
+    #[test]
+    fn test_from_json_object() {
+        let mut report = Report {
+            name: "run".to_string(),
+            playbook: "single-deck-basic".to_string(),
+            total_rounds: 10,
+            total_hands: 3,
+            total_bet: 18,
+            total_won: 6,
+            total_threads: 2,
+            duration: 5,
+            ..Default::default()
+        };
+        report.add_true_count_hand(-1, 2, -2);
+        report.add_true_count_hand(3, 8, 8);
+        report.add_true_count_hand(3, 8, 0);
+        report.add_side_bet("21+3", -1, 5);
+        report.add_dealer_outcome(10, 26, false);
+        report.add_dealer_outcome(11, 21, true);
+        report.ace_predictions = 4;
+        report.ace_rounds.hands = 2;
+        report.ace_rounds.total_won = -3;
+        report.add_decision_hand(&("hard-stand".to_string(), "16".to_string(), 10), -2);
+        report.add_decision_hand(&("hole-stand".to_string(), "S17".to_string(), 20), 4);
+        report.rates();
+
+        let loaded = Report::from_json_object(&report.to_json_object()).unwrap();
+        assert_eq!(loaded.name, "run");
+        assert_eq!(loaded.cell, None);
+        assert_eq!(loaded.playbook, report.playbook);
+        assert_eq!((loaded.total_rounds, loaded.total_hands, loaded.total_bet, loaded.total_won), (10, 3, 18, 6));
+        assert_eq!(loaded.total_threads, 2);
+        assert_eq!(loaded.true_counts, report.true_counts);
+        assert_eq!(loaded.side_bets, report.side_bets);
+        assert_eq!(loaded.dealer_outcomes, report.dealer_outcomes);
+        assert_eq!((loaded.ace_predictions, loaded.ace_rounds.hands, loaded.ace_rounds.total_won), (4, 2, -3));
+        assert_eq!(loaded.decisions, report.decisions);
+        assert!((loaded.advantage - report.advantage).abs() < 1e-9);
+        assert!((loaded.standard_error - report.standard_error).abs() < 1e-9);
+
+        report.cell = Some(json!({ "penetration": 0.7 }));
+        assert_eq!(Report::from_json_object(&report.to_json_object()).unwrap().cell, report.cell);
+
+        assert!(matches!(Report::from_json_object(&json!({ "hands": 1 })), Err(StrikerError::InvalidReport(_))));
+        let mut unknown = report.to_json_object();
+        unknown["dealer_outcomes"]["K"] = json!({});
+        assert!(Report::from_json_object(&unknown).is_err());
+    }
+
     #[test]
     fn test_parameters_to_json() {
         let report = Report::default();
@@ -263,9 +873,9 @@ mod tests {
         unsafe {
             env::set_var("STRIKER_URL_SIMULATIONS", "https://example.com/simulations");
         }
-        report.insert(&mock);
+        report.insert(&mock).unwrap();
         report.total_hands = BILLION;
-        report.insert(&mock);
+        report.insert(&mock).unwrap();
         unsafe {
             env::remove_var("STRIKER_URL_SIMULATIONS");
         }
@@ -278,9 +888,9 @@ mod tests {
         unsafe {
             env::set_var("STRIKER_URL_SIMULATIONS", "https://example.com/simulations");
         }
-        report.insert(&mock);
+        report.insert(&mock).unwrap();
         report.total_hands = BILLION;
-        report.insert(&mock);
+        report.insert(&mock).unwrap();
         unsafe {
             env::remove_var("STRIKER_URL_SIMULATIONS");
         }
@@ -308,6 +918,9 @@ mod tests {
         report2.total_loses = test_count;
         report2.total_pushes = test_count;
 
+        report2.add_true_count_hand(2, 4, -4);
+        report2.add_true_count_hand(2, 4, 6);
+
         report1.merge(&report2);
         report1.start -= 20;
         report1.finish();
@@ -323,6 +936,15 @@ mod tests {
         assert_eq!(report1.total_wins, test_count);
         assert_eq!(report1.total_loses, test_count);
         assert_eq!(report1.total_pushes, test_count);
+        assert_eq!(
+            report1.true_counts[&2],
+            TrueCountBucket {
+                hands: 2,
+                total_bet: 8,
+                total_won: 2,
+                total_squares: 52,
+            }
+        );
 
         assert!(report1.duration > 0);
         assert!(report1.advantage > 0.0);
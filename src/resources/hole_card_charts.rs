@@ -0,0 +1,54 @@
+use once_cell::sync::Lazy;
+use serde_json::Value;
+use serde_json::json;
+
+// Hole card strategy, rows are player totals (soft totals prefixed with S) and columns are dealer totals 12 through 21
+#[allow(dead_code)]
+pub static HOLE_CARD_CHARTS_JSON: Lazy<Value> = Lazy::new(|| {
+    json!({
+        "hole-stand": {
+            "12": ["N", "Y", "Y", "Y", "Y", "N", "N", "N", "N", "N"],
+            "13": ["Y", "Y", "Y", "Y", "Y", "N", "N", "N", "N", "N"],
+            "14": ["Y", "Y", "Y", "Y", "Y", "N", "N", "N", "N", "N"],
+            "15": ["Y", "Y", "Y", "Y", "Y", "N", "N", "N", "N", "N"],
+            "16": ["Y", "Y", "Y", "Y", "Y", "N", "N", "N", "N", "N"],
+            "17": ["Y", "Y", "Y", "Y", "Y", "Y", "N", "N", "N", "N"],
+            "18": ["Y", "Y", "Y", "Y", "Y", "Y", "Y", "N", "N", "N"],
+            "19": ["Y", "Y", "Y", "Y", "Y", "Y", "Y", "Y", "N", "N"],
+            "20": ["Y", "Y", "Y", "Y", "Y", "Y", "Y", "Y", "Y", "N"],
+            "21": ["Y", "Y", "Y", "Y", "Y", "Y", "Y", "Y", "Y", "Y"],
+            "S17": ["N", "N", "N", "N", "N", "Y", "N", "N", "N", "N"],
+            "S18": ["Y", "Y", "Y", "Y", "Y", "Y", "Y", "N", "N", "N"],
+            "S19": ["Y", "Y", "Y", "Y", "Y", "Y", "Y", "Y", "N", "N"],
+            "S20": ["Y", "Y", "Y", "Y", "Y", "Y", "Y", "Y", "Y", "N"],
+            "S21": ["Y", "Y", "Y", "Y", "Y", "Y", "Y", "Y", "Y", "Y"]
+        },
+        "hole-double": {
+            "8": ["Y", "Y", "Y", "Y", "Y", "N", "N", "N", "N", "N"],
+            "9": ["Y", "Y", "Y", "Y", "Y", "N", "N", "N", "N", "N"],
+            "10": ["Y", "Y", "Y", "Y", "Y", "Y", "N", "N", "N", "N"],
+            "11": ["Y", "Y", "Y", "Y", "Y", "Y", "Y", "N", "N", "N"],
+            "S13": ["Y", "Y", "Y", "Y", "Y", "N", "N", "N", "N", "N"],
+            "S14": ["Y", "Y", "Y", "Y", "Y", "N", "N", "N", "N", "N"],
+            "S15": ["Y", "Y", "Y", "Y", "Y", "N", "N", "N", "N", "N"],
+            "S16": ["Y", "Y", "Y", "Y", "Y", "N", "N", "N", "N", "N"],
+            "S17": ["Y", "Y", "Y", "Y", "Y", "N", "N", "N", "N", "N"],
+            "S18": ["Y", "Y", "Y", "Y", "Y", "N", "N", "N", "N", "N"],
+            "S19": ["Y", "Y", "Y", "Y", "Y", "N", "N", "N", "N", "N"],
+            "S20": ["Y", "Y", "Y", "Y", "Y", "N", "N", "N", "N", "N"]
+        }
+    })
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hole_card_charts() {
+        assert_eq!(HOLE_CARD_CHARTS_JSON["hole-stand"]["16"][0], "Y");
+        assert_eq!(HOLE_CARD_CHARTS_JSON["hole-stand"]["16"][5], "N");
+        assert_eq!(HOLE_CARD_CHARTS_JSON["hole-double"]["S18"][4], "Y");
+        assert_eq!(HOLE_CARD_CHARTS_JSON["hole-double"]["11"][6], "Y");
+    }
+}
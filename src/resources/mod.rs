@@ -1,2 +1,4 @@
+pub mod hole_card_charts;
 pub mod rules_table;
 pub mod single_deck_basic;
+pub mod spanish_21_rules;
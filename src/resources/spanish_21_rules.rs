@@ -0,0 +1,37 @@
+use once_cell::sync::Lazy;
+use serde_json::Value;
+use serde_json::json;
+
+#[allow(dead_code)]
+pub static SPANISH_21_RULES_JSON: Lazy<Value> = Lazy::new(|| {
+    json!({
+        "playbook": "spanish-21",
+        "hitSoft17": true,
+        "surrender": true,
+        "doubleAnyTwoCards": true,
+        "doubleAfterSplit": true,
+        "resplitAces": true,
+        "hitSplitAces": true,
+        "blackjackBets": 2,
+        "blackjackPays": 3,
+        "penetration": 0.75,
+        "spanish21": true,
+        "doubleDownRescue": true
+    })
+});
+
+#[cfg(test)]
+mod tests {
+    use super::SPANISH_21_RULES_JSON;
+
+    #[test]
+    fn test_spanish_21_rules_json_contents() {
+        assert_eq!(SPANISH_21_RULES_JSON["playbook"], "spanish-21");
+        assert_eq!(SPANISH_21_RULES_JSON["hitSoft17"], true);
+        assert_eq!(SPANISH_21_RULES_JSON["surrender"], true);
+        assert_eq!(SPANISH_21_RULES_JSON["blackjackBets"], 2);
+        assert_eq!(SPANISH_21_RULES_JSON["blackjackPays"], 3);
+        assert_eq!(SPANISH_21_RULES_JSON["spanish21"], true);
+        assert_eq!(SPANISH_21_RULES_JSON["doubleDownRescue"], true);
+    }
+}
@@ -0,0 +1,255 @@
+use crate::arguments::parameters::Parameters;
+use crate::errors::errors::StrikerError;
+use crate::report::report::Report;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+// What is written to the checkpoint file, the reports of the earlier runs and of each table of this one
+// The shoes are shuffled from fresh system randomness rather than a seed, so there is no generator state to keep
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Saved {
+    name: String,
+    epoch: String,
+    playbook: String,
+    number_of_hands: usize,
+    duration: usize,
+    reports: Vec<Report>,
+}
+
+// The partial results of a run, written every so often so a crash only loses the hands since the last write
+pub struct Checkpoint {
+    path: Option<String>,
+    interval: Duration,
+    name: String,
+    epoch: String,
+    playbook: String,
+    number_of_hands: usize,
+    earlier: Report,
+    earlier_duration: usize,
+    tables: Mutex<Vec<Report>>,
+    snapshots: AtomicUsize,
+    written: Mutex<usize>,
+    generation: AtomicUsize,
+    last_write: Mutex<Instant>,
+    started: Instant,
+}
+
+impl Checkpoint {
+    // A run without a checkpoint file gets one that never writes
+    pub fn new(parameters: &Parameters) -> Self {
+        Self {
+            path: parameters.checkpoint.clone(),
+            interval: Duration::from_secs(parameters.checkpoint_interval as u64),
+            name: parameters.name.clone(),
+            epoch: parameters.epoch.clone(),
+            playbook: parameters.playbook.clone(),
+            number_of_hands: parameters.number_of_hands,
+            earlier: Report::new(),
+            earlier_duration: 0,
+            tables: Mutex::new(vec![Report::new(); parameters.number_of_threads]),
+            snapshots: AtomicUsize::new(0),
+            written: Mutex::new(0),
+            generation: AtomicUsize::new(0),
+            last_write: Mutex::new(Instant::now()),
+            started: Instant::now(),
+        }
+    }
+
+    // Reload the checkpoint file and take over the run's name and epoch, leaving only the hands it has not played
+    pub fn resume(parameters: &mut Parameters) -> Result<Self, StrikerError> {
+        let path = parameters
+            .checkpoint
+            .clone()
+            .ok_or_else(|| StrikerError::Checkpoint("no checkpoint file, use -V".to_string()))?;
+        let text = fs::read_to_string(&path).map_err(|e| StrikerError::file("read", &path, e))?;
+        let saved: Saved = serde_json::from_str(&text).map_err(|e| StrikerError::file("parse", &path, e))?;
+        if saved.playbook != parameters.playbook {
+            return Err(StrikerError::Checkpoint(format!(
+                "{} is a checkpoint of {}, not {}",
+                path, saved.playbook, parameters.playbook
+            )));
+        }
+
+        let mut earlier = Report::new();
+        for report in &saved.reports {
+            earlier.merge(report);
+        }
+        parameters.name = saved.name;
+        parameters.epoch = saved.epoch;
+        parameters.number_of_hands = saved.number_of_hands.saturating_sub(earlier.total_hands);
+
+        let mut checkpoint = Self::new(parameters);
+        checkpoint.number_of_hands = saved.number_of_hands;
+        checkpoint.earlier = earlier;
+        checkpoint.earlier_duration = saved.duration;
+        Ok(checkpoint)
+    }
+
+    // The results of the runs before this one
+    pub fn earlier(&self) -> &Report {
+        &self.earlier
+    }
+
+    // Seconds spent simulating before this run, the time in between does not count
+    pub fn earlier_duration(&self) -> usize {
+        self.earlier_duration
+    }
+
+    // Each interval every table hands in its results once, a table that has already done so for the current round waits for the next
+    pub fn due(&self, generation: usize) -> Option<usize> {
+        self.path.as_ref()?;
+        let current = self.generation.load(Ordering::Relaxed);
+        if generation < current {
+            return Some(current);
+        }
+        let mut last_write = self.last_write.lock().unwrap();
+        if last_write.elapsed() < self.interval {
+            return None;
+        }
+        *last_write = Instant::now();
+        Some(self.generation.fetch_add(1, Ordering::Relaxed) + 1)
+    }
+
+    // Keep a table's results so far and write them out with everyone else's, a failed write is reported but does not stop the run
+    pub fn publish(&self, index: usize, report: Report) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        // Copy the reports out so the other tables are not held up while the file is written
+        let (tables, snapshot) = {
+            let mut tables = self.tables.lock().unwrap();
+            if index < tables.len() {
+                tables[index] = report;
+            }
+            (tables.clone(), self.snapshots.fetch_add(1, Ordering::Relaxed) + 1)
+        };
+
+        // One write at a time, and a copy taken before the one already written is left out
+        let mut written = self.written.lock().unwrap();
+        if snapshot < *written {
+            return;
+        }
+        *written = snapshot;
+        if let Err(e) = self.write(path, &tables) {
+            println!("    Error: Cannot write checkpoint {}: {}", path, e);
+        }
+    }
+
+    // Write to a temporary file and rename it over the checkpoint, a crash in the middle leaves the last one whole
+    fn write(&self, path: &str, tables: &[Report]) -> Result<(), StrikerError> {
+        let saved = Saved {
+            name: self.name.clone(),
+            epoch: self.epoch.clone(),
+            playbook: self.playbook.clone(),
+            number_of_hands: self.number_of_hands,
+            duration: self.earlier_duration + self.started.elapsed().as_secs() as usize,
+            reports: std::iter::once(&self.earlier).chain(tables).filter(|report| report.total_hands > 0).cloned().collect(),
+        };
+        let json = serde_json::to_string(&saved).map_err(|e| StrikerError::file("write", path, e))?;
+        let temporary = format!("{}.tmp", path);
+        fs::write(&temporary, json).map_err(|e| StrikerError::file("write", &temporary, e))?;
+        fs::rename(&temporary, path).map_err(|e| StrikerError::file("write", path, e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arguments::arguments::Arguments;
+
+    fn make_parameters(path: &str) -> Parameters {
+        let arguments = Arguments {
+            number_of_hands: 1000,
+            number_of_threads: 2,
+            checkpoint: Some(path.to_string()),
+            checkpoint_interval: 3600,
+            ..Default::default()
+        };
+        Parameters::new(&arguments)
+    }
+
+    fn make_report(hands: usize) -> Report {
+        let mut report = Report::new();
+        report.total_hands = hands;
+        report.total_bet = hands * 2;
+        report.add_true_count_hand(1, 2, -2);
+        report.add_decision_hand(&("hard-stand".to_string(), "16".to_string(), 10), -2);
+        report
+    }
+
+    #[test]
+    fn test_due() {
+        let parameters = make_parameters("unused.checkpoint");
+        let checkpoint = Checkpoint::new(&parameters);
+        assert_eq!(checkpoint.due(0), None);
+
+        let mut parameters = make_parameters("unused.checkpoint");
+        parameters.checkpoint_interval = 0;
+        let checkpoint = Checkpoint::new(&parameters);
+        assert_eq!(checkpoint.due(0), Some(1));
+        assert_eq!(checkpoint.due(0), Some(1));
+        assert_eq!(checkpoint.due(1), Some(2));
+
+        let parameters = Parameters::new(&Arguments::default());
+        assert_eq!(Checkpoint::new(&parameters).due(0), None);
+    }
+
+    #[test]
+    fn test_publish_and_resume() {
+        let path = std::env::temp_dir().join(format!("striker_checkpoint_{}.json", std::process::id()));
+        let path = path.to_string_lossy().to_string();
+        let parameters = make_parameters(&path);
+        let checkpoint = Checkpoint::new(&parameters);
+        checkpoint.publish(0, make_report(300));
+        checkpoint.publish(1, make_report(200));
+
+        let mut resumed = make_parameters(&path);
+        resumed.name = "resumed".to_string();
+        resumed.epoch = "later".to_string();
+        let checkpoint = Checkpoint::resume(&mut resumed).unwrap();
+        assert_eq!(resumed.name, parameters.name);
+        assert_eq!(resumed.epoch, parameters.epoch);
+        assert_eq!(resumed.number_of_hands, 500);
+        assert_eq!(checkpoint.earlier().total_hands, 500);
+        assert_eq!(checkpoint.earlier().true_counts[&1].hands, 2);
+        assert_eq!(checkpoint.earlier().decisions.values().next().unwrap().hands, 2);
+
+        // A resumed run keeps counting towards the original number of hands
+        checkpoint.publish(0, make_report(100));
+        let mut resumed = make_parameters(&path);
+        Checkpoint::resume(&mut resumed).unwrap();
+        assert_eq!(resumed.number_of_hands, 400);
+
+        let mut other = make_parameters(&path);
+        other.playbook = "six-shoe-basic".to_string();
+        assert!(matches!(Checkpoint::resume(&mut other), Err(StrikerError::Checkpoint(_))));
+        std::fs::remove_file(&path).unwrap();
+        assert!(Checkpoint::resume(&mut make_parameters(&path)).is_err());
+    }
+
+    #[test]
+    fn test_publish_from_tables_at_once() {
+        let path = std::env::temp_dir().join(format!("striker_checkpoint_threads_{}.json", std::process::id()));
+        let path = path.to_string_lossy().to_string();
+        let checkpoint = Checkpoint::new(&make_parameters(&path));
+        std::thread::scope(|scope| {
+            for index in 0..2 {
+                let checkpoint = &checkpoint;
+                scope.spawn(move || {
+                    for hands in 1..=50 {
+                        checkpoint.publish(index, make_report(hands * (index + 1)));
+                    }
+                });
+            }
+        });
+
+        // The last copy taken holds every table's last report and is never left out
+        let mut resumed = make_parameters(&path);
+        let checkpoint = Checkpoint::resume(&mut resumed).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(checkpoint.earlier().total_hands, 150);
+    }
+}
@@ -0,0 +1,98 @@
+use crate::cards::{card::Card, wager::Wager};
+use serde_json::{Value, json};
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+
+// Line delimited JSON log of the sampled rounds, one object per round
+pub struct HandHistory {
+    file: File,
+    sample: usize,
+    rounds: usize,
+}
+
+impl HandHistory {
+    // Start an empty history before the tables open it
+    pub fn create(path: &str) -> io::Result<()> {
+        File::create(path).map(|_| ())
+    }
+
+    // Every table appends whole lines so the threads can share one file
+    pub fn open(path: &str, sample: usize) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self {
+            file,
+            sample: sample.max(1),
+            rounds: 0,
+        })
+    }
+
+    // Log one round out of every sample
+    pub fn sampled(&mut self) -> bool {
+        self.rounds += 1;
+        self.rounds.is_multiple_of(self.sample)
+    }
+
+    pub fn write(&mut self, record: &Value) -> io::Result<()> {
+        let mut line = record.to_string();
+        line.push('\n');
+        self.file.write_all(line.as_bytes())
+    }
+
+    pub fn cards_to_json(cards: &[Card]) -> Value {
+        json!(cards.iter().map(|card| card.key()).collect::<Vec<String>>())
+    }
+
+    pub fn wager_to_json(wager: &Wager) -> Value {
+        json!({
+            "cards": Self::cards_to_json(&wager.hand.cards),
+            "total": wager.hand.total,
+            "bet": wager.amount_bet,
+            "won": wager.amount_won,
+            "insurance": wager.insurance_bet,
+            "doubled": wager.doubled,
+            "surrendered": wager.surrendered,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::{Rank, Suit};
+    use crate::constants::constants::{MAXIMUM_BET, MINIMUM_BET};
+
+    #[test]
+    fn test_sampled_lines() {
+        let path = std::env::temp_dir().join(format!("striker_history_{}.jsonl", std::process::id()));
+        let path = path.to_string_lossy().to_string();
+        HandHistory::create(&path).unwrap();
+        let mut history = HandHistory::open(&path, 3).unwrap();
+        for round in 0..9 {
+            if history.sampled() {
+                history.write(&json!({ "round": round })).unwrap();
+            }
+        }
+        let text = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let rounds: Vec<u64> = text.lines().map(|line| serde_json::from_str::<Value>(line).unwrap()["round"].as_u64().unwrap()).collect();
+        assert_eq!(rounds, vec![2, 5, 8]);
+    }
+
+    #[test]
+    fn test_wager_to_json() {
+        let mut wager = Wager::new(MINIMUM_BET, MAXIMUM_BET);
+        wager.place_bet(4);
+        wager.hand.add_card(Card::new(Rank::Ace, Suit::Spades));
+        wager.hand.add_card(Card::new(Rank::Six, Suit::Hearts));
+        wager.double_bet();
+        wager.won();
+
+        let value = HandHistory::wager_to_json(&wager);
+        assert_eq!(value["cards"], json!(["As", "6h"]));
+        assert_eq!(value["total"], 17);
+        assert_eq!(value["bet"], 8);
+        assert_eq!(value["won"], 8);
+        assert_eq!(value["doubled"], true);
+    }
+}
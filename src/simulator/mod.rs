@@ -1,3 +1,6 @@
 // simulator module
+pub mod checkpoint;
+pub mod history;
+pub mod scheduler;
 pub mod simulator;
 pub mod table;
@@ -0,0 +1,165 @@
+use crate::arguments::parameters::Parameters;
+use crate::constants::constants::TARGET_STDERR_MINIMUM_ROUNDS;
+use crate::report::report::TrueCountBucket;
+use crate::strategy::rules::Rules;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+// The hands still to be played, handed out to the tables in batches so the threads finish together
+pub struct Scheduler {
+    remaining: AtomicUsize,
+    batch: usize,
+    round: usize,
+    target_stderr: Option<f64>,
+    interim: Mutex<TrueCountBucket>,
+    stopped: AtomicBool,
+}
+
+impl Scheduler {
+    pub fn new(number_of_hands: usize, batch: usize) -> Self {
+        Self {
+            remaining: AtomicUsize::new(number_of_hands),
+            batch: batch.max(1),
+            round: 1,
+            target_stderr: None,
+            interim: Mutex::new(TrueCountBucket::default()),
+            stopped: AtomicBool::new(false),
+        }
+    }
+
+    // With a target the number of hands is only the cap
+    pub fn with_target(mut self, target_stderr: Option<f64>) -> Self {
+        self.target_stderr = target_stderr;
+        self
+    }
+
+    // Hands are handed out in whole rounds, a blackjack switch round plays two
+    pub fn with_round(mut self, hands: usize) -> Self {
+        self.round = hands.max(1);
+        self.batch = self.batch.next_multiple_of(self.round);
+        self
+    }
+
+    pub fn for_parameters(parameters: &Parameters, rules: &Rules) -> Self {
+        Self::new(parameters.number_of_hands, parameters.batch_of_hands)
+            .with_target(parameters.target_stderr)
+            .with_round(if rules.blackjack_switch { 2 } else { 1 })
+    }
+
+    // Up to one batch of the remaining hands, none once they are all taken or the target is reached
+    // Only the last claim can be rounded up past the remaining hands, so the whole run goes at most one round short of a hand over
+    pub fn claim(&self) -> usize {
+        if self.stopped.load(Ordering::Relaxed) {
+            return 0;
+        }
+        self.remaining
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |remaining| {
+                if remaining == 0 { None } else { Some(remaining.saturating_sub(self.batch)) }
+            })
+            .map(|remaining| remaining.min(self.batch).next_multiple_of(self.round))
+            .unwrap_or(0)
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.remaining.load(Ordering::Relaxed)
+    }
+
+    // Merge a table's rounds since its last batch, and stop handing out batches once the interim results are precise enough
+    pub fn record(&self, rounds: &TrueCountBucket) {
+        let Some(target) = self.target_stderr else {
+            return;
+        };
+        let mut interim = self.interim.lock().unwrap();
+        interim.merge(rounds);
+        if interim.hands >= TARGET_STDERR_MINIMUM_ROUNDS && interim.standard_error() <= target {
+            self.stopped.store(true, Ordering::Relaxed);
+        }
+    }
+
+    pub fn target_reached(&self) -> bool {
+        self.stopped.load(Ordering::Relaxed)
+    }
+
+    pub fn standard_error(&self) -> f64 {
+        self.interim.lock().unwrap().standard_error()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::constants::BILLION;
+    use std::sync::Arc;
+    use std::thread;
+
+    fn make_rounds(hands: usize) -> TrueCountBucket {
+        TrueCountBucket {
+            hands,
+            total_bet: hands * 2,
+            total_won: 0,
+            total_squares: hands * 4,
+        }
+    }
+
+    #[test]
+    fn test_claim() {
+        let scheduler = Scheduler::new(25, 10);
+        assert_eq!(scheduler.claim(), 10);
+        assert_eq!(scheduler.claim(), 10);
+        assert_eq!(scheduler.remaining(), 5);
+        assert_eq!(scheduler.claim(), 5);
+        assert_eq!(scheduler.claim(), 0);
+        assert_eq!(Scheduler::new(3, 0).claim(), 1);
+    }
+
+    #[test]
+    fn test_claim_rounds() {
+        let scheduler = Scheduler::new(25, 9).with_round(2);
+        assert_eq!(scheduler.claim(), 10);
+        assert_eq!(scheduler.claim(), 10);
+        assert_eq!(scheduler.claim(), 6);
+        assert_eq!(scheduler.claim(), 0);
+        assert_eq!(Scheduler::new(24, 5).with_round(2).claim(), 6);
+    }
+
+    #[test]
+    fn test_claim_threads() {
+        let scheduler = Arc::new(Scheduler::new(1_000_003, 100));
+        let handles: Vec<_> = (0..8)
+            .map(|_| {
+                let scheduler = Arc::clone(&scheduler);
+                thread::spawn(move || {
+                    let mut claimed = 0;
+                    loop {
+                        match scheduler.claim() {
+                            0 => return claimed,
+                            hands => claimed += hands,
+                        }
+                    }
+                })
+            })
+            .collect();
+        let total: usize = handles.into_iter().map(|handle| handle.join().unwrap()).sum();
+        assert_eq!(total, 1_000_003);
+    }
+
+    #[test]
+    fn test_record_target() {
+        // Every round bets 2 and wins or loses 2, a standard error of 100 / sqrt(rounds) percent
+        let scheduler = Scheduler::new(1_000_000, 1000).with_target(Some(0.5));
+        scheduler.record(&make_rounds(TARGET_STDERR_MINIMUM_ROUNDS));
+        assert!((scheduler.standard_error() - 1.0).abs() < 1e-9);
+        assert!(!scheduler.target_reached());
+        assert_eq!(scheduler.claim(), 1000);
+
+        scheduler.record(&make_rounds(3 * TARGET_STDERR_MINIMUM_ROUNDS));
+        assert!((scheduler.standard_error() - 0.5).abs() < 1e-9);
+        assert!(scheduler.target_reached());
+        assert_eq!(scheduler.claim(), 0);
+
+        let scheduler = Scheduler::new(1_000_000, 1000);
+        scheduler.record(&make_rounds(BILLION));
+        assert!(!scheduler.target_reached());
+        assert!(scheduler.standard_error().is_infinite());
+    }
+}
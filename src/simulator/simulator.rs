@@ -1,8 +1,14 @@
 use crate::arguments::parameters::Parameters;
+use crate::errors::errors::StrikerError;
 use crate::report::report::Report;
+use crate::simulator::checkpoint::Checkpoint;
+use crate::simulator::scheduler::Scheduler;
 use crate::simulator::table::Table;
 use crate::strategy::rules::Rules;
 use crate::strategy::strategy::Strategy;
+use crate::utilities::topology::Topology;
+use std::sync::Arc;
+use std::thread;
 
 pub struct Simulator {
     parameters: Parameters,
@@ -11,34 +17,88 @@ pub struct Simulator {
 }
 
 impl Simulator {
-    pub fn new(parameters: &Parameters, rules: &Rules, strategy: &Strategy) -> Self {
-        let table = Table::new(parameters.clone(), rules.clone(), strategy.clone());
-        Self {
+    pub fn new(parameters: &Parameters, rules: &Rules, strategy: &Strategy) -> Result<Self, StrikerError> {
+        let table = Table::new(parameters.clone(), rules.clone(), strategy.clone())?;
+        Ok(Self {
             parameters: parameters.clone(),
             table,
             report: Report::new(),
-        }
+        })
     }
 
-    pub fn run_once(mut self) -> Self {
+    pub fn run_once(mut self) -> Result<Self, StrikerError> {
         let mimic = self.parameters.strategy == "mimic";
-        self.table.session(mimic);
+        self.table.session(mimic)?;
 
         let table_report = self.table.get_report();
         self.report.merge(&table_report);
 
-        self
+        Ok(self)
+    }
+
+    pub fn set_scheduler(&mut self, scheduler: Arc<Scheduler>) {
+        self.table.set_scheduler(scheduler);
+    }
+
+    pub fn set_checkpoint(&mut self, checkpoint: Arc<Checkpoint>, index: usize) {
+        self.table.set_checkpoint(checkpoint, index);
     }
 
     pub fn get_report(&self) -> &Report {
         &self.report
     }
+
+    // Run one simulator per thread, all pulling batches of hands from the same scheduler, and merge their results into the finished report
+    // The hand count is exact, save one hand over when blackjack switch rounds of two hands meet an odd count, and only a cap with a target standard error
+    // The first thread to fail or panic fails the run
+    pub fn simulate(parameters: &Parameters, rules: &Rules, strategy: &Strategy) -> Result<Report, StrikerError> {
+        Self::simulate_from(parameters, rules, strategy, Checkpoint::new(parameters))
+    }
+
+    // The same, carrying on from the results in a checkpoint, which the tables keep writing to as they go
+    pub fn simulate_from(parameters: &Parameters, rules: &Rules, strategy: &Strategy, checkpoint: Checkpoint) -> Result<Report, StrikerError> {
+        let mut handles = Vec::new();
+        let mut report = Report::new();
+        let scheduler = Arc::new(Scheduler::for_parameters(parameters, rules));
+        let checkpoint = Arc::new(checkpoint);
+
+        report.init(parameters);
+        report.start -= checkpoint.earlier_duration();
+        report.merge(checkpoint.earlier());
+        scheduler.record(&checkpoint.earlier().totals());
+        for index in 0..parameters.number_of_threads {
+            let parameters = parameters.clone();
+            let rules = rules.clone();
+            let strategy = strategy.clone();
+            let scheduler = Arc::clone(&scheduler);
+            let checkpoint = Arc::clone(&checkpoint);
+
+            handles.push(thread::spawn(move || {
+                if parameters.pin_threads {
+                    Topology::pin_thread(index);
+                }
+                let mut simulator = Simulator::new(&parameters, &rules, &strategy)?;
+                simulator.set_scheduler(scheduler);
+                simulator.set_checkpoint(checkpoint, index);
+                simulator.run_once()
+            }));
+        }
+
+        for handle in handles {
+            let simulator = handle.join().map_err(StrikerError::worker)??;
+            report.merge(simulator.get_report());
+        }
+        report.finish();
+        Ok(report)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::arguments::arguments::Arguments;
+    use crate::constants::constants::{MILLION, TARGET_STDERR_MINIMUM_ROUNDS};
+    use crate::resources::rules_table::RULES_JSON;
 
     #[test]
     fn test_simulator_initialization() {
@@ -46,8 +106,85 @@ mod tests {
         let parameters = Parameters::new(&arguments);
         let rules = Rules::default();
         let strategy = Strategy::new();
-        let _table = Table::new(parameters.clone(), rules.clone(), strategy.clone());
-        let simulator = Simulator::new(&parameters.clone(), &rules.clone(), &strategy.clone());
+        let _table = Table::new(parameters.clone(), rules.clone(), strategy.clone()).unwrap();
+        let simulator = Simulator::new(&parameters.clone(), &rules.clone(), &strategy.clone()).unwrap();
         simulator.get_report();
     }
+
+    #[test]
+    fn test_simulate() {
+        let arguments = Arguments {
+            number_of_hands: 20_000,
+            number_of_threads: 2,
+            pin_threads: true,
+            ..Default::default()
+        };
+        let parameters = Parameters::new(&arguments);
+        let rules = Rules::from_json(RULES_JSON.clone()).unwrap();
+        let strategy = Strategy::new();
+
+        let report = Simulator::simulate(&parameters, &rules, &strategy).unwrap();
+        assert_eq!(report.total_threads, 2);
+        assert_eq!(report.total_hands, arguments.number_of_hands);
+        assert!(report.pinned_threads);
+        assert_eq!(report.topology, Some(parameters.topology.clone()));
+        assert!(report.total_bet > 0);
+    }
+
+    #[test]
+    fn test_simulate_resume() {
+        let path = std::env::temp_dir().join(format!("striker_resume_{}.json", std::process::id()));
+        let path = path.to_string_lossy().to_string();
+        let arguments = Arguments {
+            number_of_hands: 20_000,
+            number_of_threads: 2,
+            checkpoint: Some(path.clone()),
+            ..Default::default()
+        };
+        let rules = Rules::from_json(RULES_JSON.clone()).unwrap();
+        let strategy = Strategy::new();
+
+        // The first run stops after a share of the hands, as if it had crashed
+        let mut parameters = Parameters::new(&arguments);
+        parameters.number_of_hands = 5000;
+        parameters.checkpoint = None;
+        let partial = Simulator::simulate(&parameters, &rules, &strategy).unwrap();
+        parameters.number_of_hands = arguments.number_of_hands;
+        parameters.checkpoint = Some(path.clone());
+        Checkpoint::new(&parameters).publish(0, partial.clone());
+
+        let mut resumed = Parameters::new(&arguments);
+        resumed.name = "resumed".to_string();
+        let checkpoint = Checkpoint::resume(&mut resumed).unwrap();
+        assert_eq!(resumed.number_of_hands, arguments.number_of_hands - 5000);
+        let report = Simulator::simulate_from(&resumed, &rules, &strategy, checkpoint).unwrap();
+        assert_eq!(report.total_hands, arguments.number_of_hands);
+        assert_eq!(report.name, parameters.name);
+        assert_eq!(report.epoch, parameters.epoch);
+        assert!(report.total_bet > partial.total_bet);
+
+        // The checkpoint now holds the whole run
+        let mut finished = Parameters::new(&arguments);
+        Checkpoint::resume(&mut finished).unwrap();
+        assert_eq!(finished.number_of_hands, 0);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_simulate_target_stderr() {
+        let arguments = Arguments {
+            number_of_hands: 10 * MILLION,
+            number_of_threads: 2,
+            target_stderr: Some(1.0),
+            ..Default::default()
+        };
+        let parameters = Parameters::new(&arguments);
+        let rules = Rules::from_json(RULES_JSON.clone()).unwrap();
+
+        let report = Simulator::simulate(&parameters, &rules, &Strategy::new()).unwrap();
+        assert!(report.total_hands >= TARGET_STDERR_MINIMUM_ROUNDS);
+        assert!(report.total_hands < arguments.number_of_hands);
+        assert!(report.standard_error <= 1.0);
+        assert_eq!(report.target_stderr, Some(1.0));
+    }
 }
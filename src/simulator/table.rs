@@ -1,69 +1,143 @@
 use crate::{
-    arguments::parameters::Parameters, cards::card::Card, cards::dealer::Dealer, cards::shoe::Shoe, constants::constants::STATUS_ROUNDS, player::player::Player,
-    report::report::Report, strategy::rules::Rules, strategy::strategy::Strategy,
+    arguments::parameters::Parameters,
+    cards::card::Card,
+    cards::dealer::Dealer,
+    cards::replay::Replay,
+    cards::shoe::Shoe,
+    cards::side_bets::SideBets,
+    constants::constants::{
+        ADVANTAGE_PLAY_ACE_SEQUENCING, ADVANTAGE_PLAY_FIRST_BASE, ADVANTAGE_PLAY_HOLE_CARD, ADVANTAGE_PLAY_WARP, SIDE_BET_BUSTER, SIDE_BET_LUCKY_LADIES, SIDE_BET_PERFECT_PAIRS,
+        SIDE_BET_TWENTY_ONE_PLUS_THREE, STATUS_ROUNDS,
+    },
+    errors::errors::StrikerError,
+    player::player::Player,
+    report::report::{Report, TrueCountBucket},
+    simulator::checkpoint::Checkpoint,
+    simulator::history::HandHistory,
+    simulator::scheduler::Scheduler,
+    strategy::rules::Rules,
+    strategy::strategy::Strategy,
 };
 use num_format::{Locale, ToFormattedString};
 use std::io::{self, Write};
+use std::sync::Arc;
 
 pub struct Table {
     parameters: Parameters,
     shoe: Shoe,
     dealer: Dealer,
     player: Player,
+    rules: Rules,
     report: Report,
     up: Option<Card>,
     down: Option<Card>,
+    player_cards: Vec<Card>,
+    history: Option<HandHistory>,
+    scheduler: Arc<Scheduler>,
+    checkpoint: Option<(Arc<Checkpoint>, usize)>,
+    checkpoint_generation: usize,
 }
 
 impl Table {
-    pub fn new(parameters: Parameters, rules: Rules, strategy: Strategy) -> Self {
-        Self {
-            player: Player::new(&rules, &strategy),
-            shoe: Shoe::new(parameters.number_of_decks, rules.penetration),
+    pub fn new(parameters: Parameters, rules: Rules, strategy: Strategy) -> Result<Self, StrikerError> {
+        let mut player = Player::new(&rules, &strategy);
+        player.advantage_play = parameters.advantage_play.clone();
+        let mut shoe = Self::new_shoe(&parameters, &rules)?;
+        if player.advantage_play.as_deref() == Some(ADVANTAGE_PLAY_ACE_SEQUENCING) {
+            shoe.enable_tracking();
+        }
+        let history = match &parameters.hand_history {
+            Some(path) => Some(HandHistory::open(path, parameters.hand_history_sample).map_err(|e| StrikerError::file("open", path, e))?),
+            None => None,
+        };
+        player.keep_history = history.is_some();
+        player.keep_decisions = parameters.decisions_csv.is_some();
+        // A table on its own plays every hand, the simulator shares one scheduler between its tables
+        let scheduler = Arc::new(Scheduler::for_parameters(&parameters, &rules));
+        Ok(Self {
+            player,
+            shoe,
             dealer: Dealer::new(rules.hit_soft_17),
+            rules,
             report: Report::new(),
             up: None,
             down: None,
+            player_cards: Vec::new(),
+            history,
+            scheduler,
+            checkpoint: None,
+            checkpoint_generation: 0,
             parameters,
+        })
+    }
+
+    pub fn set_scheduler(&mut self, scheduler: Arc<Scheduler>) {
+        self.scheduler = scheduler;
+    }
+
+    // The table hands its results to the checkpoint in the slot of its thread
+    pub fn set_checkpoint(&mut self, checkpoint: Arc<Checkpoint>, index: usize) {
+        self.checkpoint = Some((checkpoint, index));
+    }
+
+    fn new_shoe(parameters: &Parameters, rules: &Rules) -> Result<Shoe, StrikerError> {
+        let mut shoe = if rules.spanish_21 {
+            Shoe::new_spanish_21(parameters.number_of_decks, rules.penetration)
+        } else {
+            Shoe::new(parameters.number_of_decks, rules.penetration)
+        };
+        shoe.set_shuffle_model(rules.shuffle_model.clone(), rules.burn_cards);
+        if let Some(path) = &parameters.replay {
+            shoe.set_replay(Replay::from_file(path, parameters.replay_loop)?);
         }
+        Ok(shoe)
     }
 
-    pub fn session(&mut self, mimic: bool) {
-        while self.report.total_hands < self.parameters.share_of_hands {
+    // Play batches of hands from the scheduler until none are left, a shoe carries over from one batch to the next
+    pub fn session(&mut self, mimic: bool) -> Result<(), StrikerError> {
+        let mut batch = 0;
+        let mut recorded = TrueCountBucket::default();
+        'session: loop {
+            if batch == 0 {
+                batch = self.claim_batch(&mut recorded);
+                if batch == 0 {
+                    break;
+                }
+            }
             if self.parameters.verbose {
                 self.print_status(self.report.total_rounds, self.report.total_hands);
             }
 
             self.shoe.shuffle();
+            // A recording played to the end finishes the run early
+            if self.shoe.replay_finished() {
+                break;
+            }
             self.player.shuffle();
             self.report.total_rounds += 1;
 
             while !self.shoe.should_shuffle() {
-                self.report.total_hands += 1;
-                self.dealer.hand.reset();
-                self.player.place_bet(mimic);
-                self.deal_cards();
-
-                if !mimic && self.up.as_ref().is_some_and(|card| card.is_ace()) {
-                    self.player.insurance();
+                if batch == 0 {
+                    batch = self.claim_batch(&mut recorded);
+                    if batch == 0 {
+                        break 'session;
+                    }
                 }
-
-                if !self.dealer.hand.is_blackjack() {
-                    self.player.play(self.up.as_ref().unwrap(), &mut self.shoe, mimic);
-
-                    if !self.player.busted_or_blackjack() {
-                        while !self.dealer.should_stand() {
-                            let card = self.shoe.draw_card();
-                            self.dealer.hand.draw_card(card.clone());
-                            self.show_card(&card);
+                let hands = if self.rules.blackjack_switch { 2 } else { 1 };
+                batch = batch.saturating_sub(hands);
+                // A recording can run out mid round, the unfinished round is then thrown away and the run ends
+                let saved = self.shoe.is_replay().then(|| (self.report.clone(), self.player.report.clone()));
+                match self.play_round(mimic) {
+                    Ok(()) => self.report.total_hands += hands,
+                    Err(StrikerError::OutOfCards) if self.shoe.replay_finished() => {
+                        if let Some((report, player)) = saved {
+                            self.report = report;
+                            self.player.report = player;
                         }
+                        break 'session;
                     }
+                    Err(e) => return Err(e),
                 }
-
-                let down = self.down.clone(); // Immutable borrow ends here
-                self.show_card(&down); // Safe to mutably borrow self
-                //self.show_card(&self.down);
-                self.player.payoff(self.dealer.hand.is_blackjack(), self.dealer.hand.is_busted(), self.dealer.hand.total);
             }
         }
 
@@ -71,21 +145,198 @@ impl Table {
             print!("\r");
         }
 
-        let player_report = self.player.get_report();
-        self.report.out_of_cards = self.shoe.out_of_cards;
-        self.report.total_shuffles = self.shoe.number_of_shuffles;
-        self.report.merge(&player_report);
+        self.report = self.snapshot();
+        if let Some((checkpoint, index)) = &self.checkpoint {
+            checkpoint.publish(*index, self.report.clone());
+        }
+        Ok(())
     }
 
-    pub fn deal_cards(&mut self) {
-        self.player.draw_card(self.shoe.draw_card());
-        self.down = self.shoe.draw_card();
-        self.dealer.hand.draw_card(self.down.clone());
-        self.player.draw_card(self.shoe.draw_card());
+    // Deal one round, play it out and settle it
+    fn play_round(&mut self, mimic: bool) -> Result<(), StrikerError> {
+        let position = self.shoe.position();
+        self.dealer.hand.reset();
+        self.player.ace_predicted = self.shoe.ace_predicted();
+        self.player.place_bet(mimic);
+        self.deal_cards()?;
+        if self.player.ace_predicted {
+            self.report.ace_predictions += 1;
+            if self.player.wager.hand.cards.first().is_some_and(|card| card.is_ace()) {
+                self.report.ace_hits += 1;
+            }
+        }
+        self.player.hole = self.revealed_hole_card();
+        if self.player.advantage_play.as_deref() == Some(ADVANTAGE_PLAY_FIRST_BASE) {
+            self.player.next_card = self.shoe.peek_card().cloned();
+        }
+
+        if !mimic && self.up.as_ref().is_some_and(|card| card.is_ace()) {
+            self.player.insurance();
+        }
+
+        if !self.dealer.hand.is_blackjack() {
+            self.player.play(self.up.as_ref().unwrap(), &mut self.shoe, mimic)?;
+
+            if self.rules.no_hole_card {
+                self.draw_hole_card()?;
+            }
+            // The dealer plays out the hand whenever a buster bet is riding on it
+            if !self.dealer.hand.is_blackjack() && (!self.player.busted_or_blackjack() || self.rules.side_bets.has(SIDE_BET_BUSTER)) {
+                while !self.dealer.should_stand() {
+                    let card = self.shoe.draw_card();
+                    self.dealer.hand.draw_card(card.clone())?;
+                    self.show_card(&card);
+                }
+            }
+        }
+        if let Some((total, blackjack)) = self.dealer_outcome() {
+            let up = self.up.as_ref().unwrap().rank.value();
+            self.report.add_dealer_outcome(up, total, blackjack);
+        }
+
+        let down = self.down.clone(); // Immutable borrow ends here
+        self.show_card(&down); // Safe to mutably borrow self
+        if !self.rules.side_bets.is_empty() {
+            self.settle_dealer_side_bets();
+        }
+        //self.show_card(&self.down);
+        self.player.payoff(self.dealer.hand.is_blackjack(), self.dealer.hand.is_busted(), self.dealer.hand.total);
+        if self.history.as_mut().is_some_and(|history| history.sampled()) {
+            self.write_history(position);
+        }
+        Ok(())
+    }
+
+    // The table's results so far with the player's hands merged in
+    fn snapshot(&self) -> Report {
+        let mut report = self.report.clone();
+        report.out_of_cards = self.shoe.out_of_cards;
+        report.total_shuffles = self.shoe.number_of_shuffles;
+        report.merge(self.player.get_report());
+        report
+    }
+
+    // Hand the rounds played since the last batch to the scheduler, and the results to a checkpoint when one is due, before asking for the next batch
+    fn claim_batch(&mut self, recorded: &mut TrueCountBucket) -> usize {
+        let totals = self.player.get_report().totals();
+        self.scheduler.record(&totals.since(recorded));
+        *recorded = totals;
+        if let Some((checkpoint, index)) = &self.checkpoint
+            && let Some(generation) = checkpoint.due(self.checkpoint_generation)
+        {
+            self.checkpoint_generation = generation;
+            checkpoint.publish(*index, self.snapshot());
+        }
+        self.scheduler.claim()
+    }
+
+    fn write_history(&mut self, position: usize) {
+        let record = serde_json::json!({
+            "shoe": self.report.total_rounds,
+            "position": position,
+            "true_count": self.player.true_count,
+            "bet": self.player.round_bet,
+            "player": self.player.settled.iter().map(HandHistory::wager_to_json).collect::<Vec<_>>(),
+            "actions": self.player.actions,
+            "dealer": HandHistory::cards_to_json(&self.dealer.hand.cards),
+            "dealer_total": self.dealer.hand.total,
+            "won": self.player.round_won,
+        });
+        if let Some(history) = &mut self.history
+            && let Err(e) = history.write(&record)
+        {
+            println!("    Error: Cannot write hand history: {}", e);
+            self.history = None;
+        }
+    }
+
+    pub fn deal_cards(&mut self) -> Result<(), StrikerError> {
+        self.player.draw_card(self.shoe.draw_card())?;
+        if self.rules.blackjack_switch {
+            self.player.draw_switch_card(self.shoe.draw_card())?;
+        }
+        self.down = None;
+        if !self.rules.no_hole_card {
+            self.down = self.shoe.draw_card();
+            self.dealer.hand.draw_card(self.down.clone())?;
+        }
+        self.player.draw_card(self.shoe.draw_card())?;
+        if self.rules.blackjack_switch {
+            self.player.draw_switch_card(self.shoe.draw_card())?;
+        }
         self.up = self.shoe.draw_card();
-        self.dealer.hand.draw_card(self.up.clone());
+        self.dealer.hand.draw_card(self.up.clone())?;
         let up = self.up.clone(); // Immutable borrow ends here
         self.show_card(&up); // Safe to mutably borrow self
+        if !self.rules.side_bets.is_empty() {
+            self.settle_dealt_side_bets();
+        }
+        Ok(())
+    }
+
+    // Side bets decided by the player's first two cards and the up card
+    fn settle_dealt_side_bets(&mut self) {
+        self.player_cards.clear();
+        let (Some(up), [first, second, ..]) = (&self.up, self.player.wager.hand.cards.as_slice()) else {
+            return;
+        };
+        self.player_cards = vec![first.clone(), second.clone()];
+
+        let true_count = self.player.true_count;
+        let side_bets = &self.rules.side_bets;
+        if let Some(won) = side_bets.settle(SIDE_BET_PERFECT_PAIRS, SideBets::perfect_pairs(first, second)) {
+            self.report.add_side_bet(SIDE_BET_PERFECT_PAIRS, true_count, won);
+        }
+        if let Some(won) = side_bets.settle(SIDE_BET_TWENTY_ONE_PLUS_THREE, SideBets::twenty_one_plus_three(first, second, up)) {
+            self.report.add_side_bet(SIDE_BET_TWENTY_ONE_PLUS_THREE, true_count, won);
+        }
+    }
+
+    // Side bets that wait on the dealer's hand
+    fn settle_dealer_side_bets(&mut self) {
+        let [first, second] = self.player_cards.as_slice() else {
+            return;
+        };
+
+        let true_count = self.player.true_count;
+        let side_bets = &self.rules.side_bets;
+        if let Some(won) = side_bets.settle(SIDE_BET_LUCKY_LADIES, SideBets::lucky_ladies(first, second, self.dealer.hand.is_blackjack())) {
+            self.report.add_side_bet(SIDE_BET_LUCKY_LADIES, true_count, won);
+        }
+        if let Some(won) = side_bets.settle(SIDE_BET_BUSTER, SideBets::buster(&self.dealer.hand).as_deref()) {
+            self.report.add_side_bet(SIDE_BET_BUSTER, true_count, won);
+        }
+    }
+
+    // Hole card seen by the player, warping only tells tens and aces apart
+    fn revealed_hole_card(&self) -> Option<Card> {
+        let hole = self.down.as_ref()?;
+        match self.player.advantage_play.as_deref() {
+            Some(ADVANTAGE_PLAY_HOLE_CARD) => Some(hole.clone()),
+            Some(ADVANTAGE_PLAY_WARP) if hole.rank.value() >= 10 => Some(hole.clone()),
+            _ => None,
+        }
+    }
+
+    // Every round counts towards the dealer outcomes, picking only the hands the dealer played out would skew them
+    // A hand left unfinished is played out on the side with the cards it would have drawn, peeked so neither the shoe nor the count sees them
+    fn dealer_outcome(&self) -> Option<(usize, bool)> {
+        if self.dealer.should_stand() {
+            return Some((self.dealer.hand.total, self.dealer.hand.is_blackjack()));
+        }
+        let mut dealer = self.dealer.clone();
+        let mut offset = 0;
+        while !dealer.should_stand() {
+            dealer.hand.add_card(self.shoe.peek_ahead(offset)?.clone());
+            offset += 1;
+        }
+        Some((dealer.hand.total, false))
+    }
+
+    // Without a hole card the dealer's second card is drawn once the player has acted
+    fn draw_hole_card(&mut self) -> Result<(), StrikerError> {
+        self.down = self.shoe.draw_card();
+        self.dealer.hand.draw_card(self.down.clone())
     }
 
     pub fn show_card(&mut self, card: &Option<Card>) {
@@ -113,13 +364,19 @@ mod tests {
     use super::*;
     use crate::arguments::arguments::Arguments;
     use crate::cards::{Rank, Suit};
+    use crate::constants::constants::MINIMUM_BET;
 
     fn make_table() -> Table {
         let arguments = Arguments::new();
         let parameters = Parameters::new(&arguments);
         let rules = Rules::new();
         let strategy = Strategy::new();
-        Table::new(parameters.clone(), rules.clone(), strategy.clone())
+        Table::new(parameters.clone(), rules.clone(), strategy.clone()).unwrap()
+    }
+
+    fn make_table_with_rules(rules: Rules) -> Table {
+        let parameters = Parameters::new(&Arguments::default());
+        Table::new(parameters, rules, Strategy::new()).unwrap()
     }
 
     #[test]
@@ -135,9 +392,193 @@ mod tests {
     #[test]
     fn test_deal_cards_sets_up_and_down() {
         let mut table = make_table();
-        table.deal_cards();
+        table.deal_cards().unwrap();
+        assert!(table.up.is_some());
+        assert!(table.down.is_some());
+    }
+
+    #[test]
+    fn test_deal_cards_no_hole_card() {
+        let mut rules = Rules::new();
+        rules.no_hole_card = true;
+        let mut table = make_table_with_rules(rules);
+        table.deal_cards().unwrap();
         assert!(table.up.is_some());
+        assert!(table.down.is_none());
+        assert_eq!(table.dealer.hand.cards.len(), 1);
+
+        table.draw_hole_card().unwrap();
         assert!(table.down.is_some());
+        assert_eq!(table.dealer.hand.cards.len(), 2);
+    }
+
+    #[test]
+    fn test_deal_cards_blackjack_switch() {
+        let mut rules = Rules::new();
+        rules.blackjack_switch = true;
+        let mut table = make_table_with_rules(rules);
+        table.deal_cards().unwrap();
+        assert_eq!(table.player.wager.hand.cards.len(), 2);
+        assert_eq!(table.player.switch.hand.cards.len(), 2);
+        assert_eq!(table.dealer.hand.cards.len(), 2);
+    }
+
+    #[test]
+    fn test_side_bets_settled() {
+        let mut rules = Rules::new();
+        rules.side_bets = SideBets::fetch_table(&serde_json::json!({ "perfect-pairs": true, "21+3": true, "lucky-ladies": true, "buster": true }));
+        let mut table = make_table_with_rules(rules);
+        table.deal_cards().unwrap();
+        assert_eq!(table.player_cards.len(), 2);
+        assert_eq!(table.report.side_bets.len(), 2);
+
+        table.settle_dealer_side_bets();
+        assert_eq!(table.report.side_bets.len(), 4);
+        assert!(
+            table
+                .report
+                .side_bets
+                .values()
+                .all(|true_counts| true_counts.values().map(|bucket| bucket.hands).sum::<usize>() == 1)
+        );
+    }
+
+    #[test]
+    fn test_revealed_hole_card() {
+        let mut table = make_table_with_rules(Rules::new());
+        table.down = Some(Card::new(Rank::Five, Suit::Clubs));
+        assert!(table.revealed_hole_card().is_none());
+
+        table.player.advantage_play = Some(ADVANTAGE_PLAY_HOLE_CARD.to_string());
+        assert_eq!(table.revealed_hole_card(), table.down);
+
+        table.player.advantage_play = Some(ADVANTAGE_PLAY_WARP.to_string());
+        assert!(table.revealed_hole_card().is_none());
+        table.down = Some(Card::new(Rank::Queen, Suit::Clubs));
+        assert_eq!(table.revealed_hole_card(), table.down);
+    }
+
+    #[test]
+    fn test_session_exact_hands() {
+        let arguments = Arguments {
+            number_of_hands: 1001,
+            number_of_threads: 4,
+            ..Default::default()
+        };
+        let parameters = Parameters::new(&arguments);
+        let mut table = Table::new(parameters.clone(), Rules::builder().unwrap().build(), Strategy::new()).unwrap();
+        table.session(true).unwrap();
+        assert_eq!(table.report.total_hands, 1001);
+
+        // Two tables sharing a scheduler split the hands between them, odd batches are claimed in switch rounds of two so only the last hand goes over
+        let scheduler = Arc::new(Scheduler::new(1001, 51).with_round(2));
+        let rules = Rules::builder().unwrap().blackjack_switch(true).build();
+        let mut first = Table::new(parameters.clone(), rules.clone(), Strategy::new()).unwrap();
+        let mut second = Table::new(parameters, rules, Strategy::new()).unwrap();
+        first.set_scheduler(Arc::clone(&scheduler));
+        second.set_scheduler(scheduler);
+        first.session(true).unwrap();
+        second.session(true).unwrap();
+        assert_eq!(first.report.total_hands, 1002);
+        assert_eq!(second.report.total_hands, 0);
+    }
+
+    #[test]
+    fn test_replay_session() {
+        let path = std::env::temp_dir().join(format!("striker_replay_{}.txt", std::process::id()));
+        std::fs::write(&path, "# two recorded shoes\nX 9 X 7 X\nA X X X X X 9 X\n").unwrap();
+        let arguments = Arguments {
+            replay: Some(path.to_string_lossy().to_string()),
+            ..Default::default()
+        };
+        let parameters = Parameters::new(&arguments);
+        let mut rules = Rules::new();
+        rules.blackjack_bets = 2;
+        rules.blackjack_pays = 3;
+        let mut table = Table::new(parameters, rules, Strategy::new()).unwrap();
+        table.session(true).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        // Player 20 against a dealer bust, a player blackjack against a dealer 20 and 19 against 20, dealt to the last card
+        assert_eq!(table.report.total_rounds, 2);
+        assert_eq!(table.report.total_hands, 3);
+        assert_eq!(table.report.out_of_cards, 0);
+        assert_eq!(table.report.dealer_outcomes[7][5], 1);
+        assert_eq!(table.report.dealer_outcomes[10][3], 2);
+    }
+
+    #[test]
+    fn test_replay_runs_out_mid_round() {
+        let path = std::env::temp_dir().join(format!("striker_short_replay_{}.txt", std::process::id()));
+        std::fs::write(&path, "X 9 X 7 X 5 X\n").unwrap();
+        let arguments = Arguments {
+            replay: Some(path.to_string_lossy().to_string()),
+            ..Default::default()
+        };
+        let mut table = Table::new(Parameters::new(&arguments), Rules::new(), Strategy::new()).unwrap();
+        table.session(true).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        // Player 20 against a dealer bust, then a round the recording cannot finish, which is left out
+        let report = table.get_report();
+        assert_eq!(report.total_hands, 1);
+        assert_eq!(report.total_bet, MINIMUM_BET);
+        assert_eq!(report.total_won, MINIMUM_BET as isize);
+        assert_eq!(report.dealer_outcomes.iter().flatten().sum::<usize>(), 1);
+        assert_eq!(report.true_counts.values().map(|bucket| bucket.hands).sum::<usize>(), 1);
+    }
+
+    #[test]
+    fn test_dealer_outcome_after_player_bust() {
+        let path = std::env::temp_dir().join(format!("striker_bust_replay_{}.txt", std::process::id()));
+        std::fs::write(&path, "X 6 6 X X 9 X 9 X\n").unwrap();
+        let arguments = Arguments {
+            replay: Some(path.to_string_lossy().to_string()),
+            ..Default::default()
+        };
+        let mut table = Table::new(Parameters::new(&arguments), Rules::new(), Strategy::new()).unwrap();
+        table.session(true).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        // The player busts 16 and the dealer's 16 is never played, the 9 it would draw makes it a bust but is dealt to the next round, 18 against 20
+        assert_eq!(table.report.total_hands, 2);
+        assert_eq!(table.report.out_of_cards, 0);
+        assert_eq!(table.report.dealer_outcomes[10], [0, 0, 0, 1, 0, 1, 0]);
+    }
+
+    #[test]
+    fn test_hand_history_session() {
+        let directory = std::env::temp_dir();
+        let replay = directory.join(format!("striker_history_replay_{}.txt", std::process::id()));
+        let history = directory.join(format!("striker_history_{}.jsonl", std::process::id()));
+        std::fs::write(&replay, "X 9 X 7 X 5 X 6 X 8\n").unwrap();
+        let arguments = Arguments {
+            replay: Some(replay.to_string_lossy().to_string()),
+            hand_history: Some(history.to_string_lossy().to_string()),
+            ..Default::default()
+        };
+        let mut rules = Rules::new();
+        rules.blackjack_bets = 2;
+        rules.blackjack_pays = 3;
+        let mut table = Table::new(Parameters::new(&arguments), rules, Strategy::new()).unwrap();
+        table.session(true).unwrap();
+        let text = std::fs::read_to_string(&history).unwrap();
+        std::fs::remove_file(&replay).unwrap();
+        std::fs::remove_file(&history).unwrap();
+
+        let rounds: Vec<serde_json::Value> = text.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+        assert_eq!(rounds.len(), 2);
+        assert_eq!(rounds[0]["position"], 0);
+        assert_eq!(rounds[0]["player"][0]["cards"], serde_json::json!(["Xh", "Xc"]));
+        assert_eq!(rounds[0]["dealer"], serde_json::json!(["9d", "7s", "Xh"]));
+        assert_eq!(rounds[0]["won"], MINIMUM_BET);
+
+        // The mimic player hits 11 to 19 and loses to the dealer 20
+        assert_eq!(rounds[1]["position"], 5);
+        assert_eq!(rounds[1]["actions"], serde_json::json!(["hit"]));
+        assert_eq!(rounds[1]["player"][0]["total"], 19);
+        assert_eq!(rounds[1]["dealer_total"], 20);
+        assert_eq!(rounds[1]["won"], -(MINIMUM_BET as isize));
     }
 
     #[test]
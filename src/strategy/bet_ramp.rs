@@ -0,0 +1,153 @@
+use crate::constants::constants::{BET_RAMP_BANKROLL, BET_RAMP_KELLY_FRACTION, BET_RAMP_MINIMUM_HANDS, MAXIMUM_BET, MINIMUM_BET};
+use crate::report::report::Report;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+
+// Bet to place at each true count; counts beyond either end use the nearest entry
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(from = "Value", into = "Value")]
+pub struct BetRamp {
+    bets: BTreeMap<isize, usize>,
+}
+
+impl BetRamp {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Load a ramp in the form { "-1": 2, "0": 2, "1": 4, ... }
+    pub fn fetch_table(data: &Value) -> Self {
+        let mut ramp = Self::new();
+        if let Some(map) = data.as_object() {
+            for (key, value) in map.iter() {
+                if let (Ok(true_count), Some(bet)) = (key.parse::<isize>(), value.as_u64()) {
+                    ramp.bets.insert(true_count, bet as usize);
+                }
+            }
+        }
+        ramp
+    }
+
+    // Kelly proportional bets from the edge and the variance per unit bet at each true count, the minimum where there is no edge
+    pub fn from_edges(edges: &BTreeMap<isize, (f64, f64)>) -> Self {
+        let mut ramp = Self::new();
+        for (true_count, (edge, variance)) in edges {
+            let bet = if *edge > 0.0 && *variance > 0.0 {
+                (BET_RAMP_BANKROLL as f64 * BET_RAMP_KELLY_FRACTION * edge / variance).round() as usize
+            } else {
+                MINIMUM_BET
+            };
+            ramp.bets.insert(*true_count, bet.clamp(MINIMUM_BET, MAXIMUM_BET));
+        }
+        ramp
+    }
+
+    // Kelly proportional ramp from the per true count results of a simulation, counts with too few hands are left out
+    pub fn from_report(report: &Report) -> Self {
+        let mut edges = BTreeMap::new();
+        for (true_count, bucket) in &report.true_counts {
+            if bucket.hands < BET_RAMP_MINIMUM_HANDS || bucket.total_bet == 0 {
+                continue;
+            }
+            let average_bet = bucket.total_bet as f64 / bucket.hands as f64;
+            let edge = bucket.total_won as f64 / bucket.total_bet as f64;
+            let variance = (bucket.total_squares as f64 / bucket.hands as f64) / (average_bet * average_bet) - edge * edge;
+            edges.insert(*true_count, (edge, variance));
+        }
+        Self::from_edges(&edges)
+    }
+
+    pub fn insert(&mut self, true_count: isize, bet: usize) {
+        self.bets.insert(true_count, bet);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bets.is_empty()
+    }
+
+    pub fn get_bet(&self, true_count: isize) -> Option<usize> {
+        self.bets.range(..=true_count).next_back().or_else(|| self.bets.iter().next()).map(|(_, bet)| *bet)
+    }
+
+    pub fn to_json_object(&self) -> Value {
+        let mut map = Map::new();
+        for (true_count, bet) in &self.bets {
+            map.insert(true_count.to_string(), Value::from(*bet));
+        }
+        Value::Object(map)
+    }
+}
+
+impl From<Value> for BetRamp {
+    fn from(data: Value) -> Self {
+        Self::fetch_table(&data)
+    }
+}
+
+impl From<BetRamp> for Value {
+    fn from(ramp: BetRamp) -> Self {
+        ramp.to_json_object()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_fetch_table_and_get_bet() {
+        let ramp = BetRamp::fetch_table(&json!({ "-1": 2, "0": 2, "1": 4, "3": 10, "bad": 7 }));
+        assert!(!ramp.is_empty());
+        assert_eq!(ramp.get_bet(-5), Some(2));
+        assert_eq!(ramp.get_bet(0), Some(2));
+        assert_eq!(ramp.get_bet(2), Some(4));
+        assert_eq!(ramp.get_bet(9), Some(10));
+        assert_eq!(ramp.to_json_object(), json!({ "-1": 2, "0": 2, "1": 4, "3": 10 }));
+    }
+
+    #[test]
+    fn test_insert_and_serde() {
+        let mut ramp = BetRamp::new();
+        ramp.insert(0, 2);
+        ramp.insert(2, 8);
+        assert_eq!(ramp.get_bet(3), Some(8));
+        let value = serde_json::to_value(&ramp).unwrap();
+        assert_eq!(value, json!({ "0": 2, "2": 8 }));
+        assert_eq!(serde_json::from_value::<BetRamp>(value).unwrap(), ramp);
+    }
+
+    #[test]
+    fn test_empty_ramp() {
+        let ramp = BetRamp::fetch_table(&Value::Null);
+        assert!(ramp.is_empty());
+        assert_eq!(ramp.get_bet(0), None);
+    }
+
+    #[test]
+    fn test_from_edges() {
+        let edges = BTreeMap::from([(-1, (-0.05, 1.0)), (2, (0.02, 1.25)), (4, (0.05, 1.2))]);
+        let ramp = BetRamp::from_edges(&edges);
+        assert_eq!(ramp.get_bet(-1), Some(MINIMUM_BET));
+        assert_eq!(ramp.get_bet(2), Some(8));
+        assert_eq!(ramp.get_bet(4), Some(MAXIMUM_BET));
+    }
+
+    #[test]
+    fn test_from_report() {
+        let mut report = Report::new();
+        for _ in 0..BET_RAMP_MINIMUM_HANDS {
+            report.add_true_count_hand(-1, 2, -2);
+            report.add_true_count_hand(4, 2, 2);
+            report.add_true_count_hand(4, 2, -2);
+            report.add_true_count_hand(4, 2, 2);
+        }
+        report.add_true_count_hand(9, 2, 2);
+
+        let ramp = BetRamp::from_report(&report);
+        assert_eq!(ramp.get_bet(-1), Some(MINIMUM_BET));
+        assert_eq!(ramp.get_bet(4), Some(MAXIMUM_BET));
+        assert_eq!(ramp.to_json_object().as_object().unwrap().len(), 2);
+    }
+}
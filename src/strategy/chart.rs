@@ -1,3 +1,6 @@
+use crate::errors::errors::StrikerError;
+use serde_json::{Map, Value};
+
 pub const TABLE_SIZE: usize = 21;
 pub const NUM_COLUMNS: usize = 12;
 
@@ -45,10 +48,29 @@ impl Chart {
         self.rows[index].value[up] = value.to_uppercase();
     }
 
-    pub fn get_value_by_key(&self, key: &str, up: usize) -> &str {
-        self.get_row_index(key)
-            .map(|i| &self.rows[i].value[up])
-            .unwrap_or_else(|| panic!("Cannot find value in {} for {} vs {}", self.name, key, up))
+    pub fn get_value_by_key(&self, key: &str, up: usize) -> Result<&str, StrikerError> {
+        self.get_value(key, up).ok_or_else(|| StrikerError::MissingChartValue {
+            chart: self.name.clone(),
+            key: key.to_string(),
+            up,
+        })
+    }
+
+    pub fn get_value(&self, key: &str, up: usize) -> Option<&str> {
+        self.get_row_index(key).map(|i| self.rows[i].value[up].as_str())
+    }
+
+    pub fn keys(&self) -> Vec<String> {
+        self.rows.iter().take(self.next_row).map(|row| row.key.clone()).collect()
+    }
+
+    // Chart rows in the same layout the charts are loaded from, columns 2 through A
+    pub fn to_json_object(&self) -> Value {
+        let mut map = Map::new();
+        for row in self.rows.iter().take(self.next_row) {
+            map.insert(row.key.clone(), Value::from(row.value[2..NUM_COLUMNS].to_vec()));
+        }
+        Value::Object(map)
     }
 
     pub fn print(&self) {
@@ -100,9 +122,9 @@ mod tests {
         chart.insert("8", 3, "S");
         chart.insert("A,7", 4, "D");
 
-        assert_eq!(chart.get_value_by_key("8", 2), "H");
-        assert_eq!(chart.get_value_by_key("8", 3), "S");
-        assert_eq!(chart.get_value_by_key("A,7", 4), "D");
+        assert_eq!(chart.get_value_by_key("8", 2).unwrap(), "H");
+        assert_eq!(chart.get_value_by_key("8", 3).unwrap(), "S");
+        assert_eq!(chart.get_value_by_key("A,7", 4).unwrap(), "D");
     }
 
     #[test]
@@ -111,16 +133,33 @@ mod tests {
         chart.insert("8", 2, "H");
         chart.insert("a,7", 3, "D");
 
-        assert_eq!(chart.get_value_by_key("8", 2), "H");
-        assert_eq!(chart.get_value_by_key("A,7", 3), "D");
-        assert_eq!(chart.get_value_by_key("a,7", 3), "D");
+        assert_eq!(chart.get_value_by_key("8", 2).unwrap(), "H");
+        assert_eq!(chart.get_value_by_key("A,7", 3).unwrap(), "D");
+        assert_eq!(chart.get_value_by_key("a,7", 3).unwrap(), "D");
     }
 
     #[test]
-    #[should_panic(expected = "Cannot find value in Test Chart for 5 vs 4")]
-    fn test_chart_get_value_invalid_key_panics() {
+    fn test_chart_get_value_invalid_key_is_an_error() {
         let chart = Chart::new("Test Chart");
-        chart.get_value_by_key("5", 4);
+        assert_eq!(chart.get_value_by_key("5", 4).unwrap_err().to_string(), "Cannot find value in Test Chart for 5 vs 4");
+    }
+
+    #[test]
+    fn test_chart_keys_and_to_json() {
+        let mut chart = Chart::new("Json Chart");
+        for up in 2..NUM_COLUMNS {
+            chart.insert("16", up, "N");
+            chart.insert("A", up, "Y");
+        }
+        chart.insert("16", 10, "0");
+
+        assert_eq!(chart.keys(), vec!["16".to_string(), "A".to_string()]);
+        assert_eq!(chart.get_value("16", 10), Some("0"));
+        assert_eq!(chart.get_value("17", 10), None);
+        let json = chart.to_json_object();
+        assert_eq!(json["16"].as_array().unwrap().len(), 10);
+        assert_eq!(json["16"][8], "0");
+        assert_eq!(json["A"][9], "Y");
     }
 
     #[test]
@@ -130,9 +169,9 @@ mod tests {
         chart.insert("3", 2, "S");
         chart.insert("4", 2, "D");
 
-        assert_eq!(chart.get_value_by_key("2", 2), "H");
-        assert_eq!(chart.get_value_by_key("3", 2), "S");
-        assert_eq!(chart.get_value_by_key("4", 2), "D");
+        assert_eq!(chart.get_value_by_key("2", 2).unwrap(), "H");
+        assert_eq!(chart.get_value_by_key("3", 2).unwrap(), "S");
+        assert_eq!(chart.get_value_by_key("4", 2).unwrap(), "D");
         chart.print();
     }
 }
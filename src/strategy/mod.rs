@@ -1,5 +1,7 @@
+pub mod bet_ramp;
 pub mod chart;
 pub mod rules;
 pub mod strategy;
+pub mod switch;
 
 //pub use strategy::Strategy;
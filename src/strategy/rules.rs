@@ -1,10 +1,19 @@
+use crate::cards::shuffle::ShuffleModel;
+use crate::cards::side_bets::SideBets;
+use crate::constants::constants::{BURN_CARDS_DEFAULT, NO_HOLE_CARD_ALL};
+use crate::errors::errors::StrikerError;
+use crate::resources::rules_table::RULES_JSON;
 use crate::traits::traits::JsonFetcher;
 use crate::utilities::utilities::get_rules_url;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::fmt;
 
-#[derive(Default, Debug, Clone)]
+// Serialized in the same layout as the rules tables, the optional rules fall back to their defaults
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct Rules {
+    #[serde(default)]
     pub playbook: String,
     pub hit_soft_17: bool,
     pub surrender: bool,
@@ -15,39 +24,246 @@ pub struct Rules {
     pub blackjack_bets: usize,
     pub blackjack_pays: usize,
     pub penetration: f64,
+    #[serde(default)]
+    pub no_hole_card: bool,
+    #[serde(default = "default_no_hole_card_losses")]
+    pub no_hole_card_losses: String,
+    #[serde(default, rename = "spanish21")]
+    pub spanish_21: bool,
+    #[serde(default)]
+    pub double_down_rescue: bool,
+    #[serde(default)]
+    pub blackjack_switch: bool,
+    #[serde(default)]
+    pub free_bet: bool,
+    #[serde(default, rename = "dealer22Push")]
+    pub dealer_22_push: bool,
+    #[serde(default)]
+    pub side_bets: SideBets,
+    #[serde(default, rename = "shuffle")]
+    pub shuffle_model: ShuffleModel,
+    #[serde(default = "default_burn_cards")]
+    pub burn_cards: usize,
+    #[serde(skip)]
     json_response: Value,
 }
 
+fn default_no_hole_card_losses() -> String {
+    NO_HOLE_CARD_ALL.to_string()
+}
+
+fn default_burn_cards() -> usize {
+    BURN_CARDS_DEFAULT
+}
+
+// Typed rules without a table, starting from the single deck rules in the resources
+pub struct RulesBuilder {
+    rules: Rules,
+}
+
+impl RulesBuilder {
+    pub fn playbook(mut self, playbook: &str) -> Self {
+        self.rules.playbook = playbook.to_string();
+        self
+    }
+
+    pub fn hit_soft_17(mut self, hit_soft_17: bool) -> Self {
+        self.rules.hit_soft_17 = hit_soft_17;
+        self
+    }
+
+    pub fn surrender(mut self, surrender: bool) -> Self {
+        self.rules.surrender = surrender;
+        self
+    }
+
+    pub fn double_any_two_cards(mut self, double_any_two_cards: bool) -> Self {
+        self.rules.double_any_two_cards = double_any_two_cards;
+        self
+    }
+
+    pub fn double_after_split(mut self, double_after_split: bool) -> Self {
+        self.rules.double_after_split = double_after_split;
+        self
+    }
+
+    pub fn resplit_aces(mut self, resplit_aces: bool) -> Self {
+        self.rules.resplit_aces = resplit_aces;
+        self
+    }
+
+    pub fn hit_split_aces(mut self, hit_split_aces: bool) -> Self {
+        self.rules.hit_split_aces = hit_split_aces;
+        self
+    }
+
+    pub fn blackjack_bets(mut self, blackjack_bets: usize) -> Self {
+        self.rules.blackjack_bets = blackjack_bets;
+        self
+    }
+
+    pub fn blackjack_pays(mut self, blackjack_pays: usize) -> Self {
+        self.rules.blackjack_pays = blackjack_pays;
+        self
+    }
+
+    pub fn penetration(mut self, penetration: f64) -> Self {
+        self.rules.penetration = penetration;
+        self
+    }
+
+    pub fn no_hole_card(mut self, no_hole_card: bool) -> Self {
+        self.rules.no_hole_card = no_hole_card;
+        self
+    }
+
+    pub fn spanish_21(mut self, spanish_21: bool) -> Self {
+        self.rules.spanish_21 = spanish_21;
+        self
+    }
+
+    pub fn double_down_rescue(mut self, double_down_rescue: bool) -> Self {
+        self.rules.double_down_rescue = double_down_rescue;
+        self
+    }
+
+    pub fn blackjack_switch(mut self, blackjack_switch: bool) -> Self {
+        self.rules.blackjack_switch = blackjack_switch;
+        self
+    }
+
+    pub fn free_bet(mut self, free_bet: bool) -> Self {
+        self.rules.free_bet = free_bet;
+        self
+    }
+
+    pub fn dealer_22_push(mut self, dealer_22_push: bool) -> Self {
+        self.rules.dealer_22_push = dealer_22_push;
+        self
+    }
+
+    pub fn side_bets(mut self, side_bets: SideBets) -> Self {
+        self.rules.side_bets = side_bets;
+        self
+    }
+
+    pub fn shuffle_model(mut self, shuffle_model: ShuffleModel) -> Self {
+        self.rules.shuffle_model = shuffle_model;
+        self
+    }
+
+    pub fn burn_cards(mut self, burn_cards: usize) -> Self {
+        self.rules.burn_cards = burn_cards;
+        self
+    }
+
+    pub fn no_hole_card_losses(mut self, losses: &str) -> Self {
+        self.rules.no_hole_card_losses = losses.to_lowercase();
+        self
+    }
+
+    // Blackjack Switch pays a blackjack even money and pushes a dealer 22, whatever the starting table says
+    pub fn build(mut self) -> Rules {
+        if self.rules.blackjack_switch {
+            self.rules.blackjack_pays = self.rules.blackjack_bets;
+            self.rules.dealer_22_push = true;
+        }
+        self.rules
+    }
+}
+
 impl Rules {
     pub fn new() -> Self {
         Rules { ..Default::default() }
     }
 
     // This method fetches the JSON data and parses it separately
-    pub fn init(&mut self, fetcher: &dyn JsonFetcher, decks: &str) {
-        let url = format!("http://{}/{}", get_rules_url().expect("Missing rules URL"), decks);
-        match fetcher.fetch_json(&url) {
-            Ok(json_value) => self.json_response = json_value,
-            Err(e) => panic!("Error fetching JSON: {}", e),
-        }
-        self.fetch_table();
+    pub fn init(&mut self, fetcher: &dyn JsonFetcher, decks: &str) -> Result<(), StrikerError> {
+        let url = format!("http://{}/{}", get_rules_url().ok_or(StrikerError::MissingUrl("STRIKER_URL_RULES"))?, decks);
+        self.json_response = fetcher.fetch_json(&url).map_err(|e| StrikerError::Fetch {
+            url: url.clone(),
+            message: e.to_string(),
+        })?;
+        self.fetch_table()
+    }
+
+    // Start from the single deck table in the resources, a table missing a required rule is an error rather than a panic
+    pub fn builder() -> Result<RulesBuilder, StrikerError> {
+        Ok(RulesBuilder {
+            rules: Self::from_json(RULES_JSON.clone())?,
+        })
+    }
+
+    // Rules from a table already in hand, e.g. one of the resources, without going to the rules server
+    pub fn from_json(json_value: Value) -> Result<Self, StrikerError> {
+        let mut rules = Rules {
+            json_response: json_value,
+            ..Default::default()
+        };
+        rules.fetch_table()?;
+        Ok(rules)
     }
 
     // Extract values from JSON and set member variables
-    fn fetch_table(&mut self) {
+    fn fetch_table(&mut self) -> Result<(), StrikerError> {
         if let Some(playbook) = self.json_response.get("playbook").and_then(|v| v.as_str()) {
             self.playbook = playbook.to_string();
         }
 
-        self.hit_soft_17 = self.json_response.get("hitSoft17").and_then(|v| v.as_bool()).unwrap();
-        self.surrender = self.json_response.get("surrender").and_then(|v| v.as_bool()).unwrap();
-        self.double_any_two_cards = self.json_response.get("doubleAnyTwoCards").and_then(|v| v.as_bool()).unwrap();
-        self.double_after_split = self.json_response.get("doubleAfterSplit").and_then(|v| v.as_bool()).unwrap();
-        self.resplit_aces = self.json_response.get("resplitAces").and_then(|v| v.as_bool()).unwrap();
-        self.hit_split_aces = self.json_response.get("hitSplitAces").and_then(|v| v.as_bool()).unwrap();
-        self.blackjack_bets = self.json_response.get("blackjackBets").and_then(|v| v.as_i64()).unwrap() as usize;
-        self.blackjack_pays = self.json_response.get("blackjackPays").and_then(|v| v.as_i64()).unwrap() as usize;
-        self.penetration = self.json_response.get("penetration").and_then(|v| v.as_f64()).unwrap();
+        let json = &self.json_response;
+        let missing = |field: &'static str| StrikerError::MissingField {
+            table: self.playbook.clone(),
+            field,
+        };
+        let flag = |field: &'static str| json.get(field).and_then(|v| v.as_bool()).ok_or_else(|| missing(field));
+        let number = |field: &'static str| json.get(field).and_then(|v| v.as_u64()).map(|v| v as usize).ok_or_else(|| missing(field));
+        let hit_soft_17 = flag("hitSoft17")?;
+        let surrender = flag("surrender")?;
+        let double_any_two_cards = flag("doubleAnyTwoCards")?;
+        let double_after_split = flag("doubleAfterSplit")?;
+        let resplit_aces = flag("resplitAces")?;
+        let hit_split_aces = flag("hitSplitAces")?;
+        let blackjack_bets = number("blackjackBets")?;
+        let blackjack_pays = number("blackjackPays")?;
+        let penetration = json.get("penetration").and_then(|v| v.as_f64()).ok_or_else(|| missing("penetration"))?;
+        let blackjack_switch = json.get("blackjackSwitch").and_then(|v| v.as_bool()).unwrap_or(false);
+        let dealer_22_push = json.get("dealer22Push").and_then(|v| v.as_bool()).unwrap_or(blackjack_switch);
+        let conflicting = |message: &'static str| StrikerError::ConflictingRules {
+            table: self.playbook.clone(),
+            message,
+        };
+        if blackjack_switch && blackjack_pays != blackjack_bets {
+            return Err(conflicting("Blackjack Switch pays blackjack even money"));
+        }
+        if blackjack_switch && !dealer_22_push {
+            return Err(conflicting("Blackjack Switch pushes a dealer 22"));
+        }
+
+        self.hit_soft_17 = hit_soft_17;
+        self.surrender = surrender;
+        self.double_any_two_cards = double_any_two_cards;
+        self.double_after_split = double_after_split;
+        self.resplit_aces = resplit_aces;
+        self.hit_split_aces = hit_split_aces;
+        self.blackjack_bets = blackjack_bets;
+        self.blackjack_pays = blackjack_pays;
+        self.penetration = penetration;
+        self.no_hole_card = self.json_response.get("noHoleCard").and_then(|v| v.as_bool()).unwrap_or(false);
+        self.spanish_21 = self.json_response.get("spanish21").and_then(|v| v.as_bool()).unwrap_or(false);
+        self.double_down_rescue = self.json_response.get("doubleDownRescue").and_then(|v| v.as_bool()).unwrap_or(false);
+        self.blackjack_switch = blackjack_switch;
+        self.free_bet = self.json_response.get("freeBet").and_then(|v| v.as_bool()).unwrap_or(false);
+        self.dealer_22_push = dealer_22_push;
+        self.side_bets = SideBets::fetch_table(&self.json_response["sideBets"]);
+        self.shuffle_model = ShuffleModel::fetch_table(&self.json_response["shuffle"]);
+        self.burn_cards = self.json_response.get("burnCards").and_then(|v| v.as_u64()).map_or(BURN_CARDS_DEFAULT, |v| v as usize);
+        self.no_hole_card_losses = self
+            .json_response
+            .get("noHoleCardLosses")
+            .and_then(|v| v.as_str())
+            .unwrap_or(NO_HOLE_CARD_ALL)
+            .to_lowercase();
+        Ok(())
     }
 }
 
@@ -63,31 +279,108 @@ impl fmt::Display for Rules {
         writeln!(f, "{:>6}{:<24}: {}", "", "Hit split aces", self.hit_split_aces)?;
         writeln!(f, "{:>6}{:<24}: {}", "", "Blackjack bets", self.blackjack_bets)?;
         writeln!(f, "{:>6}{:<24}: {}", "", "Blackjack pays", self.blackjack_pays)?;
-        write!(f, "{:>6}{:<24}: {:.3} %", "", "Penetration", self.penetration)
+        writeln!(f, "{:>6}{:<24}: {:.3} %", "", "Penetration", self.penetration)?;
+        writeln!(f, "{:>6}{:<24}: {}", "", "Shuffle", self.shuffle_model)?;
+        writeln!(f, "{:>6}{:<24}: {}", "", "Burn cards", self.burn_cards)?;
+        writeln!(f, "{:>6}{:<24}: {}", "", "Spanish 21", self.spanish_21)?;
+        writeln!(f, "{:>6}{:<24}: {}", "", "Double down rescue", self.double_down_rescue)?;
+        writeln!(f, "{:>6}{:<24}: {}", "", "Blackjack switch", self.blackjack_switch)?;
+        writeln!(f, "{:>6}{:<24}: {}", "", "Free bet", self.free_bet)?;
+        writeln!(f, "{:>6}{:<24}: {}", "", "Dealer 22 push", self.dealer_22_push)?;
+        if !self.side_bets.is_empty() {
+            writeln!(f, "{:>6}{:<24}: {}", "", "Side bets", self.side_bets.to_json_object())?;
+        }
+        write!(f, "{:>6}{:<24}: {}", "", "No hole card", self.no_hole_card)?;
+        if self.no_hole_card {
+            write!(f, "\n{:>6}{:<24}: {}", "", "No hole card losses", self.no_hole_card_losses)?;
+        }
+        Ok(())
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::resources::spanish_21_rules::SPANISH_21_RULES_JSON;
     use crate::utilities::utilities::Utility;
     use serde_json::json;
     use std::env;
 
     #[test]
-    #[should_panic]
     fn test_rules_table_fail() {
         let utility = Utility::default();
         let mut rules = Rules::new();
         unsafe {
             env::set_var("STRIKER_URL_RULES", "https://example.com/charts");
         }
-        rules.init(&utility, "single-deck");
+        assert!(rules.init(&utility, "single-deck").is_err());
         unsafe {
             env::remove_var("STRIKER_URL_RULES");
         }
     }
 
+    #[test]
+    fn test_from_json() {
+        let rules = Rules::from_json(RULES_JSON.clone()).unwrap();
+        assert_eq!(rules.playbook, "single-deck");
+        assert!(rules.hit_soft_17);
+        assert!(!rules.surrender);
+        assert_eq!(rules.penetration, 0.75);
+        assert_eq!(rules.burn_cards, BURN_CARDS_DEFAULT);
+    }
+
+    #[test]
+    fn test_from_json_missing_field() {
+        let mut value = RULES_JSON.clone();
+        value.as_object_mut().unwrap().remove("blackjackPays");
+        let error = Rules::from_json(value).unwrap_err();
+        assert_eq!(error.to_string(), "Missing blackjackPays in the single-deck rules");
+    }
+
+    #[test]
+    fn test_builder() {
+        let rules = Rules::builder()
+            .unwrap()
+            .playbook("six-shoe")
+            .hit_soft_17(false)
+            .surrender(true)
+            .penetration(0.8)
+            .burn_cards(0)
+            .no_hole_card(true)
+            .no_hole_card_losses("OBO")
+            .build();
+        assert_eq!(rules.playbook, "six-shoe");
+        assert!(!rules.hit_soft_17);
+        assert!(rules.surrender);
+        assert_eq!(rules.penetration, 0.8);
+        assert_eq!(rules.burn_cards, 0);
+        assert_eq!(rules.no_hole_card_losses, "obo");
+        assert_eq!(rules.blackjack_pays, 3);
+        assert!(rules.double_any_two_cards);
+    }
+
+    #[test]
+    fn test_serde_round_trip() {
+        let rules: Rules = serde_json::from_value(RULES_JSON.clone()).unwrap();
+        assert_eq!(rules.playbook, "single-deck");
+        assert_eq!(rules.burn_cards, BURN_CARDS_DEFAULT);
+        assert_eq!(rules.no_hole_card_losses, NO_HOLE_CARD_ALL);
+
+        let rules = Rules::builder().unwrap().dealer_22_push(true).spanish_21(true).build();
+        let value = serde_json::to_value(&rules).unwrap();
+        assert_eq!(value["hitSoft17"], true);
+        assert_eq!(value["dealer22Push"], true);
+        assert_eq!(value["spanish21"], true);
+        assert_eq!(value["burnCards"], 1);
+        assert!(value.get("jsonResponse").is_none());
+
+        let fetched = Rules::from_json(value.clone()).unwrap();
+        assert_eq!(serde_json::to_value(&fetched).unwrap(), value);
+        let deserialized: Rules = serde_json::from_value(value.clone()).unwrap();
+        assert_eq!(serde_json::to_value(&deserialized).unwrap(), value);
+        assert!(serde_json::from_value::<Rules>(json!({ "playbook": "single-deck" })).is_err());
+    }
+
     #[test]
     fn test_rules_table() {
         let mut rules = Rules::new();
@@ -103,7 +396,7 @@ mod tests {
             "blackjackPays": 3,
             "penetration": 0.75
         });
-        rules.fetch_table();
+        rules.fetch_table().unwrap();
 
         assert_eq!(rules.playbook, "single-deck");
         assert_eq!(rules.hit_soft_17, false);
@@ -115,7 +408,110 @@ mod tests {
         assert_eq!(rules.blackjack_bets, 2);
         assert_eq!(rules.blackjack_pays, 3);
         assert!((rules.penetration - 0.75).abs() < 1e-6);
+        assert!(!rules.no_hole_card);
+        assert!(!rules.spanish_21);
+        assert!(!rules.double_down_rescue);
+        assert!(!rules.blackjack_switch);
+        assert!(!rules.free_bet);
+        assert!(!rules.dealer_22_push);
+        assert!(!rules.shuffle_model.csm);
+        assert_eq!(rules.burn_cards, BURN_CARDS_DEFAULT);
+        assert_eq!(rules.no_hole_card_losses, NO_HOLE_CARD_ALL);
+
+        println!("{}", rules);
+    }
+
+    #[test]
+    fn test_rules_table_spanish_21() {
+        let mut rules = Rules::new();
+        rules.json_response = SPANISH_21_RULES_JSON.clone();
+        rules.fetch_table().unwrap();
+
+        assert_eq!(rules.playbook, "spanish-21");
+        assert!(rules.spanish_21);
+        assert!(rules.surrender);
+        assert!(rules.double_down_rescue);
+    }
+
+    #[test]
+    fn test_rules_table_no_hole_card() {
+        let mut rules = Rules::new();
+        let mut json = RULES_JSON.clone();
+        json["noHoleCard"] = json!(true);
+        json["noHoleCardLosses"] = json!("OBBO");
+        rules.json_response = json;
+        rules.fetch_table().unwrap();
+
+        assert!(rules.no_hole_card);
+        assert_eq!(rules.no_hole_card_losses, "obbo");
+        println!("{}", rules);
+    }
+
+    #[test]
+    fn test_rules_table_switch_and_free_bet() {
+        let mut rules = Rules::new();
+        let mut json = RULES_JSON.clone();
+        json["blackjackSwitch"] = json!(true);
+        json["blackjackBets"] = json!(1);
+        json["blackjackPays"] = json!(1);
+        json["freeBet"] = json!(true);
+        rules.json_response = json;
+        rules.fetch_table().unwrap();
+
+        assert!(rules.blackjack_switch);
+        assert!(rules.free_bet);
+        assert!(rules.dealer_22_push);
+    }
+
+    #[test]
+    fn test_rules_table_switch_conflicts() {
+        let mut json = RULES_JSON.clone();
+        json["blackjackSwitch"] = json!(true);
+        let error = Rules::from_json(json.clone()).unwrap_err();
+        assert!(matches!(error, StrikerError::ConflictingRules { message, .. } if message.contains("even money")));
+
+        json["blackjackPays"] = json["blackjackBets"].clone();
+        json["dealer22Push"] = json!(false);
+        let error = Rules::from_json(json).unwrap_err();
+        assert!(matches!(error, StrikerError::ConflictingRules { message, .. } if message.contains("dealer 22")));
+    }
+
+    #[test]
+    fn test_builder_switch_rules() {
+        let rules = Rules::builder().unwrap().blackjack_switch(true).dealer_22_push(false).build();
+        assert_eq!(rules.blackjack_pays, rules.blackjack_bets);
+        assert!(rules.dealer_22_push);
+
+        let rules = Rules::builder().unwrap().build();
+        assert_ne!(rules.blackjack_pays, rules.blackjack_bets);
+        assert!(!rules.dealer_22_push);
+    }
+
+    #[test]
+    fn test_rules_table_shuffle() {
+        let mut rules = Rules::new();
+        let mut json = RULES_JSON.clone();
+        json["shuffle"] = json!({ "steps": ["riffle", "riffle", "strip", "riffle", "box"], "clumping": 0.25 });
+        json["burnCards"] = json!(5);
+        rules.json_response = json;
+        rules.fetch_table().unwrap();
+
+        assert_eq!(rules.shuffle_model.steps.len(), 5);
+        assert_eq!(rules.shuffle_model.clumping, 0.25);
+        assert_eq!(rules.burn_cards, 5);
+        println!("{}", rules);
+    }
+
+    #[test]
+    fn test_rules_table_side_bets() {
+        let mut rules = Rules::new();
+        let mut json = RULES_JSON.clone();
+        json["sideBets"] = json!({ "perfect-pairs": true, "buster": { "3": 2 } });
+        rules.json_response = json;
+        rules.fetch_table().unwrap();
 
+        assert!(rules.side_bets.has("perfect-pairs"));
+        assert_eq!(rules.side_bets.settle("buster", Some("3")), Some(2));
         println!("{}", rules);
     }
 }
@@ -1,12 +1,19 @@
 use crate::Arguments;
 use crate::cards::{Card, Rank};
-use crate::constants::constants::{DECKS_SINGLE_DECK, NUMBER_OF_CARDS_IN_DECK, STRATEGY_MIMIC, TRUE_COUNT_BET, TRUE_COUNT_MULTIPLIER};
+use crate::constants::constants::{DECKS_SINGLE_DECK, NUMBER_OF_CARDS_IN_DECK, NUMBER_OF_CARDS_IN_SPANISH_DECK, STRATEGY_MIMIC, TRUE_COUNT_BET, TRUE_COUNT_MULTIPLIER};
+use crate::errors::errors::StrikerError;
+use crate::resources::hole_card_charts::HOLE_CARD_CHARTS_JSON;
+use crate::strategy::bet_ramp::BetRamp;
 use crate::strategy::chart::Chart;
+use crate::strategy::rules::Rules;
 use crate::traits::traits::JsonFetcher;
 use crate::utilities::utilities::get_charts_url;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-#[derive(Debug, Clone)]
+// Serialized in the same layout as the strategy charts, the number of cards comes from the decks in play rather than the charts
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(from = "Value", into = "Value")]
 pub struct Strategy {
     pub playbook: String,
     pub counts: Vec<isize>,
@@ -16,10 +23,76 @@ pub struct Strategy {
     pub pair_split: Chart,
     pub soft_stand: Chart,
     pub hard_stand: Chart,
+    pub surrender: Chart,
+    pub hole_stand: Chart,
+    pub hole_double: Chart,
+    pub bet_ramp: BetRamp,
     pub number_of_cards: usize,
     pub json_response: Value,
 }
 
+// Typed strategy without charts, the rows are for the up cards 2 through A
+pub struct StrategyBuilder {
+    strategy: Strategy,
+    error: Option<StrikerError>,
+}
+
+impl StrategyBuilder {
+    // Count values for the cards 2 through A
+    pub fn counts(mut self, counts: [isize; 10]) -> Self {
+        self.strategy.counts = [0, 0].into_iter().chain(counts).collect();
+        self
+    }
+
+    pub fn insurance(mut self, insurance: &str) -> Self {
+        self.strategy.insurance = insurance.to_string();
+        self
+    }
+
+    pub fn number_of_cards(mut self, number_of_cards: usize) -> Self {
+        self.strategy.number_of_cards = number_of_cards;
+        self
+    }
+
+    // One row of a chart by its name in the charts, e.g. row("hard-stand", "16", ["Y", "Y", "Y", "Y", "Y", "N", "N", "N", "0", "N"])
+    pub fn row(mut self, chart: &str, key: &str, values: [&str; 10]) -> Self {
+        match self.strategy.get_chart_mut(chart) {
+            Some(rows) => {
+                for (index, value) in values.iter().enumerate() {
+                    rows.insert(key, Rank::Two.value() + index, value);
+                }
+            }
+            None => {
+                self.error.get_or_insert(StrikerError::UnknownChart(chart.to_string()));
+            }
+        }
+        self
+    }
+
+    pub fn bet(mut self, true_count: isize, bet: usize) -> Self {
+        self.strategy.bet_ramp.insert(true_count, bet);
+        self
+    }
+
+    // Strategies without their own hole card charts get the default ones, the first row for an unknown chart fails the build
+    pub fn build(mut self) -> Result<Strategy, StrikerError> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+        if self.strategy.hole_stand.keys().is_empty() && self.strategy.hole_double.keys().is_empty() {
+            strategy_load_table(&HOLE_CARD_CHARTS_JSON["hole-stand"], &mut self.strategy.hole_stand);
+            strategy_load_table(&HOLE_CARD_CHARTS_JSON["hole-double"], &mut self.strategy.hole_double);
+        }
+        Ok(self.strategy)
+    }
+}
+
+impl Default for Strategy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Strategy {
     pub fn new() -> Self {
         Strategy {
@@ -31,25 +104,29 @@ impl Strategy {
             pair_split: Chart::new("Pair Split"),
             soft_stand: Chart::new("Soft Stand"),
             hard_stand: Chart::new("Hard Stand"),
+            surrender: Chart::new("Surrender"),
+            hole_stand: Chart::new("Hole Card Stand"),
+            hole_double: Chart::new("Hole Card Double"),
+            bet_ramp: BetRamp::new(),
             number_of_cards: 52,
             json_response: Value::Null,
         }
     }
 
-    pub fn init(&mut self, fetcher: &dyn JsonFetcher, arguments: &Arguments) {
-        self.number_of_cards = arguments.number_of_decks * NUMBER_OF_CARDS_IN_DECK;
+    pub fn init(&mut self, fetcher: &dyn JsonFetcher, arguments: &Arguments, rules: &Rules) -> Result<(), StrikerError> {
+        self.number_of_cards = Self::cards_in_shoe(rules, arguments.number_of_decks);
 
         if arguments.strategy.to_lowercase() != "mimic" {
             let url = format!(
                 "http://{}/{}/{}",
-                get_charts_url().expect("Missing strategy chart URL"),
+                get_charts_url().ok_or(StrikerError::MissingUrl("STRIKER_URL_CHARTS"))?,
                 &arguments.decks,
                 &arguments.strategy
             );
-            match fetcher.fetch_json(&url) {
-                Ok(json_value) => self.json_response = json_value,
-                Err(e) => panic!("Error fetching JSON: {}", e),
-            }
+            self.json_response = fetcher.fetch_json(&url).map_err(|e| StrikerError::Fetch {
+                url: url.clone(),
+                message: e.to_string(),
+            })?;
             self.fetch_table(self.json_response.clone());
 
             self.soft_double.print();
@@ -57,11 +134,36 @@ impl Strategy {
             self.pair_split.print();
             self.soft_stand.print();
             self.hard_stand.print();
+            self.surrender.print();
+            self.hole_stand.print();
+            self.hole_double.print();
             self.print_count();
         }
+        Ok(())
     }
 
-    fn fetch_table(&mut self, data: Value) {
+    // The rules decide the deck, as they do for the shoe the tables deal from
+    pub fn cards_in_shoe(rules: &Rules, number_of_decks: usize) -> usize {
+        let cards_in_deck = if rules.spanish_21 { NUMBER_OF_CARDS_IN_SPANISH_DECK } else { NUMBER_OF_CARDS_IN_DECK };
+        number_of_decks * cards_in_deck
+    }
+
+    pub fn builder(playbook: &str) -> StrategyBuilder {
+        let mut strategy = Strategy::new();
+        strategy.playbook = playbook.to_string();
+        StrategyBuilder { strategy, error: None }
+    }
+
+    // Strategy from charts already in hand for a shoe of the given number of cards, without going to the charts server
+    pub fn from_json(data: Value, number_of_cards: usize) -> Self {
+        let mut strategy = Strategy::new();
+        strategy.number_of_cards = number_of_cards;
+        strategy.json_response = data.clone();
+        strategy.fetch_table(data);
+        strategy
+    }
+
+    pub fn fetch_table(&mut self, data: Value) {
         self.playbook = data["playbook"].as_str().unwrap_or_default().to_string();
         self.insurance = data["insurance"].as_str().unwrap_or_default().to_string();
         self.counts = data["counts"].as_array().unwrap_or(&vec![]).iter().map(|v| v.as_i64().unwrap_or(0) as isize).collect();
@@ -73,6 +175,62 @@ impl Strategy {
         strategy_load_table(&data["pair-split"], &mut self.pair_split);
         strategy_load_table(&data["soft-stand"], &mut self.soft_stand);
         strategy_load_table(&data["hard-stand"], &mut self.hard_stand);
+        strategy_load_table(&data["surrender"], &mut self.surrender);
+
+        // Strategies without their own hole card charts get the default ones
+        let hole = if data["hole-stand"].is_object() { &data } else { &*HOLE_CARD_CHARTS_JSON };
+        strategy_load_table(&hole["hole-stand"], &mut self.hole_stand);
+        strategy_load_table(&hole["hole-double"], &mut self.hole_double);
+        self.bet_ramp = BetRamp::fetch_table(&data["bet-ramp"]);
+    }
+
+    // Strategy in the same layout it is loaded from
+    pub fn to_json_object(&self) -> Value {
+        let mut json = serde_json::json!({
+            "playbook": self.playbook,
+            "counts": self.counts.iter().skip(2).collect::<Vec<_>>(),
+            "insurance": self.insurance,
+            "soft-double": self.soft_double.to_json_object(),
+            "hard-double": self.hard_double.to_json_object(),
+            "pair-split": self.pair_split.to_json_object(),
+            "soft-stand": self.soft_stand.to_json_object(),
+            "hard-stand": self.hard_stand.to_json_object(),
+            "surrender": self.surrender.to_json_object(),
+            "hole-stand": self.hole_stand.to_json_object(),
+            "hole-double": self.hole_double.to_json_object(),
+        });
+        if !self.bet_ramp.is_empty() {
+            json["bet-ramp"] = self.bet_ramp.to_json_object();
+        }
+        json
+    }
+
+    pub fn get_chart(&self, name: &str) -> Option<&Chart> {
+        match name {
+            "soft-double" => Some(&self.soft_double),
+            "hard-double" => Some(&self.hard_double),
+            "pair-split" => Some(&self.pair_split),
+            "soft-stand" => Some(&self.soft_stand),
+            "hard-stand" => Some(&self.hard_stand),
+            "surrender" => Some(&self.surrender),
+            "hole-stand" => Some(&self.hole_stand),
+            "hole-double" => Some(&self.hole_double),
+            _ => None,
+        }
+    }
+
+    pub fn get_chart_mut(&mut self, name: &str) -> Option<&mut Chart> {
+        match name {
+            "soft-double" => Some(&mut self.soft_double),
+            "hard-double" => Some(&mut self.hard_double),
+            "pair-split" => Some(&mut self.pair_split),
+            "soft-stand" => Some(&mut self.soft_stand),
+            "hard-stand" => Some(&mut self.hard_stand),
+            "surrender" => Some(&mut self.surrender),
+            "hole-stand" => Some(&mut self.hole_stand),
+            "hole-double" => Some(&mut self.hole_double),
+            _ => None,
+        }
     }
 
     pub fn get_running_count(&self, seen_cards: &[usize]) -> isize {
@@ -89,40 +247,73 @@ impl Strategy {
     }
 
     pub fn get_bet(&self, seen_cards: &[usize]) -> usize {
-        let true_count = self.get_true_count(seen_cards, self.get_running_count(seen_cards)).max(0);
-        (true_count as usize) * TRUE_COUNT_BET
+        let true_count = self.get_true_count(seen_cards, self.get_running_count(seen_cards));
+        if let Some(bet) = self.bet_ramp.get_bet(true_count) {
+            return bet;
+        }
+        (true_count.max(0) as usize) * TRUE_COUNT_BET
     }
 
     pub fn get_insurance(&self, seen_cards: &[usize]) -> bool {
         self.process_value(&self.insurance, self.get_true_count(seen_cards, self.get_running_count(seen_cards)), false)
     }
 
-    pub fn get_double(&self, seen_cards: &[usize], total: usize, soft: bool, up: &Card) -> bool {
+    pub fn get_double(&self, seen_cards: &[usize], total: usize, soft: bool, up: &Card) -> Result<bool, StrikerError> {
         let chart = if soft { &self.soft_double } else { &self.hard_double };
         let key = total.to_string();
-        self.process_value(
-            &chart.get_value_by_key(&key, up.rank.value()),
+        Ok(self.process_value(
+            chart.get_value_by_key(&key, up.rank.value())?,
             self.get_true_count(seen_cards, self.get_running_count(seen_cards)),
             false,
-        )
+        ))
     }
 
-    pub fn get_split(&self, seen_cards: &[usize], pair: &Card, up: &Card) -> bool {
-        self.process_value(
-            &self.pair_split.get_value_by_key(&pair.rank.key(), up.rank.value()),
+    pub fn get_split(&self, seen_cards: &[usize], pair: &Card, up: &Card) -> Result<bool, StrikerError> {
+        Ok(self.process_value(
+            self.pair_split.get_value_by_key(pair.rank.key(), up.rank.value())?,
             self.get_true_count(seen_cards, self.get_running_count(seen_cards)),
             false,
-        )
+        ))
     }
 
-    pub fn get_stand(&self, seen_cards: &[usize], total: usize, soft: bool, up: &Card) -> bool {
+    pub fn get_stand(&self, seen_cards: &[usize], total: usize, soft: bool, up: &Card) -> Result<bool, StrikerError> {
         let chart = if soft { &self.soft_stand } else { &self.hard_stand };
         let key = total.to_string();
-        self.process_value(
-            &chart.get_value_by_key(&key, up.rank.value()),
+        Ok(self.process_value(
+            chart.get_value_by_key(&key, up.rank.value())?,
             self.get_true_count(seen_cards, self.get_running_count(seen_cards)),
             true,
-        )
+        ))
+    }
+
+    // The surrender chart is optional, hands without a row are never surrendered
+    pub fn get_surrender(&self, seen_cards: &[usize], total: usize, up: &Card) -> bool {
+        match self.surrender.get_value(&total.to_string(), up.rank.value()) {
+            Some(value) => self.process_value(value, self.get_true_count(seen_cards, self.get_running_count(seen_cards)), false),
+            None => false,
+        }
+    }
+
+    // Hole card charts are played against the dealer's known total, None leaves the decision to the regular charts
+    pub fn get_hole_stand(&self, seen_cards: &[usize], total: usize, soft: bool, dealer_total: usize) -> Option<bool> {
+        self.get_hole_value(&self.hole_stand, seen_cards, total, soft, dealer_total, true)
+    }
+
+    pub fn get_hole_double(&self, seen_cards: &[usize], total: usize, soft: bool, dealer_total: usize) -> Option<bool> {
+        self.get_hole_value(&self.hole_double, seen_cards, total, soft, dealer_total, false)
+    }
+
+    // Rows are player totals, S prefixed when soft, columns are dealer totals 12 through 21
+    pub fn hole_key(total: usize, soft: bool) -> String {
+        if soft { format!("S{}", total) } else { total.to_string() }
+    }
+
+    fn get_hole_value(&self, chart: &Chart, seen_cards: &[usize], total: usize, soft: bool, dealer_total: usize, default: bool) -> Option<bool> {
+        if !(12..=21).contains(&dealer_total) {
+            return None;
+        }
+        let value = chart.get_value(&Self::hole_key(total, soft), dealer_total - 10)?;
+        Some(self.process_value(value, self.get_true_count(seen_cards, self.get_running_count(seen_cards)), default))
     }
 
     pub fn print_count(&self) {
@@ -144,6 +335,18 @@ impl Strategy {
     }
 }
 
+impl From<Value> for Strategy {
+    fn from(data: Value) -> Self {
+        Self::from_json(data, NUMBER_OF_CARDS_IN_DECK)
+    }
+}
+
+impl From<Strategy> for Value {
+    fn from(strategy: Strategy) -> Self {
+        strategy.to_json_object()
+    }
+}
+
 fn strategy_load_table(strategy: &Value, chart: &mut Chart) {
     if let Some(map) = strategy.as_object() {
         for (key, values) in map.iter() {
@@ -191,7 +394,7 @@ mod tests {
         unsafe {
             env::set_var("STRIKER_URL_CHARTS", "https://example.com/charts");
         }
-        strategy.init(&mock, &arguments);
+        strategy.init(&mock, &arguments, &Rules::default()).unwrap();
         unsafe {
             env::remove_var("STRIKER_URL_CHARTS");
         }
@@ -199,7 +402,6 @@ mod tests {
     }
 
     #[test]
-    #[should_panic]
     fn test_get_test_fetch_json_error() {
         let mock = MockJsonFetcherError::default();
         let mut strategy = Strategy::new();
@@ -209,10 +411,11 @@ mod tests {
         unsafe {
             env::set_var("STRIKER_URL_CHARTS", "https://example.com/charts");
         }
-        strategy.init(&mock, &arguments);
+        let result = strategy.init(&mock, &arguments, &Rules::default());
         unsafe {
             env::remove_var("STRIKER_URL_CHARTS");
         }
+        assert!(result.is_err());
     }
 
     #[test]
@@ -247,6 +450,17 @@ mod tests {
         assert_eq!(strategy.get_bet(&seen_cards), 0);
     }
 
+    #[test]
+    fn test_get_bet_from_bet_ramp() {
+        let mut strategy = Strategy::new();
+        let mut data = SINGLE_DECK_BASIC_JSON.clone();
+        data["bet-ramp"] = serde_json::json!({ "0": 6, "2": 12 });
+        strategy.fetch_table(data);
+        let seen_cards = [0, 0, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1];
+        assert_eq!(strategy.get_bet(&seen_cards), 6);
+        assert_eq!(strategy.to_json_object()["bet-ramp"]["2"], 12);
+    }
+
     #[test]
     fn test_get_insurance() {
         let strategy = Strategy::new();
@@ -259,7 +473,7 @@ mod tests {
         let strategy = mock_strategy();
         let up = Card::new(Rank::Ten, Suit::Hearts);
         let seen_cards = [0, 0, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1];
-        assert_eq!(strategy.get_double(&seen_cards, 20, false, &up), false);
+        assert_eq!(strategy.get_double(&seen_cards, 20, false, &up).unwrap(), false);
     }
 
     #[test]
@@ -268,7 +482,7 @@ mod tests {
         let up = Card::new(Rank::Ten, Suit::Hearts);
         let pair = Card::new(Rank::Ten, Suit::Hearts);
         let seen_cards = [0, 0, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1];
-        assert_eq!(strategy.get_split(&seen_cards, &pair, &up), false);
+        assert_eq!(strategy.get_split(&seen_cards, &pair, &up).unwrap(), false);
     }
 
     #[test]
@@ -276,7 +490,127 @@ mod tests {
         let strategy = mock_strategy();
         let up = Card::new(Rank::Ten, Suit::Hearts);
         let seen_cards = [0, 0, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1];
-        assert_eq!(strategy.get_stand(&seen_cards, 20, false, &up), true);
+        assert_eq!(strategy.get_stand(&seen_cards, 20, false, &up).unwrap(), true);
+    }
+
+    #[test]
+    fn test_to_json_object_round_trip() {
+        let mut strategy = Strategy::new();
+        strategy.fetch_table(SINGLE_DECK_BASIC_JSON.clone());
+        let json = strategy.to_json_object();
+        assert_eq!(json["counts"].as_array().unwrap().len(), 10);
+        assert_eq!(json["hard-stand"]["13"], SINGLE_DECK_BASIC_JSON["hard-stand"]["13"]);
+        assert_eq!(json["pair-split"]["A"], SINGLE_DECK_BASIC_JSON["pair-split"]["A"]);
+
+        strategy.get_chart_mut("hard-stand").unwrap().insert("16", 10, "0");
+        assert_eq!(strategy.to_json_object()["hard-stand"]["16"][8], "0");
+        assert!(strategy.get_chart_mut("unknown").is_none());
+    }
+
+    #[test]
+    fn test_from_json() {
+        let strategy = Strategy::from_json(SINGLE_DECK_BASIC_JSON.clone(), 52);
+        assert_eq!(strategy.number_of_cards, 52);
+        assert_eq!(strategy.counts.len(), 12);
+        assert_eq!(strategy.json_response, *SINGLE_DECK_BASIC_JSON);
+        assert_eq!(strategy.to_json_object()["hard-stand"]["13"], SINGLE_DECK_BASIC_JSON["hard-stand"]["13"]);
+    }
+
+    #[test]
+    fn test_builder() {
+        let strategy = Strategy::builder("single-deck-hilo")
+            .counts([1, 1, 1, 1, 1, 0, 0, 0, -1, -1])
+            .insurance("3")
+            .number_of_cards(104)
+            .row("hard-stand", "16", ["Y", "Y", "Y", "Y", "Y", "N", "N", "N", "0", "N"])
+            .row("pair-split", "A", ["Y", "Y", "Y", "Y", "Y", "Y", "Y", "Y", "Y", "Y"])
+            .bet(0, 1)
+            .bet(2, 4)
+            .build()
+            .unwrap();
+        assert_eq!(strategy.playbook, "single-deck-hilo");
+        assert_eq!(strategy.counts, vec![0, 0, 1, 1, 1, 1, 1, 0, 0, 0, -1, -1]);
+        assert_eq!(strategy.number_of_cards, 104);
+        assert_eq!(strategy.bet_ramp.get_bet(3), Some(4));
+        assert!(!strategy.hole_stand.keys().is_empty());
+
+        let seen_cards = [0, 0, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1];
+        assert!(strategy.get_stand(&seen_cards, 16, false, &Card::new(Rank::Six, Suit::Hearts)).unwrap());
+        assert!(!strategy.get_stand(&seen_cards, 16, false, &Card::new(Rank::Seven, Suit::Hearts)).unwrap());
+        assert!(
+            strategy
+                .get_split(&seen_cards, &Card::new(Rank::Ace, Suit::Hearts), &Card::new(Rank::Ten, Suit::Hearts))
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_builder_unknown_chart() {
+        let result = Strategy::builder("single-deck-hilo")
+            .row("soft-split", "16", ["N"; 10])
+            .row("pair-split", "A", ["Y"; 10])
+            .build();
+        assert_eq!(result.unwrap_err().to_string(), "Unknown chart soft-split");
+    }
+
+    #[test]
+    fn test_init_cards_in_shoe() {
+        let arguments = Arguments {
+            number_of_decks: 6,
+            ..Default::default()
+        };
+        let mut strategy = Strategy::new();
+        strategy.init(&MockJsonFetcher, &arguments, &Rules::default()).unwrap();
+        assert_eq!(strategy.number_of_cards, 6 * NUMBER_OF_CARDS_IN_DECK);
+
+        // A Spanish 21 table dealt from whatever the decks are called
+        let rules = Rules::builder().unwrap().spanish_21(true).build();
+        strategy.init(&MockJsonFetcher, &arguments, &rules).unwrap();
+        assert_eq!(strategy.number_of_cards, 6 * NUMBER_OF_CARDS_IN_SPANISH_DECK);
+    }
+
+    #[test]
+    fn test_serde_round_trip() {
+        let strategy: Strategy = serde_json::from_value(SINGLE_DECK_BASIC_JSON.clone()).unwrap();
+        assert_eq!(strategy.number_of_cards, NUMBER_OF_CARDS_IN_DECK);
+        let value = serde_json::to_value(&strategy).unwrap();
+        assert_eq!(value, strategy.to_json_object());
+        assert_eq!(value["hard-stand"]["13"], SINGLE_DECK_BASIC_JSON["hard-stand"]["13"]);
+        let deserialized: Strategy = serde_json::from_value(value.clone()).unwrap();
+        assert_eq!(serde_json::to_value(&deserialized).unwrap(), value);
+    }
+
+    #[test]
+    fn test_get_surrender() {
+        let mut strategy = Strategy::new();
+        let mut data = SINGLE_DECK_BASIC_JSON.clone();
+        data["surrender"] = serde_json::json!({ "16": ["N", "N", "N", "N", "N", "N", "N", "Y", "Y", "Y"] });
+        strategy.fetch_table(data);
+        let seen_cards = [0, 0, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1];
+        assert!(strategy.get_surrender(&seen_cards, 16, &Card::new(Rank::Ten, Suit::Hearts)));
+        assert!(!strategy.get_surrender(&seen_cards, 16, &Card::new(Rank::Six, Suit::Hearts)));
+        assert!(!strategy.get_surrender(&seen_cards, 15, &Card::new(Rank::Ten, Suit::Hearts)));
+    }
+
+    #[test]
+    fn test_get_hole_charts() {
+        let mut strategy = Strategy::new();
+        strategy.fetch_table(SINGLE_DECK_BASIC_JSON.clone());
+        let seen_cards = [0, 0, 1, 1, 1, 1, 1, 1, 1, 1, 1, 1];
+        assert_eq!(strategy.get_hole_stand(&seen_cards, 16, false, 12), Some(true));
+        assert_eq!(strategy.get_hole_stand(&seen_cards, 16, false, 17), Some(false));
+        assert_eq!(strategy.get_hole_stand(&seen_cards, 8, false, 17), None);
+        assert_eq!(strategy.get_hole_stand(&seen_cards, 16, false, 9), None);
+        assert_eq!(strategy.get_hole_double(&seen_cards, 18, true, 16), Some(true));
+        assert_eq!(strategy.get_hole_double(&seen_cards, 11, false, 19), Some(false));
+        assert_eq!(strategy.to_json_object()["hole-stand"]["S18"][0], "Y");
+
+        let mut data = SINGLE_DECK_BASIC_JSON.clone();
+        data["hole-stand"] = serde_json::json!({ "16": ["N", "N", "N", "N", "N", "N", "N", "N", "N", "N"] });
+        strategy = Strategy::new();
+        strategy.fetch_table(data);
+        assert_eq!(strategy.get_hole_stand(&seen_cards, 16, false, 12), Some(false));
+        assert_eq!(strategy.get_hole_double(&seen_cards, 11, false, 12), None);
     }
 
     #[test]
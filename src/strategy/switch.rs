@@ -0,0 +1,173 @@
+use crate::cards::card::Card;
+use crate::cards::hand::Hand;
+use crate::strategy::rules::Rules;
+
+// Totals with aces counted as one, the highest is a hard 21 hitting a ten
+const TOTALS: usize = 32;
+
+// Dealer finishes on 17 to 21, on 22 or over 22
+const FINALS: usize = 7;
+const FINAL_22: usize = 5;
+const FINAL_OVER_22: usize = 6;
+
+// Blackjack Switch hand values against one up card, worked out on an infinite deck from the dealer's final totals.
+// Each total is worth the better of standing and hitting; doubles and splits are left out, which only ever undervalues a hand.
+pub struct SwitchValues {
+    values: [[f64; 2]; TOTALS],
+    blackjack: f64,
+}
+
+impl SwitchValues {
+    pub fn new(rules: &Rules, up: &Card) -> Self {
+        let finals = dealer_finals(card_value(up), rules.hit_soft_17);
+        let stand = |total: usize| -> f64 {
+            finals
+                .iter()
+                .enumerate()
+                .map(|(index, chance)| match index {
+                    FINAL_OVER_22 => *chance,
+                    FINAL_22 if rules.dealer_22_push => 0.0,
+                    FINAL_22 => *chance,
+                    _ => chance * (total as isize - (index + 17) as isize).signum() as f64,
+                })
+                .sum()
+        };
+
+        let mut values = [[0.0; 2]; TOTALS];
+        for total in (2..TOTALS).rev() {
+            for soft in [false, true] {
+                let best = best_total(total, soft);
+                values[total][soft as usize] = if best > 21 {
+                    -1.0
+                } else {
+                    let hit = (1..=10).map(|value| draw_chance(value) * values[total + value][(soft || value == 1) as usize]).sum();
+                    stand(best).max(hit)
+                };
+            }
+        }
+
+        Self {
+            values,
+            blackjack: rules.blackjack_pays as f64 / rules.blackjack_bets.max(1) as f64,
+        }
+    }
+
+    // Only a dealt hand can be a blackjack, a switched 21 is played as any other 21
+    pub fn value(&self, first: &Card, second: &Card, dealt: bool) -> f64 {
+        let mut hand = Hand::new();
+        hand.add_card(first.clone());
+        hand.add_card(second.clone());
+        if dealt && hand.is_blackjack() {
+            return self.blackjack;
+        }
+        if hand.is_soft() {
+            return self.values[hand.total - 10][1];
+        }
+        self.values[hand.total][0]
+    }
+}
+
+fn card_value(card: &Card) -> usize {
+    if card.is_ace() { 1 } else { card.rank.value() }
+}
+
+fn best_total(total: usize, soft: bool) -> usize {
+    if soft && total + 10 <= 21 { total + 10 } else { total }
+}
+
+// Four of the thirteen ranks count ten
+fn draw_chance(value: usize) -> f64 {
+    if value == 10 { 4.0 / 13.0 } else { 1.0 / 13.0 }
+}
+
+// Chance of each dealer final total from the up card, given the dealer has no blackjack
+fn dealer_finals(up: usize, hit_soft_17: bool) -> [f64; FINALS] {
+    let mut finals = [[[0.0; FINALS]; 2]; TOTALS];
+    for total in (2..TOTALS).rev() {
+        for soft in [false, true] {
+            let best = best_total(total, soft);
+            finals[total][soft as usize] = if best > 21 {
+                let mut bust = [0.0; FINALS];
+                bust[if best == 22 { FINAL_22 } else { FINAL_OVER_22 }] = 1.0;
+                bust
+            } else if best >= 17 && !(hit_soft_17 && soft && total == 7) {
+                let mut stand = [0.0; FINALS];
+                stand[best - 17] = 1.0;
+                stand
+            } else {
+                let mut hit = [0.0; FINALS];
+                for value in 1..=10 {
+                    for (final_chance, next) in hit.iter_mut().zip(finals[total + value][(soft || value == 1) as usize]) {
+                        *final_chance += draw_chance(value) * next;
+                    }
+                }
+                hit
+            };
+        }
+    }
+
+    let blackjack = |value: usize| up + value == 11 && (up == 1 || value == 1);
+    let others: f64 = (1..=10).filter(|value| !blackjack(*value)).map(draw_chance).sum();
+    let mut result = [0.0; FINALS];
+    for value in (1..=10).filter(|value| !blackjack(*value)) {
+        for (final_chance, next) in result.iter_mut().zip(finals[up + value][(up == 1 || value == 1) as usize]) {
+            *final_chance += draw_chance(value) / others * next;
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::{Rank, Suit};
+
+    fn card(rank: Rank) -> Card {
+        Card::new(rank, Suit::Hearts)
+    }
+
+    #[test]
+    fn test_dealer_finals() {
+        for up in 1..=10 {
+            let finals = dealer_finals(up, true);
+            assert!((finals.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+        }
+        // Busting is most likely under a six, least likely under an ace with no blackjack
+        let six = dealer_finals(6, false);
+        let ace = dealer_finals(1, false);
+        assert!(six[FINAL_22] + six[FINAL_OVER_22] > 0.4);
+        assert!(ace[FINAL_22] + ace[FINAL_OVER_22] < 0.2);
+        // Standing on soft 17 leaves more 17s than hitting it
+        assert!(dealer_finals(1, false)[0] > dealer_finals(1, true)[0]);
+    }
+
+    #[test]
+    fn test_values_follow_the_up_card() {
+        let rules = Rules::default();
+        let six = SwitchValues::new(&rules, &card(Rank::Six));
+        let ten = SwitchValues::new(&rules, &card(Rank::Ten));
+        assert!(six.value(&card(Rank::Ten), &card(Rank::Six), true) > ten.value(&card(Rank::Ten), &card(Rank::Six), true));
+        assert!(six.value(&card(Rank::Ten), &card(Rank::Queen), true) > 0.6);
+        // The published infinite deck figure for 16 against a ten is -0.54 either way it is played
+        assert!((ten.value(&card(Rank::Ten), &card(Rank::Six), true) + 0.54).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_switched_21_is_not_a_blackjack() {
+        let rules = Rules::builder().unwrap().blackjack_switch(true).build();
+        let values = SwitchValues::new(&rules, &card(Rank::Ten));
+        assert_eq!(values.value(&card(Rank::Ace), &card(Rank::King), true), 1.0);
+        let switched = values.value(&card(Rank::Ace), &card(Rank::King), false);
+        assert!(switched < 1.0);
+        assert!(switched > values.value(&card(Rank::King), &card(Rank::Queen), false));
+    }
+
+    #[test]
+    fn test_dealer_22_push_lowers_values() {
+        let mut rules = Rules::default();
+        let pays = SwitchValues::new(&rules, &card(Rank::Six));
+        rules.dealer_22_push = true;
+        let pushes = SwitchValues::new(&rules, &card(Rank::Six));
+        assert!(pushes.value(&card(Rank::Ten), &card(Rank::Nine), false) < pays.value(&card(Rank::Ten), &card(Rank::Nine), false));
+    }
+}
@@ -0,0 +1,2 @@
+// sweep module
+pub mod sweep;
@@ -0,0 +1,533 @@
+use crate::arguments::arguments::Arguments;
+use crate::arguments::parameters::Parameters;
+use crate::constants::constants::*;
+use crate::errors::errors::StrikerError;
+use crate::report::report::Report;
+use crate::simulator::simulator::Simulator;
+use crate::strategy::bet_ramp::BetRamp;
+use crate::strategy::rules::Rules;
+use crate::strategy::strategy::Strategy;
+use crate::traits::traits::JsonFetcher;
+use crate::utilities::topology::Topology;
+use num_format::{Locale, ToFormattedString};
+use serde::Deserialize;
+use serde_json::{Map, Value, json};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::thread;
+
+const STRATEGIES: [&str; 7] = [
+    STRATEGY_MIMIC,
+    STRATEGY_BASIC,
+    STRATEGY_LINEAR,
+    STRATEGY_POLYNOMIAL,
+    STRATEGY_NEURAL,
+    STRATEGY_HIGH_LOW,
+    STRATEGY_WONG,
+];
+
+// Every combination of the listed values is one cell, an empty list keeps the command line value or the table's own
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, rename_all = "camelCase", deny_unknown_fields)]
+pub struct Grid {
+    pub decks: Vec<String>,
+    pub strategies: Vec<String>,
+    pub penetrations: Vec<f64>,
+    pub rules: Vec<Map<String, Value>>,
+    pub bet_ramps: Vec<BetRamp>,
+    pub hands: Vec<usize>,
+    pub interleaved: bool,
+}
+
+// One configuration of the grid, the rules are overrides of the fetched table in its camel case keys
+#[derive(Debug, Clone)]
+pub struct Cell {
+    pub decks: String,
+    pub strategy: String,
+    pub penetration: Option<f64>,
+    pub rules: Map<String, Value>,
+    pub bet_ramp: Option<BetRamp>,
+    pub number_of_hands: usize,
+}
+
+impl Cell {
+    pub fn rules_label(&self) -> String {
+        if self.rules.is_empty() {
+            return "table".to_string();
+        }
+        self.rules
+            .iter()
+            .map(|(key, value)| format!("{}={}", key, value.as_str().map(String::from).unwrap_or_else(|| value.to_string())))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    pub fn bet_ramp_label(&self) -> String {
+        match &self.bet_ramp {
+            Some(ramp) => ramp
+                .to_json_object()
+                .as_object()
+                .unwrap()
+                .iter()
+                .map(|(true_count, bet)| format!("{}:{}", true_count, bet))
+                .collect::<Vec<_>>()
+                .join(" "),
+            None => "strategy".to_string(),
+        }
+    }
+
+    pub fn penetration_label(&self) -> String {
+        self.penetration.map(|penetration| format!("{:.2}", penetration)).unwrap_or_else(|| "table".to_string())
+    }
+
+    pub fn file_name(&self, index: usize) -> String {
+        format!("{:03}-{}-{}.json", index + 1, self.decks, self.strategy)
+    }
+
+    pub fn to_json_object(&self) -> Value {
+        json!({
+            "decks": self.decks,
+            "strategy": self.strategy,
+            "penetration": self.penetration,
+            "rules": self.rules,
+            "bet_ramp": self.bet_ramp.as_ref().map(|ramp| ramp.to_json_object()),
+            "number_of_hands": self.number_of_hands,
+        })
+    }
+}
+
+// What a cell runs with once its tables are loaded
+struct Configuration {
+    parameters: Parameters,
+    rules: Rules,
+    strategy: Strategy,
+}
+
+// Runs every cell of a grid, one after the other over all the threads or interleaved with one thread per cell
+pub struct Sweep {
+    pub cells: Vec<Cell>,
+    interleaved: bool,
+    number_of_threads: usize,
+    pin_threads: bool,
+    target_stderr: Option<f64>,
+    advantage_play: Option<String>,
+}
+
+impl Sweep {
+    pub fn new(grid: &Grid, arguments: &Arguments) -> Result<Self, StrikerError> {
+        let decks = if grid.decks.is_empty() { vec![arguments.decks.clone()] } else { grid.decks.clone() };
+        let strategies = if grid.strategies.is_empty() {
+            vec![arguments.strategy.clone()]
+        } else {
+            grid.strategies.clone()
+        };
+        let penetrations: Vec<Option<f64>> = if grid.penetrations.is_empty() {
+            vec![None]
+        } else {
+            grid.penetrations.iter().map(|p| Some(*p)).collect()
+        };
+        let rules = if grid.rules.is_empty() { vec![Map::new()] } else { grid.rules.clone() };
+        let bet_ramps: Vec<Option<BetRamp>> = if grid.bet_ramps.is_empty() {
+            vec![None]
+        } else {
+            grid.bet_ramps.iter().cloned().map(Some).collect()
+        };
+        let hands = if grid.hands.is_empty() { vec![arguments.number_of_hands] } else { grid.hands.clone() };
+
+        for deck in &decks {
+            Self::number_of_decks(deck).ok_or_else(|| StrikerError::InvalidGrid(format!("unknown decks {}", deck)))?;
+        }
+        for strategy in &strategies {
+            if !STRATEGIES.contains(&strategy.as_str()) {
+                return Err(StrikerError::InvalidGrid(format!("unknown strategy {}", strategy)));
+            }
+        }
+        if let Some(penetration) = grid.penetrations.iter().find(|p| **p <= 0.0 || **p >= 1.0) {
+            return Err(StrikerError::InvalidGrid(format!("penetration {} must be between 0 and 1", penetration)));
+        }
+        if let Some(number) = hands.iter().find(|h| **h < NUMBER_OF_HANDS_MINIMUM || **h > NUMBER_OF_HANDS_MAXIMUM) {
+            return Err(StrikerError::InvalidGrid(format!(
+                "{} hands must be between {} and {}",
+                number, NUMBER_OF_HANDS_MINIMUM, NUMBER_OF_HANDS_MAXIMUM
+            )));
+        }
+
+        let mut cells = Vec::new();
+        for deck in &decks {
+            for strategy in &strategies {
+                for penetration in &penetrations {
+                    for overrides in &rules {
+                        for bet_ramp in &bet_ramps {
+                            for number_of_hands in &hands {
+                                cells.push(Cell {
+                                    decks: deck.clone(),
+                                    strategy: strategy.clone(),
+                                    penetration: *penetration,
+                                    rules: overrides.clone(),
+                                    bet_ramp: bet_ramp.clone(),
+                                    number_of_hands: *number_of_hands,
+                                });
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(Self {
+            cells,
+            interleaved: grid.interleaved,
+            number_of_threads: arguments.number_of_threads.max(1),
+            pin_threads: arguments.pin_threads,
+            target_stderr: arguments.target_stderr,
+            advantage_play: arguments.advantage_play.clone(),
+        })
+    }
+
+    pub fn load(path: &str, arguments: &Arguments) -> Result<Self, StrikerError> {
+        let text = fs::read_to_string(path).map_err(|e| StrikerError::file("read grid file", path, e))?;
+        let grid: Grid = serde_json::from_str(&text).map_err(|e| StrikerError::InvalidGrid(format!("{}: {}", path, e)))?;
+        Self::new(&grid, arguments)
+    }
+
+    fn number_of_decks(decks: &str) -> Option<usize> {
+        match decks {
+            DECKS_SINGLE_DECK => Some(1),
+            DECKS_DOUBLE_DECK => Some(2),
+            DECKS_SIX_SHOE | DECKS_SPANISH_21 => Some(6),
+            _ => None,
+        }
+    }
+
+    // Fetch each rules table and strategy once, then simulate every cell, in grid order
+    pub fn run(&mut self, fetcher: &dyn JsonFetcher) -> Result<Vec<Report>, StrikerError> {
+        let mut tables: BTreeMap<String, Rules> = BTreeMap::new();
+        let mut strategies: BTreeMap<(String, String), Strategy> = BTreeMap::new();
+        let mut configurations = Vec::new();
+
+        for cell in &self.cells {
+            if !tables.contains_key(&cell.decks) {
+                let mut rules = Rules::new();
+                rules.init(fetcher, &cell.decks)?;
+                tables.insert(cell.decks.clone(), rules);
+            }
+            let key = (cell.decks.clone(), cell.strategy.clone());
+            if !strategies.contains_key(&key) {
+                let mut strategy = Strategy::new();
+                strategy.init(fetcher, &self.arguments(cell), &tables[&cell.decks])?;
+                strategies.insert(key.clone(), strategy);
+            }
+            configurations.push(self.configure(cell, &tables[&cell.decks], &strategies[&key])?);
+        }
+
+        // Record the penetration each cell ran with, taken from the table when the grid left it out
+        for (cell, configuration) in self.cells.iter_mut().zip(&configurations) {
+            cell.penetration = Some(configuration.rules.penetration);
+        }
+        self.simulate(configurations)
+    }
+
+    fn arguments(&self, cell: &Cell) -> Arguments {
+        Arguments {
+            command: COMMAND_SWEEP.to_string(),
+            strategy: cell.strategy.clone(),
+            decks: cell.decks.clone(),
+            number_of_decks: Self::number_of_decks(&cell.decks).unwrap_or(1),
+            number_of_hands: cell.number_of_hands,
+            number_of_threads: if self.interleaved { 1 } else { self.number_of_threads },
+            pin_threads: self.pin_threads,
+            target_stderr: self.target_stderr,
+            advantage_play: self.advantage_play.clone(),
+            ..Default::default()
+        }
+    }
+
+    // The cell's rules are the fetched table with the grid's penetration and rule overrides on top
+    fn configure(&self, cell: &Cell, table: &Rules, strategy: &Strategy) -> Result<Configuration, StrikerError> {
+        let mut value = serde_json::to_value(table).map_err(|e| StrikerError::InvalidGrid(e.to_string()))?;
+        if let Some(object) = value.as_object_mut() {
+            // The rules accept unknown keys, so a misspelt override would otherwise be silently ignored
+            for (key, rule) in &cell.rules {
+                if !object.contains_key(key) {
+                    return Err(StrikerError::InvalidGrid(format!("unknown rule {}", key)));
+                }
+                object.insert(key.clone(), rule.clone());
+            }
+            if let Some(penetration) = cell.penetration {
+                object.insert("penetration".to_string(), json!(penetration));
+            }
+        }
+        let rules: Rules = serde_json::from_value(value).map_err(|e| StrikerError::InvalidGrid(format!("rules {}: {}", cell.rules_label(), e)))?;
+
+        let mut strategy = strategy.clone();
+        let mut parameters = Parameters::new(&self.arguments(cell));
+        parameters.verbose = false;
+        strategy.number_of_cards = Strategy::cards_in_shoe(&rules, parameters.number_of_decks);
+        if let Some(bet_ramp) = &cell.bet_ramp {
+            strategy.bet_ramp = bet_ramp.clone();
+        }
+
+        Ok(Configuration { parameters, rules, strategy })
+    }
+
+    fn simulate(&self, configurations: Vec<Configuration>) -> Result<Vec<Report>, StrikerError> {
+        let total = configurations.len();
+        if !self.interleaved {
+            let mut reports = Vec::new();
+            for (index, configuration) in configurations.iter().enumerate() {
+                println!("    Cell {} of {}: {}", index + 1, total, self.describe(index));
+                reports.push(Simulator::simulate(&configuration.parameters, &configuration.rules, &configuration.strategy)?);
+            }
+            return Ok(reports);
+        }
+
+        // Cells are dealt round robin to the threads, each cell played out by a single table
+        let threads = self.number_of_threads.min(total).max(1);
+        let mut shares: Vec<Vec<(usize, Configuration)>> = (0..threads).map(|_| Vec::new()).collect();
+        for (index, configuration) in configurations.into_iter().enumerate() {
+            shares[index % threads].push((index, configuration));
+        }
+        let mut handles = Vec::new();
+        let pin_threads = self.pin_threads;
+        for (thread_index, share) in shares.into_iter().enumerate() {
+            handles.push(thread::spawn(move || {
+                if pin_threads {
+                    Topology::pin_thread(thread_index);
+                }
+                share
+                    .into_iter()
+                    .map(|(index, configuration)| {
+                        let mut report = Report::new();
+                        report.init(&configuration.parameters);
+                        report.merge(
+                            Simulator::new(&configuration.parameters, &configuration.rules, &configuration.strategy)?
+                                .run_once()?
+                                .get_report(),
+                        );
+                        report.finish();
+                        Ok((index, report))
+                    })
+                    .collect::<Vec<Result<(usize, Report), StrikerError>>>()
+            }));
+        }
+
+        let mut reports: Vec<Option<Report>> = vec![None; total];
+        for handle in handles {
+            for result in handle.join().map_err(StrikerError::worker)? {
+                let (index, report) = result?;
+                println!("    Cell {} of {}: {}", index + 1, total, self.describe(index));
+                reports[index] = Some(report);
+            }
+        }
+        // Every cell has its report, in order, or the cells and reports would no longer line up
+        reports
+            .into_iter()
+            .enumerate()
+            .map(|(index, report)| report.ok_or_else(|| StrikerError::InvalidGrid(format!("cell {} has no report", index + 1))))
+            .collect()
+    }
+
+    fn describe(&self, index: usize) -> String {
+        let cell = &self.cells[index];
+        format!(
+            "{} {}, penetration {}, rules {}, bet ramp {}, {} hands",
+            cell.decks,
+            cell.strategy,
+            cell.penetration_label(),
+            cell.rules_label(),
+            cell.bet_ramp_label(),
+            cell.number_of_hands.to_formatted_string(&Locale::en)
+        )
+    }
+
+    pub fn to_comparison_csv(&self, reports: &[Report]) -> String {
+        let mut csv = String::from("cell,decks,strategy,penetration,rules,bet_ramp,hands,total_bet,total_won,advantage,report\n");
+        for (index, (cell, report)) in self.cells.iter().zip(reports).enumerate() {
+            csv.push_str(&format!(
+                "{},{},{},{},{},{},{},{},{},{:.4},{}\n",
+                index + 1,
+                csv_field(&cell.decks),
+                csv_field(&cell.strategy),
+                cell.penetration_label(),
+                csv_field(&cell.rules_label()),
+                csv_field(&cell.bet_ramp_label()),
+                report.total_hands,
+                report.total_bet,
+                report.total_won,
+                report.advantage,
+                csv_field(&cell.file_name(index))
+            ));
+        }
+        csv
+    }
+
+    pub fn print(&self, reports: &[Report]) {
+        println!(
+            "    {:>4}  {:<12} {:<11} {:>5}  {:<24} {:>15} {:>10}",
+            "Cell", "Decks", "Strategy", "Pen.", "Rules", "Hands", "Advantage"
+        );
+        for (index, (cell, report)) in self.cells.iter().zip(reports).enumerate() {
+            println!(
+                "    {:>4}  {:<12} {:<11} {:>5}  {:<24} {:>15} {:>+9.3}%",
+                index + 1,
+                cell.decks,
+                cell.strategy,
+                cell.penetration_label(),
+                cell.rules_label(),
+                report.total_hands.to_formatted_string(&Locale::en),
+                report.advantage
+            );
+        }
+    }
+
+    // One report per cell, with the cell it ran, and the comparison table of them all
+    pub fn write(&self, reports: &[Report], directory: &str) -> Result<(), StrikerError> {
+        fs::create_dir_all(directory).map_err(|e| StrikerError::file("create", directory, e))?;
+        for (index, (cell, report)) in self.cells.iter().zip(reports).enumerate() {
+            let mut value = report.to_json_object();
+            value["cell"] = cell.to_json_object();
+            let path = Path::new(directory).join(cell.file_name(index));
+            fs::write(&path, serde_json::to_string_pretty(&value).unwrap_or_default()).map_err(|e| StrikerError::file("write", &path.to_string_lossy(), e))?;
+        }
+        let path = Path::new(directory).join(SWEEP_COMPARISON_FILE);
+        fs::write(&path, self.to_comparison_csv(reports)).map_err(|e| StrikerError::file("write", &path.to_string_lossy(), e))
+    }
+}
+
+// Quoted when it holds a comma, quote or line break, as the side bets in a rules override do
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::report::comparison::Comparison;
+    use crate::resources::rules_table::RULES_JSON;
+
+    fn make_grid(value: Value) -> Grid {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn make_arguments(number_of_hands: usize, number_of_threads: usize) -> Arguments {
+        Arguments {
+            number_of_hands,
+            number_of_threads,
+            ..Default::default()
+        }
+    }
+
+    fn make_configurations(sweep: &Sweep) -> Vec<Configuration> {
+        let table = Rules::from_json(RULES_JSON.clone()).unwrap();
+        sweep.cells.iter().map(|cell| sweep.configure(cell, &table, &Strategy::new()).unwrap()).collect()
+    }
+
+    #[test]
+    fn test_cells() {
+        let grid = make_grid(json!({
+            "decks": ["single-deck", "six-shoe"],
+            "strategies": ["mimic", "basic", "high-low"],
+            "penetrations": [0.65, 0.8],
+            "rules": [{}, { "surrender": true, "hitSoft17": false, "noHoleCardLosses": "obo" }],
+            "betRamps": [{ "0": 2, "2": 8 }]
+        }));
+        let sweep = Sweep::new(&grid, &make_arguments(MILLION, 4)).unwrap();
+        assert_eq!(sweep.cells.len(), 24);
+        assert!(!sweep.interleaved);
+
+        let cell = &sweep.cells[23];
+        assert_eq!(cell.decks, DECKS_SIX_SHOE);
+        assert_eq!(cell.strategy, STRATEGY_HIGH_LOW);
+        assert_eq!(cell.penetration_label(), "0.80");
+        assert_eq!(cell.rules_label(), "hitSoft17=false noHoleCardLosses=obo surrender=true");
+        assert_eq!(cell.bet_ramp_label(), "0:2 2:8");
+        assert_eq!(cell.number_of_hands, MILLION);
+        assert_eq!(cell.file_name(23), "024-six-shoe-high-low.json");
+        assert_eq!(sweep.cells[0].rules_label(), "table");
+
+        let sweep = Sweep::new(&Grid::default(), &make_arguments(MILLION, 4)).unwrap();
+        assert_eq!(sweep.cells.len(), 1);
+        assert_eq!(sweep.cells[0].decks, DECKS_SINGLE_DECK);
+        assert_eq!(sweep.cells[0].penetration_label(), "table");
+        assert_eq!(sweep.cells[0].bet_ramp_label(), "strategy");
+    }
+
+    #[test]
+    fn test_invalid_grid() {
+        let arguments = make_arguments(MILLION, 1);
+        let error = Sweep::new(&make_grid(json!({ "decks": ["eight-deck"] })), &arguments).err().unwrap();
+        assert_eq!(error.to_string(), "Invalid sweep grid: unknown decks eight-deck");
+        assert!(Sweep::new(&make_grid(json!({ "strategies": ["martingale"] })), &arguments).is_err());
+        assert!(Sweep::new(&make_grid(json!({ "penetrations": [1.5] })), &arguments).is_err());
+        assert!(Sweep::new(&make_grid(json!({ "hands": [10] })), &arguments).is_err());
+        assert!(serde_json::from_value::<Grid>(json!({ "deck": ["six-shoe"] })).is_err());
+        assert!(Sweep::load("/nonexistent/grid.json", &arguments).is_err());
+    }
+
+    #[test]
+    fn test_configure() {
+        let grid = make_grid(json!({ "penetrations": [0.5], "rules": [{ "surrender": true }], "betRamps": [{ "1": 6 }] }));
+        let sweep = Sweep::new(&grid, &make_arguments(MILLION, 4)).unwrap();
+        let configurations = make_configurations(&sweep);
+        assert_eq!(configurations[0].rules.penetration, 0.5);
+        assert!(configurations[0].rules.surrender);
+        assert!(configurations[0].rules.hit_soft_17);
+        assert_eq!(configurations[0].strategy.bet_ramp.get_bet(3), Some(6));
+        assert_eq!(configurations[0].parameters.number_of_threads, 4);
+        assert_eq!(configurations[0].strategy.number_of_cards, NUMBER_OF_CARDS_IN_DECK);
+
+        let grid = make_grid(json!({ "rules": [{ "spanish21": true }] }));
+        let sweep = Sweep::new(&grid, &make_arguments(MILLION, 4)).unwrap();
+        assert_eq!(make_configurations(&sweep)[0].strategy.number_of_cards, NUMBER_OF_CARDS_IN_SPANISH_DECK);
+
+        let grid = make_grid(json!({ "rules": [{ "surrender": "sometimes" }] }));
+        let sweep = Sweep::new(&grid, &make_arguments(MILLION, 4)).unwrap();
+        let table = Rules::from_json(RULES_JSON.clone()).unwrap();
+        assert!(sweep.configure(&sweep.cells[0], &table, &Strategy::new()).is_err());
+
+        let grid = make_grid(json!({ "rules": [{ "hitsoft17": false }] }));
+        let sweep = Sweep::new(&grid, &make_arguments(MILLION, 4)).unwrap();
+        assert!(matches!(sweep.configure(&sweep.cells[0], &table, &Strategy::new()), Err(StrikerError::InvalidGrid(message)) if message == "unknown rule hitsoft17"));
+    }
+
+    #[test]
+    fn test_csv_field() {
+        assert_eq!(csv_field("single-deck"), "single-deck");
+        assert_eq!(
+            csv_field("sideBets={\"insurance\":true,\"perfectPairs\":false}"),
+            "\"sideBets={\"\"insurance\"\":true,\"\"perfectPairs\"\":false}\""
+        );
+    }
+
+    #[test]
+    fn test_simulate_and_write() {
+        for interleaved in [false, true] {
+            let grid = make_grid(json!({ "penetrations": [0.6, 0.7, 0.8], "hands": [2000], "interleaved": interleaved }));
+            let sweep = Sweep::new(&grid, &make_arguments(MILLION, 2)).unwrap();
+            let reports = sweep.simulate(make_configurations(&sweep)).unwrap();
+            assert_eq!(reports.len(), 3);
+            assert!(reports.iter().all(|report| report.total_hands == 2000 && report.total_bet > 0));
+
+            let directory = std::env::temp_dir().join(format!("striker_sweep_{}_{}", std::process::id(), interleaved));
+            let directory = directory.to_string_lossy().to_string();
+            sweep.write(&reports, &directory).unwrap();
+            let csv = fs::read_to_string(Path::new(&directory).join(SWEEP_COMPARISON_FILE)).unwrap();
+            assert_eq!(csv.lines().count(), 4);
+            assert!(csv.lines().nth(2).unwrap().starts_with("2,single-deck,mimic,0.70,table,strategy,"));
+            let report: Value = serde_json::from_str(&fs::read_to_string(Path::new(&directory).join("003-single-deck-mimic.json")).unwrap()).unwrap();
+            assert_eq!(report["cell"]["penetration"], 0.8);
+
+            // The cells share a playbook, but a cell only merges with runs of itself
+            let cell = |index: usize| Path::new(&directory).join(sweep.cells[index].file_name(index)).to_string_lossy().to_string();
+            assert!(matches!(Comparison::load(&[cell(0), cell(1)]), Err(StrikerError::InvalidReport(_))));
+            let merged = Comparison::load(&[cell(2)]).unwrap();
+            assert_eq!(merged.merged[0].cell.as_ref().unwrap()["penetration"], 0.8);
+            fs::remove_dir_all(&directory).unwrap();
+        }
+    }
+}
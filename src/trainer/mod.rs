@@ -0,0 +1,2 @@
+// trainer module
+pub mod trainer;
@@ -0,0 +1,400 @@
+use crate::cards::{card::Card, hand::Hand, shoe::Shoe};
+use crate::constants::constants::{TRAINER_DRILL_CARDS, TRAINER_FLASH_MILLISECONDS, TRAINER_QUESTIONS, TRUE_COUNT_MULTIPLIER};
+use crate::errors::errors::StrikerError;
+use crate::game::game::{Action, chart_action};
+use crate::strategy::rules::Rules;
+use crate::strategy::strategy::Strategy;
+use rand::Rng;
+use serde_json::{Value, json};
+use std::fs;
+use std::io::{BufRead, Write};
+use std::thread;
+use std::time::{Duration, Instant};
+
+const DRILL_COUNT: &str = "count";
+const DRILL_TRUE_COUNT: &str = "true-count";
+const DRILL_STRATEGY: &str = "strategy";
+
+// Running count drills plus true count and chart quizzes, with each user's progress kept in a local JSON file
+pub struct Trainer {
+    rules: Rules,
+    strategy: Strategy,
+    number_of_decks: usize,
+    user: String,
+    progress_file: String,
+    progress: Value,
+    pub flash: Duration,
+}
+
+impl Trainer {
+    pub fn new(number_of_decks: usize, rules: &Rules, strategy: &Strategy, user: &str, progress_file: &str) -> Self {
+        Self {
+            rules: rules.clone(),
+            strategy: strategy.clone(),
+            number_of_decks,
+            user: user.to_string(),
+            progress_file: progress_file.to_string(),
+            progress: json!({}),
+            flash: Duration::from_millis(TRAINER_FLASH_MILLISECONDS),
+        }
+    }
+
+    pub fn run(&mut self, input: &mut impl BufRead, output: &mut impl Write) -> Result<(), StrikerError> {
+        // A missing or unreadable file starts the progress over
+        self.progress = fs::read_to_string(&self.progress_file)
+            .ok()
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .unwrap_or_else(|| json!({}));
+        if self.strategy.hard_stand.keys().is_empty() {
+            writeln!(output, "The trainer needs a chart strategy, use -B, -H or -W")?;
+            return Ok(());
+        }
+
+        while let Some(line) = Self::read_answer(input, output, "\n[c]ount drill, [t]rue count quiz, [s]trategy quiz, [q]uit")? {
+            let more = match line.as_str() {
+                "c" => self.count_drill(input, output)?,
+                "t" => self.true_count_quiz(input, output)?,
+                "s" => self.strategy_quiz(input, output)?,
+                _ => true,
+            };
+            if !more {
+                break;
+            }
+        }
+
+        self.print_progress(output)?;
+        fs::write(&self.progress_file, serde_json::to_string_pretty(&self.progress).unwrap_or_default()).map_err(|e| StrikerError::file("write", &self.progress_file, e))
+    }
+
+    // Flash cards one at a time, then ask for their running count
+    fn count_drill(&mut self, input: &mut impl BufRead, output: &mut impl Write) -> Result<bool, StrikerError> {
+        if !self.counting(output)? {
+            return Ok(true);
+        }
+        let mut shoe = self.new_shoe();
+        for _ in 0..TRAINER_QUESTIONS {
+            if shoe.should_shuffle() {
+                shoe.shuffle();
+            }
+            let mut seen_cards = [0; 13];
+            for _ in 0..TRAINER_DRILL_CARDS {
+                let card = shoe.draw_card().ok_or(StrikerError::OutOfCards)?;
+                seen_cards[card.rank.value()] += 1;
+                write!(output, "\r  {:<20}", card.to_string())?;
+                output.flush()?;
+                thread::sleep(self.flash);
+            }
+            write!(output, "\r{:<22}\r", "")?;
+
+            let running_count = self.strategy.get_running_count(&seen_cards);
+            let start = Instant::now();
+            let Some(answer) = Self::read_answer(input, output, "Running count")? else {
+                return Ok(false);
+            };
+            let milliseconds = start.elapsed().as_millis() as u64;
+            let correct = answer.parse::<isize>() == Ok(running_count);
+            self.add(DRILL_COUNT, "questions", 1);
+            self.add(DRILL_COUNT, "correct", correct as u64);
+            self.add(DRILL_COUNT, "milliseconds", milliseconds);
+            let verdict = if correct {
+                "Correct".to_string()
+            } else {
+                format!("Wrong, the running count is {}", running_count)
+            };
+            writeln!(output, "  {} ({:.1} seconds)", verdict, milliseconds as f64 / 1000.0)?;
+        }
+        Ok(true)
+    }
+
+    // Convert a running count with part of the shoe dealt, rounded down as the strategy does
+    fn true_count_quiz(&mut self, input: &mut impl BufRead, output: &mut impl Write) -> Result<bool, StrikerError> {
+        if !self.counting(output)? {
+            return Ok(true);
+        }
+        writeln!(output, "True count is the running count per {} unseen cards, rounded down", TRUE_COUNT_MULTIPLIER)?;
+        for _ in 0..TRAINER_QUESTIONS {
+            let seen_cards = self.deal_into_shoe(&mut self.new_shoe());
+            let running_count = self.strategy.get_running_count(&seen_cards);
+            let true_count = self.strategy.get_true_count(&seen_cards, running_count);
+            let unseen = self.strategy.number_of_cards - seen_cards.iter().sum::<usize>();
+            let decks = unseen as f64 / Strategy::cards_in_shoe(&self.rules, 1) as f64;
+            let prompt = format!("Running count {:+}, {} cards unseen ({:.1} decks), true count", running_count, unseen, decks);
+            let Some(answer) = Self::read_answer(input, output, &prompt)? else {
+                return Ok(false);
+            };
+            let correct = answer.parse::<isize>() == Ok(true_count);
+            self.add(DRILL_TRUE_COUNT, "questions", 1);
+            self.add(DRILL_TRUE_COUNT, "correct", correct as u64);
+            writeln!(
+                output,
+                "  {}",
+                if correct {
+                    "Correct".to_string()
+                } else {
+                    format!("Wrong, the true count is {}", true_count)
+                }
+            )?;
+        }
+        Ok(true)
+    }
+
+    // First decisions part way through a shoe, answered from the charts including their count thresholds
+    fn strategy_quiz(&mut self, input: &mut impl BufRead, output: &mut impl Write) -> Result<bool, StrikerError> {
+        let mut questions = 0;
+        while questions < TRAINER_QUESTIONS {
+            let mut shoe = self.new_shoe();
+            let mut seen_cards = self.deal_into_shoe(&mut shoe);
+            let mut hand = Hand::new();
+            let mut draw = |seen_cards: &mut [usize; 13]| -> Result<Card, StrikerError> {
+                let card = shoe.draw_card().ok_or(StrikerError::OutOfCards)?;
+                seen_cards[card.rank.value()] += 1;
+                Ok(card)
+            };
+            hand.add_card(draw(&mut seen_cards)?);
+            hand.add_card(draw(&mut seen_cards)?);
+            let up = draw(&mut seen_cards)?;
+            if hand.is_blackjack() {
+                continue;
+            }
+            questions += 1;
+
+            let mut options = vec![Action::Hit, Action::Stand, Action::Double];
+            if hand.is_pair() {
+                options.push(Action::Split);
+            }
+            if self.rules.surrender {
+                options.push(Action::Surrender);
+            }
+            let answer = chart_action(&self.strategy, &seen_cards, &hand, &up, &options)?;
+            let basic = chart_action(&self.strategy, &[0; 13], &hand, &up, &options)?;
+            let running_count = self.strategy.get_running_count(&seen_cards);
+            let true_count = self.strategy.get_true_count(&seen_cards, running_count);
+
+            let cards: Vec<String> = hand.cards.iter().map(|card| card.to_string()).collect();
+            writeln!(output, "True count {:+}, dealer shows {}, you have {} ({})", true_count, up, cards.join(", "), hand.total)?;
+            let prompt = options.iter().map(|action| action.prompt()).collect::<Vec<_>>().join(" ");
+            let action = loop {
+                let Some(line) = Self::read_answer(input, output, &prompt)? else {
+                    return Ok(false);
+                };
+                if let Some(action) = Action::from_input(&line).filter(|action| options.contains(action)) {
+                    break action;
+                }
+            };
+
+            let correct = action == answer;
+            let deviation = answer != basic;
+            self.add(DRILL_STRATEGY, "questions", 1);
+            self.add(DRILL_STRATEGY, "correct", correct as u64);
+            self.add(DRILL_STRATEGY, "deviations", deviation as u64);
+            self.add(DRILL_STRATEGY, "deviations-correct", (deviation && correct) as u64);
+            let reason = if deviation {
+                format!(", a count deviation from {}", basic.name())
+            } else {
+                String::new()
+            };
+            let verdict = if correct { "Correct" } else { "Wrong" };
+            writeln!(output, "  {}, the chart says {}{}", verdict, answer.name(), reason)?;
+        }
+        Ok(true)
+    }
+
+    fn counting(&self, output: &mut impl Write) -> Result<bool, StrikerError> {
+        let counting = self.strategy.counts.iter().any(|count| *count != 0);
+        if !counting {
+            writeln!(output, "Count drills need a counting strategy, use -H or -W")?;
+        }
+        Ok(counting)
+    }
+
+    fn new_shoe(&self) -> Shoe {
+        let mut shoe = if self.rules.spanish_21 {
+            Shoe::new_spanish_21(self.number_of_decks, self.rules.penetration)
+        } else {
+            Shoe::new(self.number_of_decks, self.rules.penetration)
+        };
+        shoe.shuffle();
+        shoe
+    }
+
+    // Deal a random part of the shoe, up to the cut card, and return the cards seen
+    fn deal_into_shoe(&self, shoe: &mut Shoe) -> [usize; 13] {
+        let mut seen_cards = [0; 13];
+        let dealt = rand::rng().random_range(0..shoe.get_cut_card().max(1));
+        for _ in 0..dealt {
+            if let Some(card) = shoe.draw_card() {
+                seen_cards[card.rank.value()] += 1;
+            }
+        }
+        seen_cards
+    }
+
+    // A user or drill that is not an object, e.g. in a hand edited file, starts over instead of failing the index
+    fn add(&mut self, drill: &str, field: &str, amount: u64) {
+        let mut entry = &mut self.progress;
+        for key in [self.user.as_str(), drill] {
+            if !entry.is_object() {
+                *entry = json!({});
+            }
+            entry = &mut entry[key];
+        }
+        if !entry.is_object() {
+            *entry = json!({});
+        }
+        let entry = &mut entry[field];
+        *entry = json!(entry.as_u64().unwrap_or(0) + amount);
+    }
+
+    fn print_progress(&self, output: &mut impl Write) -> Result<(), StrikerError> {
+        let progress = &self.progress[&self.user];
+        let stat = |drill: &str, field: &str| progress[drill][field].as_u64().unwrap_or(0);
+        let percent = |correct: u64, questions: u64| if questions > 0 { 100.0 * correct as f64 / questions as f64 } else { 0.0 };
+
+        writeln!(output, "\nProgress for {}", self.user)?;
+        let (questions, correct) = (stat(DRILL_COUNT, "questions"), stat(DRILL_COUNT, "correct"));
+        let seconds = if questions > 0 {
+            stat(DRILL_COUNT, "milliseconds") as f64 / 1000.0 / questions as f64
+        } else {
+            0.0
+        };
+        writeln!(
+            output,
+            "  Count drills     : {:>5} {:>6.1} % correct, {:.1} seconds per answer",
+            questions,
+            percent(correct, questions),
+            seconds
+        )?;
+        let (questions, correct) = (stat(DRILL_TRUE_COUNT, "questions"), stat(DRILL_TRUE_COUNT, "correct"));
+        writeln!(output, "  True count quiz  : {:>5} {:>6.1} % correct", questions, percent(correct, questions))?;
+        let (questions, correct) = (stat(DRILL_STRATEGY, "questions"), stat(DRILL_STRATEGY, "correct"));
+        let (deviations, deviations_correct) = (stat(DRILL_STRATEGY, "deviations"), stat(DRILL_STRATEGY, "deviations-correct"));
+        writeln!(
+            output,
+            "  Strategy quiz    : {:>5} {:>6.1} % correct, {:.1} % of {} deviations",
+            questions,
+            percent(correct, questions),
+            percent(deviations_correct, deviations),
+            deviations
+        )?;
+        Ok(())
+    }
+
+    // None once the player quits or the input ends
+    fn read_answer(input: &mut impl BufRead, output: &mut impl Write, prompt: &str) -> Result<Option<String>, StrikerError> {
+        write!(output, "{}: ", prompt)?;
+        output.flush()?;
+        let mut line = String::new();
+        if input.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        let line = line.trim().to_lowercase();
+        Ok(if line == "q" || line == "quit" { None } else { Some(line) })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::resources::single_deck_basic::SINGLE_DECK_BASIC_JSON;
+    use std::io::Cursor;
+
+    fn make_trainer(progress_file: &str) -> Trainer {
+        let mut rules = Rules::new();
+        rules.penetration = 0.75;
+        let mut strategy = Strategy::new();
+        strategy.fetch_table(SINGLE_DECK_BASIC_JSON.clone());
+        strategy.counts = vec![0, 0, 1, 1, 1, 1, 1, 0, 0, 0, -1, -1];
+        let mut trainer = Trainer::new(1, &rules, &strategy, "tester", progress_file);
+        trainer.flash = Duration::ZERO;
+        trainer
+    }
+
+    fn progress_file(name: &str) -> String {
+        std::env::temp_dir()
+            .join(format!("striker_{}_{}.json", name, std::process::id()))
+            .to_string_lossy()
+            .to_string()
+    }
+
+    #[test]
+    fn test_progress_saved_and_accumulated() {
+        let path = progress_file("progress");
+        let _ = fs::remove_file(&path);
+        for _ in 0..2 {
+            let mut trainer = make_trainer(&path);
+            let script = format!(
+                "t\n{}c\n{}s\n{}q\n",
+                "0\n".repeat(TRAINER_QUESTIONS),
+                "0\n".repeat(TRAINER_QUESTIONS),
+                "s\n".repeat(TRAINER_QUESTIONS)
+            );
+            let mut output = Vec::new();
+            trainer.run(&mut Cursor::new(script), &mut output).unwrap();
+            assert!(String::from_utf8(output).unwrap().contains("Progress for tester"));
+        }
+        let progress: Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        let questions = (TRAINER_QUESTIONS * 2) as u64;
+        for drill in [DRILL_COUNT, DRILL_TRUE_COUNT, DRILL_STRATEGY] {
+            assert_eq!(progress["tester"][drill]["questions"], questions, "{}", drill);
+            assert!(progress["tester"][drill]["correct"].as_u64().unwrap() <= questions);
+        }
+    }
+
+    #[test]
+    fn test_count_drill_scoring() {
+        let mut trainer = make_trainer(&progress_file("scoring"));
+        let mut output = Vec::new();
+        trainer.count_drill(&mut Cursor::new("1000\n".repeat(TRAINER_QUESTIONS)), &mut output).unwrap();
+        assert_eq!(trainer.progress["tester"][DRILL_COUNT]["correct"], 0);
+        assert!(String::from_utf8(output).unwrap().contains("Wrong, the running count is"));
+    }
+
+    #[test]
+    fn test_true_count_quiz_spanish_decks() {
+        let mut trainer = make_trainer(&progress_file("spanish"));
+        trainer.rules.spanish_21 = true;
+        trainer.strategy.number_of_cards = Strategy::cards_in_shoe(&trainer.rules, 1);
+        let mut output = Vec::new();
+        trainer.true_count_quiz(&mut Cursor::new("0\n".repeat(TRAINER_QUESTIONS)), &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert_eq!(output.matches("cards unseen").count(), TRAINER_QUESTIONS);
+        for prompt in output.split("Running count ").skip(1) {
+            let unseen: f64 = prompt.split(", ").nth(1).and_then(|part| part.split(' ').next()).unwrap().parse().unwrap();
+            assert!(prompt.contains(&format!("({:.1} decks)", unseen / 48.0)), "{}", prompt);
+        }
+    }
+
+    #[test]
+    fn test_add_rebuilds_progress() {
+        let mut trainer = make_trainer(&progress_file("rebuild"));
+        for progress in [
+            json!([1, 2]),
+            json!({ "tester": 3 }),
+            json!({ "tester": { "count": "many" }, "other": { "count": { "questions": 4 } } }),
+        ] {
+            trainer.progress = progress;
+            trainer.add(DRILL_COUNT, "questions", 1);
+            trainer.add(DRILL_COUNT, "questions", 1);
+            assert_eq!(trainer.progress["tester"][DRILL_COUNT]["questions"], 2);
+        }
+        assert_eq!(trainer.progress["other"][DRILL_COUNT]["questions"], 4);
+    }
+
+    #[test]
+    fn test_needs_counting_strategy() {
+        let mut trainer = make_trainer(&progress_file("basic"));
+        trainer.strategy.counts = vec![0; 12];
+        let mut output = Vec::new();
+        assert!(trainer.count_drill(&mut Cursor::new(""), &mut output).unwrap());
+        assert!(String::from_utf8(output).unwrap().contains("need a counting strategy"));
+
+        let path = progress_file("mimic");
+        let mut trainer = Trainer::new(1, &Rules::new(), &Strategy::new(), "tester", &path);
+        let mut output = Vec::new();
+        trainer.run(&mut Cursor::new("c\n"), &mut output).unwrap();
+        assert!(String::from_utf8(output).unwrap().contains("needs a chart strategy"));
+        assert!(fs::metadata(&path).is_err());
+    }
+}
@@ -1,2 +1,3 @@
 // utilities module
+pub mod topology;
 pub mod utilities;
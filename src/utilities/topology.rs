@@ -0,0 +1,59 @@
+use serde::{Deserialize, Serialize};
+use std::thread;
+
+// The processors this run may use, detected rather than assumed so the default thread count fits the machine
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Topology {
+    pub available_cpus: usize,
+    pub physical_cores: usize,
+}
+
+impl Default for Topology {
+    fn default() -> Self {
+        Self::detect()
+    }
+}
+
+impl Topology {
+    // Available parallelism honors the affinity mask and cgroup CPU quotas, so a container gets its share and not the host's
+    pub fn detect() -> Self {
+        Self {
+            available_cpus: thread::available_parallelism().map(|cpus| cpus.get()).unwrap_or(1),
+            physical_cores: num_cpus::get_physical().max(1),
+        }
+    }
+
+    // Pin the calling worker thread to one of the CPUs it may run on, in turn by index, false when the platform does not allow it
+    pub fn pin_thread(index: usize) -> bool {
+        match core_affinity::get_core_ids() {
+            Some(core_ids) if !core_ids.is_empty() => core_affinity::set_for_current(core_ids[index % core_ids.len()]),
+            _ => false,
+        }
+    }
+
+    pub fn to_json_object(&self) -> serde_json::Value {
+        serde_json::json!({
+            "available_cpus": self.available_cpus,
+            "physical_cores": self.physical_cores,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect() {
+        let topology = Topology::detect();
+        assert!(topology.available_cpus >= 1);
+        assert!(topology.physical_cores >= 1);
+        assert_eq!(topology.to_json_object()["available_cpus"], topology.available_cpus);
+    }
+
+    #[test]
+    fn test_pin_thread() {
+        let pinned = thread::spawn(|| Topology::pin_thread(usize::MAX)).join().unwrap();
+        assert_eq!(pinned, core_affinity::get_core_ids().is_some_and(|core_ids| !core_ids.is_empty()));
+    }
+}